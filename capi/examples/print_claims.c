@@ -0,0 +1,49 @@
+/*
+ * Prints the subject and the issuer of the token given as argument.
+ *
+ *   cargo build --release -p jwtinfo-capi
+ *   cc capi/examples/print_claims.c -Icapi/include -Ltarget/release -ljwtinfo_capi -o print_claims
+ *   LD_LIBRARY_PATH=target/release ./print_claims <token>
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "jwtinfo.h"
+
+static void print_claim(const jwtinfo_token *token, const char *pointer) {
+    char *value = NULL;
+    jwtinfo_error error = jwtinfo_token_get_string(token, pointer, &value);
+    if (error == JWTINFO_OK) {
+        printf("%s: %s\n", pointer, value);
+        jwtinfo_string_free(value);
+    } else {
+        printf("%s: <%s>\n", pointer, jwtinfo_error_name(error));
+    }
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <token>\n", argv[0]);
+        return 2;
+    }
+    if (jwtinfo_abi_version() != JWTINFO_ABI_VERSION) {
+        fprintf(stderr, "incompatible jwtinfo library %s\n", jwtinfo_version());
+        return 2;
+    }
+
+    jwtinfo_token *token = NULL;
+    char *message = NULL;
+    jwtinfo_error error = jwtinfo_parse(argv[1], strlen(argv[1]), &token, &message);
+    if (error != JWTINFO_OK) {
+        fprintf(stderr, "%s: %s\n", jwtinfo_error_name(error), message);
+        jwtinfo_string_free(message);
+        return 1;
+    }
+
+    print_claim(token, "/claims/sub");
+    print_claim(token, "/claims/iss");
+    print_claim(token, "/header/alg");
+    jwtinfo_token_free(token);
+    return 0;
+}
@@ -0,0 +1,97 @@
+/*
+ * jwtinfo: decode JWTs (Json Web Tokens) and read their claims.
+ *
+ * Memory ownership:
+ * - the library never takes ownership of the arguments
+ * - a jwtinfo_token returned by jwtinfo_parse must be released with
+ *   jwtinfo_token_free
+ * - every char * returned through an out parameter must be released with
+ *   jwtinfo_string_free
+ * - the strings returned by jwtinfo_error_name and jwtinfo_version are static
+ *   and must not be freed
+ *
+ * Out parameters are only written when a function returns JWTINFO_OK (except
+ * the error message of jwtinfo_parse).
+ */
+
+#ifndef JWTINFO_H
+#define JWTINFO_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The version of the ABI, incremented on every incompatible change */
+#define JWTINFO_ABI_VERSION 1
+
+/* The result of a function. The codes from 1 to 4 mirror the variants of
+ * jwt::JWTParsePartError. */
+typedef enum jwtinfo_error {
+    JWTINFO_OK = 0,
+    /* the header cannot be parsed */
+    JWTINFO_ERROR_HEADER = 1,
+    /* the body cannot be parsed */
+    JWTINFO_ERROR_BODY = 2,
+    /* the signature cannot be parsed */
+    JWTINFO_ERROR_SIGNATURE = 3,
+    /* the token has a part after the signature */
+    JWTINFO_ERROR_UNEXPECTED_PART = 4,
+    /* a required argument is NULL, or a string is not valid UTF-8 */
+    JWTINFO_ERROR_INVALID_ARGUMENT = 5,
+    /* the JSON Pointer doesn't match any value of the token */
+    JWTINFO_ERROR_NOT_FOUND = 6
+} jwtinfo_error;
+
+/* A decoded token (opaque) */
+typedef struct jwtinfo_token jwtinfo_token;
+
+/*
+ * Parses a token of len bytes (it doesn't need to be NUL terminated).
+ *
+ * On success, *out receives a token to release with jwtinfo_token_free. On
+ * failure, if error_message is not NULL, it receives a description of the
+ * error, to release with jwtinfo_string_free.
+ */
+jwtinfo_error jwtinfo_parse(const char *token, size_t len, jwtinfo_token **out,
+                            char **error_message);
+
+/* Releases a token returned by jwtinfo_parse (NULL is ignored) */
+void jwtinfo_token_free(jwtinfo_token *token);
+
+/*
+ * Finds a value of the token with a JSON Pointer (RFC 6901) into
+ * {"header": ..., "claims": ...}, e.g. "/claims/sub", "/header/kid" or
+ * "/claims/roles/0", and writes it to *out as JSON (an empty pointer returns
+ * the whole token). *out must be released with jwtinfo_string_free.
+ */
+jwtinfo_error jwtinfo_token_get_json(const jwtinfo_token *token, const char *pointer,
+                                     char **out);
+
+/*
+ * Same as jwtinfo_token_get_json, but string values are written without quotes
+ * and escapes (other values are still written as JSON).
+ */
+jwtinfo_error jwtinfo_token_get_string(const jwtinfo_token *token, const char *pointer,
+                                       char **out);
+
+/* Releases a string returned by the library (NULL is ignored) */
+void jwtinfo_string_free(char *s);
+
+/* Returns the name of an error code (e.g. "JWTINFO_ERROR_HEADER"), or
+ * "JWTINFO_ERROR_UNKNOWN". The string is static. */
+const char *jwtinfo_error_name(int error);
+
+/* Returns the version of the library (e.g. "0.6.1"). The string is static. */
+const char *jwtinfo_version(void);
+
+/* Returns the ABI version of the library, to compare with JWTINFO_ABI_VERSION */
+uint32_t jwtinfo_abi_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* JWTINFO_H */
@@ -0,0 +1,264 @@
+//! # jwtinfo-capi
+//!
+//! A stable C ABI of the `jwt` module of `jwtinfo`, declared in
+//! `include/jwtinfo.h`, to decode tokens and read their claims from C and C++.
+//!
+//! ## Memory ownership
+//!
+//! - the library never takes ownership of the arguments: tokens and pointers
+//!   are only read during the call
+//! - a `jwtinfo_token` returned by `jwtinfo_parse` is owned by the caller and
+//!   must be released with `jwtinfo_token_free`
+//! - every `char *` returned through an out parameter is owned by the caller
+//!   and must be released with `jwtinfo_string_free`
+//! - the strings returned by `jwtinfo_error_name` and `jwtinfo_version` are
+//!   static and must not be freed
+//!
+//! Out parameters are only written when the function returns `JWTINFO_OK`
+//! (except the error message of `jwtinfo_parse`).
+
+use jwtinfo::jwt;
+use serde_json::Value;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::slice;
+use std::str;
+
+/// The version of the ABI, incremented on every incompatible change
+pub const JWTINFO_ABI_VERSION: u32 = 1;
+
+/// The result of a function: `JWTINFO_OK` or an error
+///
+/// The codes from 1 to 4 mirror the variants of `jwt::JWTParsePartError`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtinfoError {
+    /// The function succeeded
+    Ok = 0,
+    /// The header cannot be parsed (`JWTParsePartError::Header`)
+    Header = 1,
+    /// The body cannot be parsed (`JWTParsePartError::Body`)
+    Body = 2,
+    /// The signature cannot be parsed (`JWTParsePartError::Signature`)
+    Signature = 3,
+    /// The token has a part after the signature (`JWTParsePartError::UnexpectedPart`)
+    UnexpectedPart = 4,
+    /// A required argument is `NULL`, or a string is not valid UTF-8
+    InvalidArgument = 5,
+    /// The JSON Pointer doesn't match any value of the token
+    NotFound = 6,
+}
+
+impl From<&jwt::JWTParsePartError> for JwtinfoError {
+    fn from(error: &jwt::JWTParsePartError) -> Self {
+        match error {
+            jwt::JWTParsePartError::Header(_) => JwtinfoError::Header,
+            jwt::JWTParsePartError::Body(_) => JwtinfoError::Body,
+            jwt::JWTParsePartError::Signature(_) => JwtinfoError::Signature,
+            jwt::JWTParsePartError::UnexpectedPart() => JwtinfoError::UnexpectedPart,
+        }
+    }
+}
+
+/// A decoded token (opaque for C)
+pub struct JwtinfoToken {
+    /// the header and the claims, as `{"header": ..., "claims": ...}`
+    json: Value,
+}
+
+/// Converts a Rust string to a C string owned by the caller
+///
+/// JSON strings can contain `\u0000`, which cannot be represented in C: the
+/// value is truncated at the first NUL byte.
+#[doc(hidden)]
+fn into_c_string(s: String) -> *mut c_char {
+    let mut bytes = s.into_bytes();
+    if let Some(end) = bytes.iter().position(|&b| b == 0) {
+        bytes.truncate(end);
+    }
+    CString::new(bytes).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Reads a NUL terminated UTF-8 string argument
+#[doc(hidden)]
+unsafe fn read_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Parses a token of `len` bytes (it doesn't need to be NUL terminated)
+///
+/// On success, `*out` receives a token that must be released with
+/// `jwtinfo_token_free`. On failure, if `error_message` is not `NULL`, it
+/// receives a description of the error, to release with `jwtinfo_string_free`.
+///
+/// # Safety
+///
+/// `token` must point to at least `len` readable bytes, `out` must be a valid
+/// pointer and `error_message` must be `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwtinfo_parse(
+    token: *const c_char,
+    len: usize,
+    out: *mut *mut JwtinfoToken,
+    error_message: *mut *mut c_char,
+) -> JwtinfoError {
+    if token.is_null() || out.is_null() {
+        return JwtinfoError::InvalidArgument;
+    }
+    let bytes = slice::from_raw_parts(token as *const u8, len);
+    let Ok(token) = str::from_utf8(bytes) else {
+        return JwtinfoError::InvalidArgument;
+    };
+    match jwt::parse(token.trim()) {
+        Ok(token) => {
+            let token = JwtinfoToken {
+                json: token.to_json(),
+            };
+            *out = Box::into_raw(Box::new(token));
+            JwtinfoError::Ok
+        }
+        Err(e) => {
+            if !error_message.is_null() {
+                *error_message = into_c_string(e.to_string());
+            }
+            JwtinfoError::from(&e)
+        }
+    }
+}
+
+/// Releases a token returned by `jwtinfo_parse` (`NULL` is ignored)
+///
+/// # Safety
+///
+/// `token` must be `NULL` or a token returned by `jwtinfo_parse` that was not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn jwtinfo_token_free(token: *mut JwtinfoToken) {
+    if !token.is_null() {
+        drop(Box::from_raw(token));
+    }
+}
+
+/// Looks up a value of the token with a JSON Pointer (RFC 6901) into
+/// `{"header": ..., "claims": ...}`, e.g. `/claims/sub` or `/header/kid`
+#[doc(hidden)]
+unsafe fn lookup<'a>(
+    token: *const JwtinfoToken,
+    pointer: *const c_char,
+) -> Result<&'a Value, JwtinfoError> {
+    if token.is_null() {
+        return Err(JwtinfoError::InvalidArgument);
+    }
+    let pointer = read_str(pointer).ok_or(JwtinfoError::InvalidArgument)?;
+    (*token).json.pointer(pointer).ok_or(JwtinfoError::NotFound)
+}
+
+/// Writes the value found with `lookup` to `out`, formatted with `format`
+#[doc(hidden)]
+unsafe fn get_with(
+    token: *const JwtinfoToken,
+    pointer: *const c_char,
+    out: *mut *mut c_char,
+    format: fn(&Value) -> String,
+) -> JwtinfoError {
+    if out.is_null() {
+        return JwtinfoError::InvalidArgument;
+    }
+    match lookup(token, pointer) {
+        Ok(value) => {
+            *out = into_c_string(format(value));
+            JwtinfoError::Ok
+        }
+        Err(e) => e,
+    }
+}
+
+/// Finds a value of the token with a JSON Pointer into
+/// `{"header": ..., "claims": ...}` (e.g. `/claims/sub`, `/header/kid` or
+/// `/claims/roles/0`) and writes it to `*out` as JSON
+///
+/// `*out` must be released with `jwtinfo_string_free`. An empty pointer
+/// returns the whole token.
+///
+/// # Safety
+///
+/// `token` must be a token returned by `jwtinfo_parse`, `pointer` a NUL
+/// terminated string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwtinfo_token_get_json(
+    token: *const JwtinfoToken,
+    pointer: *const c_char,
+    out: *mut *mut c_char,
+) -> JwtinfoError {
+    get_with(token, pointer, out, |value| value.to_string())
+}
+
+/// Same as `jwtinfo_token_get_json`, but string values are written without
+/// quotes and escapes (other values are still written as JSON)
+///
+/// # Safety
+///
+/// See `jwtinfo_token_get_json`.
+#[no_mangle]
+pub unsafe extern "C" fn jwtinfo_token_get_string(
+    token: *const JwtinfoToken,
+    pointer: *const c_char,
+    out: *mut *mut c_char,
+) -> JwtinfoError {
+    get_with(token, pointer, out, |value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Releases a string returned by the library (`NULL` is ignored)
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a string returned by the library that was not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn jwtinfo_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Returns the name of an error code (e.g. `"JWTINFO_ERROR_HEADER"`), as a
+/// static string
+///
+/// The code is an `int`, so that any value received from C is valid: unknown
+/// codes return `"JWTINFO_ERROR_UNKNOWN"`.
+#[no_mangle]
+pub extern "C" fn jwtinfo_error_name(error: c_int) -> *const c_char {
+    let name: &'static [u8] = match error {
+        0 => b"JWTINFO_OK\0",
+        1 => b"JWTINFO_ERROR_HEADER\0",
+        2 => b"JWTINFO_ERROR_BODY\0",
+        3 => b"JWTINFO_ERROR_SIGNATURE\0",
+        4 => b"JWTINFO_ERROR_UNEXPECTED_PART\0",
+        5 => b"JWTINFO_ERROR_INVALID_ARGUMENT\0",
+        6 => b"JWTINFO_ERROR_NOT_FOUND\0",
+        _ => b"JWTINFO_ERROR_UNKNOWN\0",
+    };
+    name.as_ptr() as *const c_char
+}
+
+/// Returns the version of the library (e.g. `"0.6.1"`), as a static string
+#[no_mangle]
+pub extern "C" fn jwtinfo_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Returns the version of the ABI (`JWTINFO_ABI_VERSION`)
+#[no_mangle]
+pub extern "C" fn jwtinfo_abi_version() -> u32 {
+    JWTINFO_ABI_VERSION
+}
+
+#[cfg(test)]
+mod test;
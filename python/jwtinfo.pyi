@@ -0,0 +1,32 @@
+from typing import Any, Dict, List, Optional, Union
+
+__version__: str
+
+class JWTParsePartError(ValueError):
+    """Raised when a token cannot be parsed (the base class of the other errors)"""
+
+    # "missing_section", "invalid_utf8", "invalid_base64" or "invalid_json"
+    # (None for UnexpectedPartError)
+    reason: Optional[str]
+
+class InvalidHeaderError(JWTParsePartError): ...
+class InvalidBodyError(JWTParsePartError): ...
+class InvalidSignatureError(JWTParsePartError): ...
+class UnexpectedPartError(JWTParsePartError): ...
+
+class Token:
+    # the body of an encrypted token, or of a `cty: JWT` token, is a string
+    header: Dict[str, Any]
+    body: Union[Dict[str, Any], str]
+    signature: bytes
+    nested: List["NestedToken"]
+    def __init__(self, token: str) -> None: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class NestedToken:
+    path: str
+    token: Token
+
+def parse(token: str) -> Token: ...
+def parse_recursive(token: str, max_depth: int = 5) -> Token: ...
+def looks_like_token(token: str) -> bool: ...
@@ -0,0 +1,129 @@
+//! # Batch
+//!
+//! `batch` spreads the processing of many inputs (e.g. the lines of a token
+//! dump) across several threads, while keeping the results in input order.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::batch;
+//!
+//! let mut lengths = Vec::new();
+//! batch::map_ordered(
+//!     vec!["a.b.c", "abc.def.ghi"].into_iter(),
+//!     4,
+//!     |token| token.len(),
+//!     |length| -> Result<(), ()> {
+//!         lengths.push(length);
+//!         Ok(())
+//!     },
+//! )
+//! .unwrap();
+//! assert_eq!(lengths, vec![5, 11]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+/// The number of inputs sent to a worker at once
+///
+/// Processing a token takes a few microseconds, so inputs are grouped to keep
+/// the cost of the channels negligible.
+pub const CHUNK_SIZE: usize = 256;
+
+/// The number of chunks per worker that can be in flight (read but not yet
+/// consumed), which bounds the memory used when a chunk is slow to process
+#[doc(hidden)]
+const CHUNKS_PER_WORKER: usize = 4;
+
+/// Returns the default number of workers: the available parallelism, or 1 if unknown
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Applies `f` to every input on `jobs` threads and passes the results to
+/// `consume`, in the same order as the inputs
+///
+/// Inputs are read lazily, so `inputs` can be a stream of any length. With
+/// `jobs` lower than 2 everything runs on the calling thread. `consume` always
+/// runs on the calling thread.
+///
+/// # Errors
+///
+/// This function stops reading the inputs and returns the first error of `consume`
+pub fn map_ordered<I, T, R, E, F, C>(inputs: I, jobs: usize, f: F, mut consume: C) -> Result<(), E>
+where
+    I: Iterator<Item = T> + Send,
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    C: FnMut(R) -> Result<(), E>,
+{
+    if jobs < 2 {
+        return inputs.map(f).try_for_each(consume);
+    }
+
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel::<(usize, Vec<T>)>(jobs);
+    let chunk_rx = Mutex::new(chunk_rx);
+    thread::scope(|scope| {
+        let (result_tx, result_rx) = mpsc::channel::<(usize, Vec<R>)>();
+        // every chunk in flight holds a permit, released once it is consumed
+        let (permit_tx, permit_rx) = mpsc::sync_channel::<()>(jobs * CHUNKS_PER_WORKER);
+
+        scope.spawn(move || {
+            let mut inputs = inputs.peekable();
+            let mut index = 0;
+            while inputs.peek().is_some() {
+                if permit_tx.send(()).is_err() {
+                    // the consumer stopped
+                    return;
+                }
+                let chunk = inputs.by_ref().take(CHUNK_SIZE).collect();
+                if chunk_tx.send((index, chunk)).is_err() {
+                    return;
+                }
+                index += 1;
+            }
+        });
+
+        for _ in 0..jobs {
+            let result_tx = result_tx.clone();
+            let (chunk_rx, f) = (&chunk_rx, &f);
+            scope.spawn(move || {
+                // once the consumer stops, the remaining chunks are drained
+                // without processing them, so that the reader never blocks
+                let mut stopped = false;
+                loop {
+                    let received = chunk_rx.lock().unwrap().recv();
+                    let Ok((index, chunk)) = received else {
+                        return;
+                    };
+                    if !stopped {
+                        let results = chunk.into_iter().map(f).collect();
+                        stopped = result_tx.send((index, results)).is_err();
+                    }
+                }
+            });
+        }
+        drop(result_tx);
+
+        // chunks can complete out of order: keep them until their turn comes
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, results) in result_rx {
+            pending.insert(index, results);
+            while let Some(results) = pending.remove(&next) {
+                results.into_iter().try_for_each(&mut consume)?;
+                // releases the permit taken by the reader for this chunk
+                let _ = permit_rx.recv();
+                next += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,53 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn assert_map_ordered_keeps_the_input_order() {
+    for jobs in [1, 2, 8] {
+        let mut results = Vec::new();
+        map_ordered(
+            0..(CHUNK_SIZE * 10 + 7),
+            jobs,
+            |n| {
+                // make the early chunks slower, so that they complete last
+                if n % CHUNK_SIZE == 0 && n < CHUNK_SIZE * 3 {
+                    thread::sleep(std::time::Duration::from_millis(20));
+                }
+                n * 2
+            },
+            |n| -> Result<(), ()> {
+                results.push(n);
+                Ok(())
+            },
+        )
+        .unwrap();
+        let expected: Vec<usize> = (0..(CHUNK_SIZE * 10 + 7)).map(|n| n * 2).collect();
+        assert_eq!(results, expected, "jobs = {}", jobs);
+    }
+}
+
+#[test]
+fn assert_map_ordered_empty_input() {
+    let result = map_ordered(std::iter::empty::<u8>(), 4, |n| n, |_| Err("consumed"));
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn assert_map_ordered_stops_at_the_first_error() {
+    let mut consumed = 0;
+    let result = map_ordered(
+        0..,
+        4,
+        |n: usize| n,
+        |n| {
+            consumed += 1;
+            if n == 1000 {
+                Err(n)
+            } else {
+                Ok(())
+            }
+        },
+    );
+    assert_eq!(result, Err(1000));
+    assert_eq!(consumed, 1001);
+}
@@ -1,14 +1,1311 @@
-use clap::{Arg, ArgAction, Command};
-use jwtinfo::jwt;
-use serde_json::to_string_pretty;
-use std::io::{self, Read};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use jwtinfo::{
+    batch, color, cwt, expiry, explain, har, jwt, profile, provider, redact, sdjwt, template,
+    timestamp, validation, watch,
+};
+#[cfg(feature = "crypto")]
+use jwtinfo::{dpop, jwk, paseto, x5c};
+#[cfg(feature = "server")]
+use jwtinfo::{gateway, issuer, server};
+#[cfg(feature = "crypto")]
+use rand_core::{OsRng, RngCore};
+use serde_json::{to_string_pretty, Value};
+use std::convert::TryFrom;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+#[cfg(feature = "server")]
+use std::net::{IpAddr, SocketAddr};
 use std::process;
+use std::str;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
+/// Parses a duration argument (e.g. `90s`, `15m` or `1h30m`)
 #[doc(hidden)]
+fn parse_duration_arg(arg: &str) -> Result<u64, String> {
+    timestamp::parse_duration(arg)
+        .ok_or_else(|| "expected a duration such as 90s, 15m, 1h30m or 7d".to_string())
+}
+
+/// Parses a time argument: a number of seconds since the Unix epoch or an ISO 8601 date
+#[doc(hidden)]
+fn parse_time_arg(arg: &str) -> Result<u64, String> {
+    arg.parse()
+        .ok()
+        .or_else(|| {
+            let millis = timestamp::parse_iso8601_millis(arg)?;
+            u64::try_from(millis.div_euclid(1000)).ok()
+        })
+        .ok_or_else(|| "expected a NumericDate or an ISO 8601 date".to_string())
+}
+
+/// Prints an error and terminates the process with a non-zero exit code
+#[doc(hidden)]
+fn fail<E: Display>(error: E) -> ! {
+    eprintln!("Error: {}", error);
+    process::exit(1);
+}
+
+/// Reads the raw value of a token argument (`-` reads it from stdin)
+#[doc(hidden)]
+fn read_input(arg: &str) -> io::Result<Vec<u8>> {
+    if arg == "-" {
+        let mut raw = Vec::new();
+        io::stdin().read_to_end(&mut raw)?;
+        Ok(raw)
+    } else {
+        Ok(arg.as_bytes().to_vec())
+    }
+}
+
+/// Serializes a JSON value for the terminal, colorized and/or pretty printed
+#[doc(hidden)]
+fn render(output: &Value, colorize: bool, pretty: bool, now: u64) -> io::Result<String> {
+    Ok(if colorize {
+        color::colorize(output, pretty, now)
+    } else if pretty {
+        to_string_pretty(output)?
+    } else {
+        output.to_string()
+    })
+}
+
+/// Validates a DPoP proof (`jwtinfo dpop`)
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn run_dpop(matches: &ArgMatches, colorize: bool, pretty: bool) -> io::Result<()> {
+    let raw = read_input(matches.get_one::<String>("proof").unwrap())?;
+    let proof = dpop::parse(String::from_utf8_lossy(&raw).trim()).unwrap_or_else(|e| fail(e));
+    let mut request = dpop::Request::new(
+        matches.get_one::<String>("method").unwrap(),
+        matches.get_one::<String>("url").unwrap(),
+    );
+    request.access_token = matches.get_one::<String>("access-token").cloned();
+    request.nonce = matches.get_one::<String>("nonce").cloned();
+    request.max_age = *matches.get_one::<u64>("max-age").unwrap();
+
+    let now = timestamp::now();
+    let checks = proof.check(&request, now);
+    if matches.get_flag("json") {
+        let mut report = proof.to_json(&checks);
+        report["valid"] = Value::Bool(validation::all_passed(&checks));
+        println!("{}", render(&report, colorize, pretty, now)?);
+    } else {
+        for check in &checks {
+            println!("{}", check);
+        }
+    }
+    if !validation::all_passed(&checks) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Reads a key argument: `-` reads it from stdin, an existing path reads the file
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn read_key_input(arg: &str) -> io::Result<Vec<u8>> {
+    if arg != "-" && std::path::Path::new(arg).is_file() {
+        fs::read(arg)
+    } else {
+        read_input(arg)
+    }
+}
+
+/// Parses a JWK (or a JWK Set) argument
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn read_jwk(arg: &str) -> io::Result<Value> {
+    let raw = read_key_input(arg)?;
+    Ok(serde_json::from_slice(&raw).unwrap_or_else(|e| fail(format!("Invalid JWK: {}", e))))
+}
+
+/// Runs the JWK utilities (`jwtinfo jwk`)
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn run_jwk(matches: &ArgMatches, colorize: bool, pretty: bool) -> io::Result<()> {
+    let now = timestamp::now();
+    let with_kid = |mut key: Value, sub_matches: &ArgMatches| {
+        key["kid"] = match sub_matches.get_one::<String>("kid") {
+            Some(kid) => Value::from(kid.as_str()),
+            None => Value::from(jwk::thumbprint(&key).unwrap_or_else(|e| fail(e))),
+        };
+        key
+    };
+    match matches.subcommand() {
+        Some(("thumbprint", sub_matches)) => {
+            let raw = read_key_input(sub_matches.get_one::<String>("input").unwrap())?;
+            let text = String::from_utf8_lossy(&raw).trim().to_string();
+            // a token is given: use the key embedded in its header
+            let key = if jwt::looks_like_token(&text) {
+                let token = jwt::parse(&text).unwrap_or_else(|e| fail(e));
+                match token.header.get("jwk") {
+                    Some(key) => key.clone(),
+                    None => fail("the token header has no jwk parameter"),
+                }
+            } else {
+                serde_json::from_str(&text).unwrap_or_else(|e| fail(format!("Invalid JWK: {}", e)))
+            };
+            println!("{}", jwk::thumbprint(&key).unwrap_or_else(|e| fail(e)));
+        }
+        Some(("from-pem", sub_matches)) => {
+            let raw = read_key_input(sub_matches.get_one::<String>("input").unwrap())?;
+            let key = jwk::from_pem(&raw).unwrap_or_else(|e| fail(e));
+            println!(
+                "{}",
+                render(&with_kid(key, sub_matches), colorize, pretty, now)?
+            );
+        }
+        Some(("to-pem", sub_matches)) => {
+            let key = read_jwk(sub_matches.get_one::<String>("input").unwrap())?;
+            print!("{}", jwk::to_pem(&key).unwrap_or_else(|e| fail(e)));
+        }
+        Some(("public", sub_matches)) => {
+            let keys = read_jwk(sub_matches.get_one::<String>("input").unwrap())?;
+            let jwks = jwk::to_public_jwks(&keys).unwrap_or_else(|e| fail(e));
+            println!("{}", render(&jwks, colorize, pretty, now)?);
+        }
+        Some(("generate", sub_matches)) => {
+            let size = sub_matches.get_one::<usize>("size").copied();
+            let crv = sub_matches.get_one::<String>("crv").map(String::as_str);
+            let spec = match sub_matches.get_one::<String>("kty").unwrap().as_str() {
+                "RSA" => jwk::KeySpec::Rsa(size.unwrap_or(2048)),
+                "EC" => match crv.unwrap_or("P-256") {
+                    "P-256" => jwk::KeySpec::P256,
+                    "P-384" => jwk::KeySpec::P384,
+                    other => fail(format!("unsupported curve `{}` for EC keys", other)),
+                },
+                "OKP" => match crv.unwrap_or("Ed25519") {
+                    "Ed25519" => jwk::KeySpec::Ed25519,
+                    other => fail(format!("unsupported curve `{}` for OKP keys", other)),
+                },
+                _ => jwk::KeySpec::Oct(size.unwrap_or(256)),
+            };
+            let key = jwk::generate(spec).unwrap_or_else(|e| fail(e));
+            println!(
+                "{}",
+                render(&with_kid(key, sub_matches), colorize, pretty, now)?
+            );
+        }
+        _ => unreachable!("a subcommand is required"),
+    }
+    Ok(())
+}
+
+/// Starts the local token inspector (`jwtinfo serve`)
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn run_serve(matches: &ArgMatches) -> io::Result<()> {
+    let bind = *matches.get_one::<IpAddr>("bind").unwrap();
+    let port = *matches.get_one::<u16>("port").unwrap();
+    if !bind.is_loopback() {
+        eprintln!(
+            "Warning: {} is reachable from other machines, tokens will be sent over plain HTTP",
+            bind
+        );
+    }
+    server::serve(SocketAddr::new(bind, port), |addr| {
+        eprintln!("Listening on http://{}/ (press Ctrl+C to stop)", addr);
+    })
+}
+
+/// Starts the forward-auth verifier (`jwtinfo gateway`)
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn run_gateway(matches: &ArgMatches) -> io::Result<()> {
+    let mut keys = Vec::new();
+    for path in matches.get_many::<String>("key-file").unwrap() {
+        let input = fs::read(path)?;
+        keys.extend(jwk::read_keys(&input).unwrap_or_else(|e| fail(format!("{}: {}", path, e))));
+    }
+    let mut config = gateway::Config::new(keys);
+    config.header = matches.get_one::<String>("header").unwrap().clone();
+    config.issuer = matches.get_one::<String>("issuer").cloned();
+    config.audience = matches.get_one::<String>("audience").cloned();
+    config.rules = matches
+        .get_many::<gateway::Rule>("require")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.forward = matches
+        .get_many::<gateway::Forward>("forward")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.leeway = *matches.get_one::<u64>("leeway").unwrap();
+
+    let bind = *matches.get_one::<IpAddr>("bind").unwrap();
+    let port = *matches.get_one::<u16>("port").unwrap();
+    let quiet = matches.get_flag("quiet");
+    gateway::serve(
+        &config,
+        SocketAddr::new(bind, port),
+        |addr| eprintln!("Listening on http://{}/ (press Ctrl+C to stop)", addr),
+        |method, path, decision| {
+            if quiet {
+                return;
+            }
+            match decision.failure() {
+                Some(check) => eprintln!("{} {} {}: {}", decision.status, method, path, check),
+                None => eprintln!("{} {} {}", decision.status, method, path),
+            }
+        },
+    )
+}
+
+/// Starts the mock OpenID Provider (`jwtinfo issuer serve`)
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn run_issuer(matches: &ArgMatches) -> io::Result<()> {
+    let matches = match matches.subcommand() {
+        Some(("serve", sub_matches)) => sub_matches,
+        _ => unreachable!("a subcommand is required"),
+    };
+    let mut keys = Vec::new();
+    for path in matches.get_many::<String>("key-file").unwrap_or_default() {
+        let input = fs::read(path)?;
+        keys.extend(jwk::read_keys(&input).unwrap_or_else(|e| fail(format!("{}: {}", path, e))));
+    }
+    if keys.is_empty() {
+        let mut key = jwk::generate(jwk::KeySpec::Rsa(2048)).unwrap_or_else(|e| fail(e));
+        key["kid"] = Value::from(jwk::thumbprint(&key).unwrap_or_else(|e| fail(e)));
+        eprintln!("Signing with a new RSA key (kid {})", key["kid"]);
+        keys.push(key);
+    }
+    let mut config = issuer::Config::new(keys);
+    config.issuer = matches.get_one::<String>("issuer").cloned();
+    config.audience = matches.get_one::<String>("audience").cloned();
+    config.clients = matches
+        .get_many::<issuer::Credentials>("client")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.users = matches
+        .get_many::<issuer::Credentials>("user")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    for claim in matches.get_many::<String>("claim").unwrap_or_default() {
+        let (name, value) = claim
+            .split_once('=')
+            .unwrap_or_else(|| fail(format!("Invalid claim `{}`, expected NAME=VALUE", claim)));
+        // values are JSON when they can be parsed as such, strings otherwise
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+        config.claims.insert(name.to_string(), value);
+    }
+    config.lifetime = *matches.get_one::<u64>("lifetime").unwrap();
+
+    let mock = issuer::Issuer::new(config).unwrap_or_else(|e| fail(e));
+    let bind = *matches.get_one::<IpAddr>("bind").unwrap();
+    let port = *matches.get_one::<u16>("port").unwrap();
+    let quiet = matches.get_flag("quiet");
+    issuer::serve(
+        mock,
+        SocketAddr::new(bind, port),
+        |addr, iss| {
+            eprintln!(
+                "Listening on http://{}/ as issuer {} (press Ctrl+C to stop)",
+                addr, iss
+            )
+        },
+        |method, path, status| {
+            if !quiet {
+                eprintln!("{} {} {}", status, method, path);
+            }
+        },
+    )
+}
+
+/// The options of the main command, resolved once (including the files they
+/// refer to) so that they can be shared by the threads of `--batch`
+#[doc(hidden)]
+struct Options {
+    header: bool,
+    full: bool,
+    recursive: Option<usize>,
+    kb_nonce: Option<String>,
+    kb_audience: Option<String>,
+    explain: bool,
+    colorize: bool,
+    pretty: bool,
+    #[cfg(feature = "crypto")]
+    key: Option<String>,
+    #[cfg(feature = "crypto")]
+    implicit_assertion: String,
+    profile: Option<String>,
+    expected_typ: Option<String>,
+    expectations: profile::Expectations,
+    #[cfg(feature = "crypto")]
+    trusted: Option<Vec<x5c::Certificate>>,
+    #[cfg(feature = "crypto")]
+    chain_at_iat: bool,
+    template: Option<template::Template>,
+    min_remaining: Option<u64>,
+    quiet: bool,
+    redactor: Option<redact::Redactor>,
+    redact_token: bool,
+}
+
+impl Options {
+    #[doc(hidden)]
+    fn from_matches(matches: &ArgMatches, colorize: bool, pretty: bool) -> io::Result<Self> {
+        #[cfg(feature = "crypto")]
+        let key = match matches.get_one::<String>("key-file") {
+            Some(path) => Some(fs::read_to_string(path)?),
+            None => matches.get_one::<String>("key").cloned(),
+        };
+        #[cfg(feature = "crypto")]
+        let trusted = match matches.get_one::<String>("ca-file") {
+            Some(path) => {
+                Some(x5c::parse_pem_bundle(&fs::read_to_string(path)?).unwrap_or_else(|e| fail(e)))
+            }
+            None => None,
+        };
+        let template_source = match matches.get_one::<String>("template-file") {
+            Some(path) => Some(fs::read_to_string(path)?),
+            None => matches.get_one::<String>("template").cloned(),
+        };
+        let template = template_source.map(|source| {
+            let source = source.strip_suffix('\n').unwrap_or(&source);
+            template::parse(source).unwrap_or_else(|e| fail(e))
+        });
+        Ok(Self {
+            header: matches.get_flag("header"),
+            full: matches.get_flag("full"),
+            recursive: if matches.get_flag("recursive") {
+                matches.get_one::<usize>("max-depth").copied()
+            } else {
+                None
+            },
+            kb_nonce: matches.get_one::<String>("kb-nonce").cloned(),
+            kb_audience: matches.get_one::<String>("kb-audience").cloned(),
+            explain: matches.get_flag("explain"),
+            colorize,
+            pretty,
+            #[cfg(feature = "crypto")]
+            key,
+            #[cfg(feature = "crypto")]
+            implicit_assertion: matches
+                .get_one::<String>("implicit-assertion")
+                .unwrap()
+                .clone(),
+            profile: matches.get_one::<String>("profile").cloned(),
+            expected_typ: matches.get_one::<String>("expect-typ").cloned(),
+            expectations: profile::Expectations {
+                issuer: matches.get_one::<String>("issuer").cloned(),
+                audience: matches.get_one::<String>("audience").cloned(),
+                nonce: matches.get_one::<String>("nonce").cloned(),
+                max_age: matches.get_one::<u64>("max-age").copied(),
+                access_token: matches.get_one::<String>("access-token").cloned(),
+                code: matches.get_one::<String>("code").cloned(),
+            },
+            #[cfg(feature = "crypto")]
+            trusted,
+            #[cfg(feature = "crypto")]
+            chain_at_iat: matches.get_one::<String>("chain-time").unwrap() == "iat",
+            template,
+            // a token expires within a duration when at most this duration is left
+            min_remaining: matches
+                .get_one::<u64>("min-remaining")
+                .copied()
+                .or_else(|| {
+                    matches
+                        .get_one::<u64>("expires-within")
+                        .map(|d| d.saturating_add(1))
+                }),
+            quiet: matches.get_flag("quiet"),
+            redactor: redactor_from_matches(matches),
+            redact_token: matches.get_flag("redact-token"),
+        })
+    }
+}
+
+/// Returns the redactor of `--redact` (or `--redact-token`), if enabled
+#[doc(hidden)]
+fn redactor_from_matches(matches: &ArgMatches) -> Option<redact::Redactor> {
+    if !matches.contains_id("redaction") {
+        return None;
+    }
+    // without a salt, the hashes only match within this run
+    let salt = match matches.get_one::<String>("redact-salt") {
+        Some(salt) => salt.as_bytes().to_vec(),
+        None => random_salt(),
+    };
+    let mut redactor = redact::Redactor::new(salt);
+    if let Some(claims) = matches.get_many::<String>("redact-claim") {
+        redactor.claims.extend(claims.cloned());
+    }
+    Some(redactor)
+}
+
+/// Returns 16 random bytes to salt the redaction hashes
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn random_salt() -> Vec<u8> {
+    let mut salt = vec![0; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Returns 16 random bytes to salt the redaction hashes, from the hasher of
+/// the standard library (whose keys are picked by the operating system)
+#[doc(hidden)]
+#[cfg(not(feature = "crypto"))]
+fn random_salt() -> Vec<u8> {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+
+    (0..2u8)
+        .flat_map(|i| RandomState::new().hash_one(i).to_le_bytes())
+        .collect()
+}
+
+/// The output produced for a token, and the exit code of the process (`0` if
+/// its checks passed)
+#[doc(hidden)]
+struct Report {
+    output: String,
+    code: i32,
+}
+
+/// Decodes a PASETO (verified or decrypted with `--key`) into its header,
+/// claims and full output, or returns `None` if the token is not a PASETO
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn decode_paseto(token: &str, options: &Options) -> Result<Option<(Value, Value, Value)>, String> {
+    if !paseto::is_paseto(token) {
+        return Ok(None);
+    }
+    let mut paseto_token = paseto::parse(token).map_err(|e| e.to_string())?;
+    if let Some(key) = &options.key {
+        let assertion = options.implicit_assertion.as_bytes();
+        match paseto_token.purpose {
+            paseto::Purpose::Public => paseto_token
+                .verify(key, assertion)
+                .map_err(|e| e.to_string())?,
+            paseto::Purpose::Local => {
+                paseto_token.claims = paseto_token
+                    .decrypt(key, assertion)
+                    .map_err(|e| e.to_string())?
+            }
+        }
+    }
+    Ok(Some((
+        paseto_token.header(),
+        paseto_token.claims.clone(),
+        paseto_token.to_json(),
+    )))
+}
+
+/// Rejects the PASETOs, which cannot be decoded without the `crypto` feature
+#[doc(hidden)]
+#[cfg(not(feature = "crypto"))]
+fn decode_paseto(token: &str, _: &Options) -> Result<Option<(Value, Value, Value)>, String> {
+    let mut parts = token.splitn(3, '.');
+    match (parts.next(), parts.next()) {
+        (Some("v1" | "v2" | "v3" | "v4"), Some("local" | "public")) => {
+            Err("Cannot decode PASETOs: jwtinfo was built without the crypto feature".to_string())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Decodes (and, depending on the options, validates) a token
+#[doc(hidden)]
+fn inspect(raw: &[u8], options: &Options, now: u64) -> Result<Report, String> {
+    let token = String::from_utf8_lossy(raw).trim().to_string();
+
+    // CWTs are given as hex, base64 or binary data and never contain `.` or `~`
+    let cwt_bytes = match str::from_utf8(raw) {
+        Ok(text) if !text.contains('.') && !text.contains('~') => cwt::decode_input(text),
+        Ok(_) => None,
+        Err(_) => Some(raw.to_vec()),
+    }
+    .filter(|bytes| cwt::looks_like_cwt(bytes));
+
+    // only JWTs (and SD-JWTs) can be re-encoded by --redact-token
+    let mut compact = true;
+    let (header, claims, full) = if let Some(bytes) = cwt_bytes {
+        compact = false;
+        let cwt_token = cwt::parse(&bytes).map_err(|e| e.to_string())?;
+        (
+            cwt_token.header(),
+            cwt_token.claims.clone(),
+            cwt_token.to_json(),
+        )
+    } else if let Some(decoded) = decode_paseto(&token, options)? {
+        compact = false;
+        decoded
+    } else if sdjwt::is_sd_jwt(&token) {
+        // SD-JWT: show the claims of the issuer JWT once the disclosures are applied
+        let sd_jwt = sdjwt::parse(&token).map_err(|e| e.to_string())?;
+        let full = sd_jwt.to_json(
+            options.kb_nonce.as_deref(),
+            options.kb_audience.as_deref(),
+            now,
+        );
+        (sd_jwt.issuer_jwt.header, sd_jwt.claims, full)
+    } else {
+        let parsed = match options.recursive {
+            Some(max_depth) => jwt::parse_recursive(&token, max_depth),
+            None => jwt::parse(&token),
+        };
+        let jwt_token = parsed.map_err(|e| e.to_string())?;
+        let full = jwt_token.to_json();
+        (jwt_token.header, jwt_token.body, full)
+    };
+
+    // the detected provider picks the explanations and the "auto" profile
+    let detection = provider::detect(&header, &claims);
+    let detected_source = detection
+        .as_ref()
+        .filter(|d| d.confidence >= provider::Confidence::Medium)
+        .and_then(|d| d.provider.source());
+    #[cfg(feature = "crypto")]
+    let chain = x5c::parse_chain(&header);
+    let mut full = full;
+    if let Value::Object(members) = &mut full {
+        if let Some(d) = &detection {
+            members.insert("provider".to_string(), d.to_json());
+        }
+        #[cfg(feature = "crypto")]
+        match &chain {
+            Ok(certificates) if !certificates.is_empty() => {
+                let certificates = certificates.iter().map(|c| c.to_json()).collect();
+                members.insert("certificates".to_string(), Value::Array(certificates));
+            }
+            _ => {}
+        }
+    }
+
+    // nothing personal is left in any of the outputs below
+    let (header, claims, full) = match &options.redactor {
+        Some(redactor) => (
+            redactor.redact(&header),
+            redactor.redact(&claims),
+            redactor.redact(&full),
+        ),
+        None => (header, claims, full),
+    };
+    if options.redact_token {
+        if !compact || !claims.is_object() {
+            return Err(
+                "Cannot re-encode the token: --redact-token only supports JWTs with JSON claims"
+                    .to_string(),
+            );
+        }
+        return Ok(Report {
+            output: format!("{}\n", redact::safe_token(&header, &claims)),
+            code: 0,
+        });
+    }
+
+    if let Some(min_remaining) = options.min_remaining {
+        let expiry = expiry::check(&claims, min_remaining, now);
+        return Ok(Report {
+            output: if options.quiet {
+                String::new()
+            } else {
+                format!("{}\n", expiry)
+            },
+            code: expiry.status.exit_code(),
+        });
+    }
+
+    if options.explain {
+        let mut output = String::new();
+        if options.header || options.full {
+            output.push_str(&explain::explain(
+                explain::Location::Header,
+                &header,
+                detected_source,
+                now,
+            ));
+        }
+        if !options.header {
+            output.push_str(&explain::explain(
+                explain::Location::Claims,
+                &claims,
+                detected_source,
+                now,
+            ));
+        }
+        return Ok(Report { output, code: 0 });
+    }
+
+    // profiles and certificate chains are validated with checks
+    let mut checks: Option<Vec<validation::Check>> = None;
+    if let Some(name) = &options.profile {
+        let selected: profile::Profile = if name == "auto" {
+            detection
+                .as_ref()
+                .and_then(|d| d.provider.default_profile())
+                .ok_or("Cannot pick a profile: the provider of the token is not detected or has no default profile")?
+        } else {
+            name.parse()
+                .map_err(|e: profile::ProfileError| e.to_string())?
+        };
+        checks.get_or_insert_with(Vec::new).extend(selected.check(
+            &header,
+            &claims,
+            &options.expectations,
+            now,
+        ));
+    }
+    if let Some(typ) = &options.expected_typ {
+        checks
+            .get_or_insert_with(Vec::new)
+            .push(profile::check_typ(&header, typ, true));
+    }
+    #[cfg(feature = "crypto")]
+    if let Some(trusted) = &options.trusted {
+        let chain = chain.map_err(|e| e.to_string())?;
+        let at = if options.chain_at_iat {
+            claims
+                .get("iat")
+                .and_then(|iat| iat.as_u64())
+                .ok_or("Cannot validate the chain at iat: the token has no numeric iat claim")?
+        } else {
+            now
+        };
+        checks
+            .get_or_insert_with(Vec::new)
+            .extend(x5c::validate(&token, &header, &chain, trusted, at));
+    }
+    if let Some(checks) = checks {
+        let valid = validation::all_passed(&checks);
+        let output = if options.full {
+            let report = serde_json::json!({
+                "header": header,
+                "claims": claims,
+                "checks": checks.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+                "valid": valid,
+            });
+            let rendered = render(&report, options.colorize, options.pretty, now)
+                .map_err(|e| e.to_string())?;
+            format!("{}\n", rendered)
+        } else {
+            checks.iter().map(|check| format!("{}\n", check)).collect()
+        };
+        return Ok(Report {
+            output,
+            code: if valid { 0 } else { 1 },
+        });
+    }
+
+    if let Some(template) = &options.template {
+        let context = serde_json::json!({ "header": header, "claims": claims });
+        let rendered = template.render(&context, now).map_err(|e| e.to_string())?;
+        return Ok(Report {
+            output: format!("{}\n", rendered),
+            code: 0,
+        });
+    }
+
+    let output = if options.full || options.recursive.is_some() {
+        // Show both header and claims (and the nested tokens, if any)
+        full
+    } else if options.header {
+        header
+    } else {
+        claims
+    };
+    let rendered =
+        render(&output, options.colorize, options.pretty, now).map_err(|e| e.to_string())?;
+    Ok(Report {
+        output: format!("{}\n", rendered),
+        code: 0,
+    })
+}
+
+/// Inspects one token per line of a file or of stdin (`--batch`), on `jobs` threads
+///
+/// The outputs are printed in the order of the lines and the errors are
+/// reported with their line number. Blank lines are skipped.
+#[doc(hidden)]
+fn run_batch(input: &str, options: &Options, jobs: usize, now: u64) -> io::Result<()> {
+    let reader: Box<dyn BufRead + Send> = if input == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(fs::File::open(input)?))
+    };
+    let lines = reader.split(b'\n').enumerate();
+
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    // errors take precedence over the expiry statuses, which can't hide them
+    let mut code = 0;
+    let mut failed = false;
+    batch::map_ordered(
+        lines,
+        jobs,
+        |(index, line)| {
+            let report = line.map(|line| {
+                if line.trim_ascii().is_empty() {
+                    None
+                } else {
+                    Some(inspect(&line, options, now))
+                }
+            });
+            (index + 1, report)
+        },
+        |(number, report)| {
+            match report? {
+                Some(Ok(report)) => {
+                    failed |= report.code == 1;
+                    code = code.max(report.code);
+                    stdout.write_all(report.output.as_bytes())?;
+                }
+                Some(Err(e)) => {
+                    failed = true;
+                    // keeps the errors in sync with the outputs
+                    stdout.flush()?;
+                    eprintln!("Error: line {}: {}", number, e);
+                }
+                None => {}
+            }
+            Ok::<(), io::Error>(())
+        },
+    )?;
+    stdout.flush()?;
+    if failed {
+        process::exit(1);
+    }
+    if code != 0 {
+        process::exit(code);
+    }
+    Ok(())
+}
+
+/// Prints the tokens found in a HAR file (`--har`), one JSON object per sighting
+#[doc(hidden)]
+fn run_har(
+    path: &str,
+    redactor: Option<&redact::Redactor>,
+    colorize: bool,
+    pretty: bool,
+) -> io::Result<()> {
+    let input = if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(path)?
+    };
+    let sightings = har::parse(&input).unwrap_or_else(|e| fail(e));
+    if sightings.is_empty() {
+        fail("no token found in the HAR file");
+    }
+    let now = timestamp::now();
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    for sighting in &sightings {
+        let json = match redactor {
+            Some(redactor) => redactor.redact(&sighting.to_json()),
+            None => sighting.to_json(),
+        };
+        writeln!(stdout, "{}", render(&json, colorize, pretty, now)?)?;
+    }
+    stdout.flush()
+}
+
+/// Follows a token file and reports its rotations (`jwtinfo watch`)
+#[doc(hidden)]
+fn run_watch(matches: &ArgMatches) -> io::Result<()> {
+    let input = matches.get_one::<String>("input").unwrap().clone();
+    let interval = *matches.get_one::<f64>("interval").unwrap();
+    let stale_after = matches.get_one::<f64>("stale-after").copied();
+    if !(interval.is_finite() && interval > 0.0) {
+        fail("--interval must be a positive number of seconds");
+    }
+    if stale_after.is_some_and(|fraction| !(fraction > 0.0 && fraction <= 1.0)) {
+        fail("--stale-after must be a fraction of the lifetime between 0 and 1");
+    }
+    let interval = Duration::from_secs_f64(interval);
+    // the countdown is rewritten in place, on stderr, for humans only
+    let live = io::stderr().is_terminal();
+
+    // stdin gives one version of the token per line, a file is polled
+    let lines = if input == "-" {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Some(receiver)
+    } else {
+        None
+    };
+
+    let log = |message: &str| {
+        if live {
+            eprint!("\r\x1b[2K");
+        }
+        println!(
+            "{} {}",
+            timestamp::format_iso8601(timestamp::now() as i64),
+            message
+        );
+    };
+    let mut watcher = watch::Watcher::new();
+    let mut last_error: Option<String> = None;
+    let mut expired = false;
+    loop {
+        let content = match &lines {
+            Some(receiver) => match receiver.recv_timeout(interval) {
+                Ok(line) => Some(line),
+                Err(mpsc::RecvTimeoutError::Timeout) => None,
+                Err(mpsc::RecvTimeoutError::Disconnected) if watcher.claims().is_none() => {
+                    fail("no token read from stdin")
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    thread::sleep(interval);
+                    None
+                }
+            },
+            None => Some(fs::read_to_string(&input)),
+        };
+        let now = timestamp::now();
+        match content {
+            Some(Ok(content)) => {
+                last_error = None;
+                let (verb, changes) = match watcher.update(&content) {
+                    Some(watch::Event::Loaded) => ("loaded", Vec::new()),
+                    Some(watch::Event::Rotated(changes)) => ("rotated", changes),
+                    Some(watch::Event::Invalid(e)) => {
+                        log(&format!("invalid token: {}", e));
+                        ("", Vec::new())
+                    }
+                    None => ("", Vec::new()),
+                };
+                if let (false, Some(claims)) = (verb.is_empty(), watcher.claims()) {
+                    expired = false;
+                    log(&format!(
+                        "token {}, {}",
+                        verb,
+                        watch::countdown(claims, now)
+                    ));
+                    for change in changes {
+                        println!("  {}", change);
+                    }
+                    if stale_after.is_some() && watch::elapsed_fraction(claims, now).is_none() {
+                        log("the token has no exp or no iat/nbf: --stale-after cannot be checked");
+                    }
+                }
+            }
+            Some(Err(e)) => {
+                // reported once, until the file can be read again
+                let error = format!("cannot read {}: {}", input, e);
+                if last_error.as_ref() != Some(&error) {
+                    log(&error);
+                    last_error = Some(error);
+                }
+            }
+            None => {}
+        }
+
+        if let Some(claims) = watcher.claims() {
+            let exp = watch::time_claim(claims, "exp");
+            if !expired && exp.is_some_and(|exp| exp <= i64::try_from(now).unwrap_or(i64::MAX)) {
+                expired = true;
+                log("token expired");
+            }
+            if let (Some(threshold), Some(fraction)) =
+                (stale_after, watch::elapsed_fraction(claims, now))
+            {
+                if fraction >= threshold {
+                    if live {
+                        eprintln!();
+                    }
+                    eprintln!(
+                        "Error: the token used {:.0}% of its lifetime without being refreshed",
+                        fraction * 100.0
+                    );
+                    process::exit(watch::EXIT_STALE);
+                }
+            }
+            if live {
+                eprint!("\r\x1b[2K{}", watch::countdown(claims, now));
+            }
+        }
+        if lines.is_none() {
+            thread::sleep(interval);
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Returns the `jwtinfo dpop` subcommand
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn dpop_command() -> Command {
+    Command::new("dpop")
+        .about("Validates a DPoP proof (RFC 9449) against an HTTP request and, optionally, an access token")
+        .args([
+            Arg::new("method")
+                .short('m')
+                .long("method")
+                .value_name("METHOD")
+                .required(true)
+                .help("The HTTP method of the request (compared with htm)"),
+            Arg::new("url")
+                .short('u')
+                .long("url")
+                .value_name("URL")
+                .required(true)
+                .help("The URL of the request (compared with htu, after normalisation)"),
+            Arg::new("access-token")
+                .long("access-token")
+                .value_name("TOKEN")
+                .help("The access token sent with the proof (checks ath and cnf.jkt)"),
+            Arg::new("nonce")
+                .long("nonce")
+                .value_name("NONCE")
+                .help("The nonce provided by the server"),
+            Arg::new("max-age")
+                .long("max-age")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("300")
+                .help("Maximum difference between iat and the current time"),
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Shows the proof and the checks as JSON"),
+            Arg::new("proof")
+                .index(1)
+                .allow_hyphen_values(true)
+                .required(true)
+                .help("the DPoP proof JWT (use \"-\" to read from stdin)"),
+        ])
+}
+
+/// Returns the `jwtinfo jwk` subcommand
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn jwk_command() -> Command {
+    Command::new("jwk")
+        .about("JWK utilities: thumbprints, PEM/DER conversion and key generation")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("thumbprint")
+                .about("Computes the RFC 7638 thumbprint of a JWK, or of the jwk header of a token")
+                .arg(
+                    Arg::new("input")
+                        .index(1)
+                        .allow_hyphen_values(true)
+                        .required(true)
+                        .help("A JWK, a token with a jwk header or a file (use \"-\" to read from stdin)"),
+                ),
+        )
+        .subcommand(
+            Command::new("from-pem")
+                .about("Converts a PEM or DER encoded key (RSA, EC or Ed25519) to a JWK")
+                .args([
+                    Arg::new("kid")
+                        .long("kid")
+                        .value_name("KID")
+                        .help("The key ID to assign (defaults to the thumbprint)"),
+                    Arg::new("input")
+                        .index(1)
+                        .allow_hyphen_values(true)
+                        .required(true)
+                        .help("The key file (use \"-\" to read from stdin)"),
+                ]),
+        )
+        .subcommand(
+            Command::new("to-pem")
+                .about("Converts a JWK to PEM (PKCS#8 for private keys, SPKI for public keys)")
+                .arg(
+                    Arg::new("input")
+                        .index(1)
+                        .allow_hyphen_values(true)
+                        .required(true)
+                        .help("A JWK or a file (use \"-\" to read from stdin)"),
+                ),
+        )
+        .subcommand(
+            Command::new("public")
+                .about("Extracts a public JWK Set from a private JWK or JWK Set")
+                .arg(
+                    Arg::new("input")
+                        .index(1)
+                        .allow_hyphen_values(true)
+                        .required(true)
+                        .help("A JWK, a JWK Set or a file (use \"-\" to read from stdin)"),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Generates a new key")
+                .args([
+                    Arg::new("kty")
+                        .long("kty")
+                        .value_name("KTY")
+                        .value_parser(["RSA", "EC", "OKP", "oct"])
+                        .required(true)
+                        .help("The type of the key"),
+                    Arg::new("size")
+                        .long("size")
+                        .value_name("BITS")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("The size of RSA (default 2048) and oct (default 256) keys"),
+                    Arg::new("crv")
+                        .long("crv")
+                        .value_name("CURVE")
+                        .value_parser(["P-256", "P-384", "Ed25519"])
+                        .help("The curve of EC (default P-256) and OKP (default Ed25519) keys"),
+                    Arg::new("kid")
+                        .long("kid")
+                        .value_name("KID")
+                        .help("The key ID to assign (defaults to the thumbprint)"),
+                ]),
+        )
+}
+
+/// Returns the `jwtinfo serve` subcommand
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn serve_command() -> Command {
+    Command::new("serve")
+        .about("Starts a local web page to inspect tokens, with a JSON API (POST /decode)")
+        .args([
+            Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .value_parser(clap::value_parser!(u16))
+                .default_value("8080")
+                .help("The port to listen on (0 picks a free port)"),
+            Arg::new("bind")
+                .long("bind")
+                .value_name("ADDRESS")
+                .value_parser(clap::value_parser!(IpAddr))
+                .default_value("127.0.0.1")
+                .help("The address to listen on (only loopback addresses keep the server private)"),
+        ])
+}
+
+/// Returns the `jwtinfo gateway` subcommand
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn gateway_command() -> Command {
+    Command::new("gateway")
+        .about("Starts a forward-auth verifier for reverse proxies (nginx auth_request, Traefik ForwardAuth, Envoy ext_authz)")
+        .args([
+            Arg::new("key-file")
+                .short('k')
+                .long("key-file")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .required(true)
+                .help("A public JWK, JWK Set or PEM key the tokens can be signed with (can be repeated)"),
+            Arg::new("header")
+                .long("header")
+                .value_name("NAME")
+                .default_value(gateway::DEFAULT_HEADER)
+                .help("The request header containing the token"),
+            Arg::new("issuer")
+                .long("issuer")
+                .value_name("ISS")
+                .help("The expected issuer (iss)"),
+            Arg::new("audience")
+                .long("audience")
+                .value_name("AUD")
+                .help("The expected audience (one of the values of aud)"),
+            Arg::new("require")
+                .long("require")
+                .value_name("CLAIM=VALUE")
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(gateway::Rule))
+                .help("A claim the token must contain, answering 403 otherwise (arrays and scopes must contain the value, can be repeated)"),
+            Arg::new("forward")
+                .long("forward")
+                .value_name("CLAIM[=HEADER]")
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(gateway::Forward))
+                .help("A claim sent back as a response header, X-Auth-<Claim> by default (can be repeated)"),
+            Arg::new("leeway")
+                .long("leeway")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("60")
+                .help("The tolerated clock skew for exp and nbf"),
+            Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .value_parser(clap::value_parser!(u16))
+                .default_value("8081")
+                .help("The port to listen on (0 picks a free port)"),
+            Arg::new("bind")
+                .long("bind")
+                .value_name("ADDRESS")
+                .value_parser(clap::value_parser!(IpAddr))
+                .default_value("127.0.0.1")
+                .help("The address to listen on"),
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Doesn't log the decisions on stderr"),
+        ])
+}
+
+/// Returns the `jwtinfo issuer` subcommand
+#[doc(hidden)]
+#[cfg(feature = "server")]
+fn issuer_command() -> Command {
+    Command::new("issuer")
+        .about("A mock OpenID Provider issuing signed tokens, for tests and local development")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("serve")
+                .about("Serves discovery, JWKS and a token endpoint (client_credentials and password grants)")
+                .args([
+                    Arg::new("key-file")
+                        .short('k')
+                        .long("key-file")
+                        .value_name("FILE")
+                        .action(ArgAction::Append)
+                        .help("A private JWK, JWK Set or PEM key, the first one signs the tokens (defaults to a new RSA key, can be repeated)"),
+                    Arg::new("issuer")
+                        .long("issuer")
+                        .value_name("URL")
+                        .help("The issuer identifier and base URL of the endpoints (defaults to the address of the server)"),
+                    Arg::new("audience")
+                        .long("audience")
+                        .value_name("AUD")
+                        .help("The audience of the access tokens (defaults to the audience parameter, then to the client id)"),
+                    Arg::new("client")
+                        .long("client")
+                        .value_name("ID:SECRET")
+                        .action(ArgAction::Append)
+                        .value_parser(clap::value_parser!(issuer::Credentials))
+                        .help("An accepted client, any client is accepted if none is given (can be repeated)"),
+                    Arg::new("user")
+                        .long("user")
+                        .value_name("NAME:PASSWORD")
+                        .action(ArgAction::Append)
+                        .value_parser(clap::value_parser!(issuer::Credentials))
+                        .help("An accepted user of the password grant, any user is accepted if none is given (can be repeated)"),
+                    Arg::new("claim")
+                        .long("claim")
+                        .value_name("NAME=VALUE")
+                        .action(ArgAction::Append)
+                        .help("A claim added to every token, the value is parsed as JSON if possible (can be repeated)"),
+                    Arg::new("lifetime")
+                        .long("lifetime")
+                        .value_name("SECONDS")
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("3600")
+                        .help("The lifetime of the tokens"),
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .value_name("PORT")
+                        .value_parser(clap::value_parser!(u16))
+                        .default_value("8082")
+                        .help("The port to listen on (0 picks a free port)"),
+                    Arg::new("bind")
+                        .long("bind")
+                        .value_name("ADDRESS")
+                        .value_parser(clap::value_parser!(IpAddr))
+                        .default_value("127.0.0.1")
+                        .help("The address to listen on"),
+                    Arg::new("quiet")
+                        .short('q')
+                        .long("quiet")
+                        .action(ArgAction::SetTrue)
+                        .help("Doesn't log the requests on stderr"),
+                ]),
+        )
+}
+
+/// Returns the options of the main command that need the `crypto` feature
+#[doc(hidden)]
+#[cfg(feature = "crypto")]
+fn crypto_args() -> [Arg; 5] {
+    [
+        Arg::new("key")
+            .long("key")
+            .value_name("KEY")
+            .conflicts_with("key-file")
+            .help("Verifies (public) or decrypts (local) a PASETO with the given key (hex, PASERK or PEM)"),
+        Arg::new("key-file")
+            .long("key-file")
+            .value_name("FILE")
+            .help("Same as --key, but reads the key from a file"),
+        Arg::new("implicit-assertion")
+            .long("implicit-assertion")
+            .value_name("ASSERTION")
+            .default_value("")
+            .help("The implicit assertion used to verify or decrypt a v3 or v4 PASETO"),
+        Arg::new("ca-file")
+            .long("ca-file")
+            .value_name("FILE")
+            .conflicts_with_all([
+                "header",
+                "template",
+                "template-file",
+                "explain",
+                "expiry",
+                "redact-token",
+                "har",
+            ])
+            .help("Validates the x5c certificate chain against the CA certificates of a PEM bundle, and the signature with the leaf key"),
+        Arg::new("chain-time")
+            .long("chain-time")
+            .value_name("WHEN")
+            .value_parser(["now", "iat"])
+            .default_value("now")
+            .requires("ca-file")
+            .help("When the certificates of the chain must be valid: now or at the iat of the token"),
+    ]
+}
+
 fn main() -> io::Result<()> {
-    let matches = Command::new("jwtinfo")
+    let command = Command::new("jwtinfo")
         .version(env!("CARGO_PKG_VERSION"))
         .about("Shows information about a JWT (Json Web Token)")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true);
+    #[cfg(feature = "crypto")]
+    let command = command.subcommand(dpop_command()).subcommand(jwk_command());
+    #[cfg(feature = "server")]
+    let command = command
+        .subcommand(serve_command())
+        .subcommand(gateway_command())
+        .subcommand(issuer_command());
+    let command = command
+        .subcommand(
+            Command::new("watch")
+                .about("Follows a token file that is rotated in place, logging rotations and counting down to exp")
+                .args([
+                    Arg::new("interval")
+                        .long("interval")
+                        .value_name("SECONDS")
+                        .value_parser(clap::value_parser!(f64))
+                        .default_value("1")
+                        .help("How often the file is read"),
+                    Arg::new("stale-after")
+                        .long("stale-after")
+                        .value_name("FRACTION")
+                        .value_parser(clap::value_parser!(f64))
+                        .help("Exits with code 13 once the token used this fraction of its lifetime (from nbf or iat to exp) without being refreshed, e.g. 0.8"),
+                    Arg::new("input")
+                        .index(1)
+                        .allow_hyphen_values(true)
+                        .required(true)
+                        .help("The token file (use \"-\" to read one token per line from stdin)"),
+                ]),
+        )
         .args([
             Arg::new("header")
                 .short('H')
@@ -26,60 +1323,270 @@ fn main() -> io::Result<()> {
                 .short('P')
                 .long("pretty")
                 .action(ArgAction::SetTrue)
+                .global(true)
                 .help("Pretty prints the JWT header or body"),
+            Arg::new("color")
+                .long("color")
+                .value_name("WHEN")
+                .value_parser(["auto", "always", "never"])
+                .default_value("auto")
+                .global(true)
+                .help("Colorizes the output (\"auto\" only colorizes when writing to a terminal and NO_COLOR is not set)"),
+            Arg::new("template")
+                .short('t')
+                .long("template")
+                .value_name("TEMPLATE")
+                .conflicts_with_all(["header", "full", "template-file"])
+                .help("Renders the token with a template (e.g. \"{{claims.sub}} expires {{claims.exp | relative}}\")"),
+            Arg::new("template-file")
+                .long("template-file")
+                .value_name("FILE")
+                .conflicts_with_all(["header", "full"])
+                .help("Renders the token with a template read from a file"),
+            Arg::new("recursive")
+                .short('R')
+                .long("recursive")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("header")
+                .help("Decodes nested tokens (cty: JWT payloads and claims containing a JWT) and shows them as a tree (implies --full)"),
+            Arg::new("max-depth")
+                .long("max-depth")
+                .value_name("DEPTH")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("5")
+                .requires("recursive")
+                .help("Maximum nesting depth decoded by --recursive"),
+            Arg::new("kb-nonce")
+                .long("kb-nonce")
+                .value_name("NONCE")
+                .requires("full")
+                .help("The expected nonce of the key binding JWT of an SD-JWT, checked with --full"),
+            Arg::new("kb-audience")
+                .long("kb-audience")
+                .value_name("AUDIENCE")
+                .requires("full")
+                .help("The expected aud of the key binding JWT of an SD-JWT, checked with --full"),
+            Arg::new("explain")
+                .short('E')
+                .long("explain")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["template", "template-file", "profile"])
+                .help("Annotates every claim (and header parameter, with --header or --full) with its meaning"),
+            Arg::new("profile")
+                .long("profile")
+                .value_name("PROFILE")
+                .value_parser(clap::builder::PossibleValuesParser::new(
+                    profile::Profile::NAMES.iter().chain(&["auto"]),
+                ))
+                .conflicts_with_all(["header", "template", "template-file"])
+                .help("Validates the claims against a token profile and prints the checks (with --full, as JSON); \"auto\" picks the profile of the detected provider"),
+            Arg::new("issuer")
+                .long("issuer")
+                .value_name("ISSUER")
+                .requires("profile")
+                .help("The expected issuer (iss), checked by --profile"),
+            Arg::new("audience")
+                .long("audience")
+                .visible_alias("client-id")
+                .value_name("AUDIENCE")
+                .requires("profile")
+                .help("The expected audience (aud and azp), checked by --profile"),
+            Arg::new("nonce")
+                .long("nonce")
+                .value_name("NONCE")
+                .requires("profile")
+                .help("The nonce sent in the authentication request, checked by --profile"),
+            Arg::new("max-age")
+                .long("max-age")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .requires("profile")
+                .help("The max_age sent in the authentication request (checks auth_time), checked by --profile"),
+            Arg::new("access-token")
+                .long("access-token")
+                .value_name("TOKEN")
+                .requires("profile")
+                .help("The access token issued with the ID token (checks at_hash), checked by --profile"),
+            Arg::new("code")
+                .long("code")
+                .value_name("CODE")
+                .requires("profile")
+                .help("The authorization code issued with the ID token (checks c_hash), checked by --profile"),
+            Arg::new("expect-typ")
+                .long("expect-typ")
+                .value_name("TYPE")
+                .conflicts_with_all(["header", "template", "template-file", "explain"])
+                .help("Checks that the typ header is TYPE (e.g. JWT, at+jwt or dpop+jwt) and prints the check"),
+            Arg::new("batch")
+                .short('b')
+                .long("batch")
+                .action(ArgAction::SetTrue)
+                .help("Reads one token per line from a file (or from stdin with \"-\") and prints the results in the same order"),
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires("batch")
+                .help("The number of threads used by --batch (defaults to the number of CPUs)"),
+            Arg::new("expires-within")
+                .long("expires-within")
+                .value_name("DURATION")
+                .value_parser(parse_duration_arg)
+                .help("Checks exp: exits with 10 if the token expires within DURATION (e.g. 15m, 1h30m, 7d), 11 if it is expired and 12 if it has no exp"),
+            Arg::new("min-remaining")
+                .long("min-remaining")
+                .value_name("DURATION")
+                .value_parser(parse_duration_arg)
+                .help("Same as --expires-within, but a token with exactly DURATION left is OK"),
+            Arg::new("at")
+                .long("at")
+                .value_name("TIME")
+                .value_parser(parse_time_arg)
+                .requires("expiry")
+                .help("Checks exp at this time (a NumericDate or an ISO 8601 date) rather than now"),
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .requires("expiry")
+                .help("Only reports the expiry status with the exit code"),
+            Arg::new("redact")
+                .long("redact")
+                .action(ArgAction::SetTrue)
+                .help("Replaces the personal claims (name, email, phone, address, ...) with salted hashes, to share the token safely"),
+            Arg::new("redact-token")
+                .long("redact-token")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all([
+                    "header",
+                    "full",
+                    "recursive",
+                    "template",
+                    "template-file",
+                    "explain",
+                    "profile",
+                    "expect-typ",
+                    "expiry",
+                    "har",
+                ])
+                .help("Prints the token with the personal claims redacted and without its signature (implies --redact)"),
+            Arg::new("redact-claim")
+                .long("redact-claim")
+                .value_name("NAME")
+                .action(ArgAction::Append)
+                .requires("redaction")
+                .help("Also redacts this claim (can be repeated)"),
+            Arg::new("redact-salt")
+                .long("redact-salt")
+                .value_name("SALT")
+                .requires("redaction")
+                .help("The salt of the redaction hashes, to match the hashes of several runs (random by default)"),
+            Arg::new("har")
+                .long("har")
+                .value_name("FILE")
+                .conflicts_with_all([
+                    "token",
+                    "batch",
+                    "header",
+                    "template",
+                    "template-file",
+                    "explain",
+                    "profile",
+                    "expect-typ",
+                ])
+                .help("Decodes every token found in a HAR file exported from a browser (or from stdin with \"-\"), as a timeline"),
             Arg::new("token")
                 .index(1)
                 .allow_hyphen_values(true)
-                .required(true)
-                .help("the JWT as a string (use \"-\" to read from stdin)"),
+                .required_unless_present("har")
+                .help("the JWT as a string (use \"-\" to read from stdin, or the file with the tokens with --batch)"),
         ])
-        .get_matches();
+        .group(
+            ArgGroup::new("redaction")
+                .args(["redact", "redact-token"])
+                .multiple(true),
+        )
+        .group(
+            ArgGroup::new("expiry")
+                .args(["expires-within", "min-remaining"])
+                .conflicts_with_all([
+                    "header",
+                    "full",
+                    "recursive",
+                    "template",
+                    "template-file",
+                    "explain",
+                    "profile",
+                    "expect-typ",
+                ]),
+        );
+    #[cfg(feature = "crypto")]
+    let command = command.args(crypto_args());
+    let matches = command.get_matches();
 
     let should_pretty_print = matches.get_flag("pretty");
+    let should_colorize = match matches.get_one::<String>("color").unwrap().as_str() {
+        "always" => true,
+        "never" => false,
+        _ => io::stdout().is_terminal() && env::var_os("NO_COLOR").map_or(true, |v| v.is_empty()),
+    };
 
-    let mut token = matches.get_one::<String>("token").unwrap().clone();
-    let mut buffer = String::new();
-
-    // if the token is "-" read it from stdin
-    if token == "-" {
-        io::stdin().read_to_string(&mut buffer)?;
-        token = (*buffer.trim()).to_string();
+    #[cfg(feature = "crypto")]
+    if let Some(("dpop", sub_matches)) = matches.subcommand() {
+        return run_dpop(sub_matches, should_colorize, should_pretty_print);
+    }
+    #[cfg(feature = "crypto")]
+    if let Some(("jwk", sub_matches)) = matches.subcommand() {
+        return run_jwk(sub_matches, should_colorize, should_pretty_print);
+    }
+    #[cfg(feature = "server")]
+    if let Some(("serve", sub_matches)) = matches.subcommand() {
+        return run_serve(sub_matches);
+    }
+    #[cfg(feature = "server")]
+    if let Some(("gateway", sub_matches)) = matches.subcommand() {
+        return run_gateway(sub_matches);
+    }
+    #[cfg(feature = "server")]
+    if let Some(("issuer", sub_matches)) = matches.subcommand() {
+        return run_issuer(sub_matches);
+    }
+    if let Some(("watch", sub_matches)) = matches.subcommand() {
+        return run_watch(sub_matches);
     }
 
-    let jwt_token = match jwt::parse(token) {
-        Ok(t) => t,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            process::exit(1);
-        }
-    };
+    if let Some(path) = matches.get_one::<String>("har") {
+        let redactor = redactor_from_matches(&matches);
+        return run_har(
+            path,
+            redactor.as_ref(),
+            should_colorize,
+            should_pretty_print,
+        );
+    }
 
-    let stringified = if matches.get_flag("full") {
-        // Show both header and claims
-        let full_output = serde_json::json!({
-            "header": jwt_token.header,
-            "claims": jwt_token.body
-        });
-        if should_pretty_print {
-            to_string_pretty(&full_output)?
-        } else {
-            full_output.to_string()
-        }
-    } else {
-        // Show either header or body
-        let part = if matches.get_flag("header") {
-            jwt_token.header
-        } else {
-            jwt_token.body
-        };
-        if should_pretty_print {
-            to_string_pretty(&part)?
-        } else {
-            part.to_string()
-        }
-    };
+    let options = Options::from_matches(&matches, should_colorize, should_pretty_print)?;
+    let input = matches.get_one::<String>("token").unwrap();
+    let now = matches
+        .get_one::<u64>("at")
+        .copied()
+        .unwrap_or_else(timestamp::now);
+    if matches.get_flag("batch") {
+        let jobs = matches
+            .get_one::<usize>("jobs")
+            .copied()
+            .unwrap_or_else(batch::default_jobs);
+        return run_batch(input, &options, jobs, now);
+    }
 
-    println!("{}", stringified);
+    // if the token is "-" read it from stdin (CWTs can be provided in binary form)
+    let raw = read_input(input)?;
+    let report = inspect(&raw, &options, now).unwrap_or_else(|e| fail(e));
+    print!("{}", report.output);
+    if report.code != 0 {
+        process::exit(report.code);
+    }
 
     Ok(())
 }
@@ -0,0 +1,155 @@
+//! # Color
+//!
+//! `color` renders JSON values (the header or the claims of a token) with ANSI
+//! escape sequences, so that they are easier to read in a terminal.
+//!
+//! Keys, strings, numbers, booleans and nulls get their own colour. Numeric
+//! time claims (`exp`, `nbf`, `iat` and `auth_time`) are coloured according to
+//! their state with respect to the current time: expired (red), in the future
+//! (yellow) or valid (green).
+//!
+//! Once the escape sequences are removed, the output is identical to the one
+//! produced by `serde_json::to_string` (or `serde_json::to_string_pretty`).
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::color;
+//!
+//! let claims = serde_json::json!({"exp": 1000, "sub": "1234567890"});
+//! let colored = color::colorize(&claims, false, 2000);
+//! assert_eq!(
+//!     colored,
+//!     "{\u{1b}[1;34m\"exp\"\u{1b}[0m:\u{1b}[1;31m1000\u{1b}[0m,\u{1b}[1;34m\"sub\"\u{1b}[0m:\u{1b}[32m\"1234567890\"\u{1b}[0m}"
+//! );
+//! ```
+
+use serde_json::Value;
+
+const RESET: &str = "\u{1b}[0m";
+const KEY: &str = "\u{1b}[1;34m";
+const STRING: &str = "\u{1b}[32m";
+const NUMBER: &str = "\u{1b}[36m";
+const BOOLEAN: &str = "\u{1b}[33m";
+const NULL: &str = "\u{1b}[90m";
+const EXPIRED: &str = "\u{1b}[1;31m";
+const FUTURE: &str = "\u{1b}[1;33m";
+const VALID: &str = "\u{1b}[1;32m";
+
+/// The state of a time claim with respect to a given instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeState {
+    /// The instant described by the claim makes the token no longer valid
+    Expired,
+    /// The instant described by the claim has not been reached yet
+    Future,
+    /// The claim does not prevent the token from being used
+    Valid,
+}
+
+/// Classifies a time claim (`exp`, `nbf`, `iat` or `auth_time`) against `now`
+///
+/// Both `value` and `now` are expressed in seconds since the Unix epoch.
+/// Returns `None` if `name` is not a time claim.
+pub fn time_state(name: &str, value: f64, now: u64) -> Option<TimeState> {
+    let now = now as f64;
+    match name {
+        "exp" if value <= now => Some(TimeState::Expired),
+        "exp" => Some(TimeState::Valid),
+        "nbf" | "iat" | "auth_time" if value > now => Some(TimeState::Future),
+        "nbf" | "iat" | "auth_time" => Some(TimeState::Valid),
+        _ => None,
+    }
+}
+
+/// Renders a JSON value with ANSI colours
+///
+/// `now` (seconds since the Unix epoch) is used to decide how time claims
+/// should be coloured. When `pretty` is `true` the output is indented like
+/// `serde_json::to_string_pretty` would do.
+pub fn colorize(value: &Value, pretty: bool, now: u64) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, None, pretty, 0, now);
+    out
+}
+
+#[doc(hidden)]
+fn paint(out: &mut String, color: &str, text: &str) {
+    out.push_str(color);
+    out.push_str(text);
+    out.push_str(RESET);
+}
+
+#[doc(hidden)]
+fn newline(out: &mut String, pretty: bool, depth: usize) {
+    if pretty {
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth));
+    }
+}
+
+#[doc(hidden)]
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    key: Option<&str>,
+    pretty: bool,
+    depth: usize,
+    now: u64,
+) {
+    match value {
+        Value::Null => paint(out, NULL, "null"),
+        Value::Bool(b) => paint(out, BOOLEAN, &b.to_string()),
+        Value::Number(n) => {
+            let state = key.and_then(|k| n.as_f64().and_then(|v| time_state(k, v, now)));
+            let color = match state {
+                Some(TimeState::Expired) => EXPIRED,
+                Some(TimeState::Future) => FUTURE,
+                Some(TimeState::Valid) => VALID,
+                None => NUMBER,
+            };
+            paint(out, color, &n.to_string())
+        }
+        Value::String(_) => paint(out, STRING, &value.to_string()),
+        Value::Array(items) => {
+            if items.is_empty() {
+                out.push_str("[]");
+                return;
+            }
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, pretty, depth + 1);
+                write_value(out, item, None, pretty, depth + 1, now);
+            }
+            newline(out, pretty, depth);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            if map.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+            out.push('{');
+            for (i, (k, v)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, pretty, depth + 1);
+                paint(out, KEY, &Value::String(k.clone()).to_string());
+                out.push(':');
+                if pretty {
+                    out.push(' ');
+                }
+                write_value(out, v, Some(k), pretty, depth + 1, now);
+            }
+            newline(out, pretty, depth);
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
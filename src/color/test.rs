@@ -0,0 +1,79 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[test]
+fn assert_colorize_matches_compact_json_once_stripped() {
+    let value = serde_json::json!({
+        "sub": "1234567890",
+        "name": "John \"Doe\"",
+        "admin": true,
+        "picture": null,
+        "roles": ["a", "b"],
+        "empty": {},
+        "none": [],
+        "nested": {"exp": 1.5, "n": -3}
+    });
+    let colored = colorize(&value, false, 0);
+    assert_eq!(strip_ansi(&colored), value.to_string());
+}
+
+#[test]
+fn assert_colorize_matches_pretty_json_once_stripped() {
+    let value = serde_json::json!({
+        "sub": "1234567890",
+        "roles": ["a", {"b": [1, 2]}],
+        "empty": {},
+        "none": []
+    });
+    let colored = colorize(&value, true, 0);
+    assert_eq!(
+        strip_ansi(&colored),
+        serde_json::to_string_pretty(&value).unwrap()
+    );
+}
+
+#[test]
+fn assert_time_claims_are_colored_by_state() {
+    let value = serde_json::json!({"exp": 1000, "nbf": 3000, "iat": 1000});
+    let colored = colorize(&value, false, 2000);
+    assert!(colored.contains("\u{1b}[1;31m1000\u{1b}[0m"));
+    assert!(colored.contains("\u{1b}[1;33m3000\u{1b}[0m"));
+    assert!(colored.contains("\u{1b}[1;32m1000\u{1b}[0m"));
+}
+
+#[test]
+fn assert_time_state() {
+    assert_eq!(time_state("exp", 10.0, 10), Some(TimeState::Expired));
+    assert_eq!(time_state("exp", 11.0, 10), Some(TimeState::Valid));
+    assert_eq!(time_state("nbf", 11.0, 10), Some(TimeState::Future));
+    assert_eq!(time_state("nbf", 10.0, 10), Some(TimeState::Valid));
+    assert_eq!(time_state("auth_time", 9.0, 10), Some(TimeState::Valid));
+    assert_eq!(time_state("sub", 9.0, 10), None);
+}
+
+#[test]
+fn assert_non_time_numbers_use_number_color() {
+    let value = serde_json::json!({"count": 5});
+    assert_eq!(
+        colorize(&value, false, 0),
+        "{\u{1b}[1;34m\"count\"\u{1b}[0m:\u{1b}[36m5\u{1b}[0m}"
+    );
+}
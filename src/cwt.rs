@@ -0,0 +1,418 @@
+//! # CWT
+//!
+//! `cwt` is a collection of utilities to inspect [CBOR Web Tokens](https://datatracker.ietf.org/doc/html/rfc8392)
+//! (CWTs), the CBOR counterpart of JWTs used by constrained devices.
+//!
+//! A CWT is a `COSE_Sign1`, `COSE_Mac0` or `COSE_Encrypt0` structure
+//! ([RFC 9052](https://datatracker.ietf.org/doc/html/rfc9052)), optionally
+//! wrapped in the CWT CBOR tag (61). The protected and unprotected headers and
+//! the claims are converted to JSON, mapping the registered integer labels to
+//! their names (e.g. `1` becomes `iss` in the claims and `alg` in the header,
+//! and the algorithm `-7` becomes `ES256`). Byte strings are base64url encoded.
+//!
+//! No signature or MAC is verified and encrypted payloads are not decrypted.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::cwt;
+//!
+//! // COSE_Sign1 with protected header {1: -7} (alg: ES256),
+//! // unprotected header {4: h'6b6964'} (kid) and claims {1: "iss", 4: 1444064944}
+//! let bytes = cwt::decode_input("d28443a10126a104436b69644ca20163697373041a5612aeb04100").unwrap();
+//! let token = cwt::parse(&bytes).unwrap();
+//! assert_eq!(token.header().to_string(), "{\"alg\":\"ES256\",\"kid\":\"a2lk\"}");
+//! assert_eq!(token.claims.to_string(), "{\"exp\":1444064944,\"iss\":\"iss\"}");
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use base64::{engine::general_purpose, Engine as _};
+use ciborium::Value as Cbor;
+use serde_json::{Map, Value};
+
+use crate::jwt::get_base64;
+
+/// The CBOR tag of a CWT
+const CWT_TAG: u64 = 61;
+
+/// Represents an error while parsing a CWT
+#[derive(Debug)]
+pub enum CwtError {
+    /// Indicates that the data is not valid CBOR
+    InvalidCbor(String),
+    /// Indicates that the CBOR data does not have the structure of a COSE message
+    InvalidStructure(&'static str),
+    /// Indicates that the COSE message uses an unsupported tag
+    UnsupportedTag(u64),
+}
+
+impl fmt::Display for CwtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            CwtError::InvalidCbor(e) => format!("CBOR error, {}", e),
+            CwtError::InvalidStructure(e) => format!("Invalid COSE structure, {}", e),
+            CwtError::UnsupportedTag(tag) => format!("Unsupported CBOR tag {}", tag),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for CwtError {}
+
+/// The type of COSE structure carrying the claims
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoseKind {
+    /// A signed message with a single signer (tag 18)
+    Sign1,
+    /// A MACed message with implicit key (tag 17)
+    Mac0,
+    /// An encrypted message with implicit key (tag 16)
+    Encrypt0,
+}
+
+impl fmt::Display for CoseKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CoseKind::Sign1 => "COSE_Sign1",
+            CoseKind::Mac0 => "COSE_Mac0",
+            CoseKind::Encrypt0 => "COSE_Encrypt0",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Represents a decoded CWT
+#[derive(Debug)]
+pub struct Cwt {
+    /// the type of COSE structure
+    pub kind: CoseKind,
+    /// the protected header, converted to JSON
+    pub protected: Value,
+    /// the unprotected header, converted to JSON
+    pub unprotected: Value,
+    /// the claims, converted to JSON (a placeholder string for `COSE_Encrypt0`)
+    pub claims: Value,
+    /// the signature or MAC tag (empty for `COSE_Encrypt0`)
+    pub signature: Vec<u8>,
+}
+
+impl Cwt {
+    /// Returns the protected and unprotected header parameters merged in a
+    /// single object (protected parameters take precedence)
+    pub fn header(&self) -> Value {
+        let mut header = Map::new();
+        for part in [&self.unprotected, &self.protected] {
+            if let Value::Object(map) = part {
+                header.extend(map.clone());
+            }
+        }
+        Value::Object(header)
+    }
+
+    /// Returns the CWT as a JSON object with the merged `header`, the `claims`,
+    /// the `protected` and `unprotected` headers and the `cose` structure type
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "cose": self.kind.to_string(),
+            "header": self.header(),
+            "protected": self.protected,
+            "unprotected": self.unprotected,
+            "claims": self.claims,
+        })
+    }
+}
+
+/// Decodes a CWT given as hex, base64 or base64url text
+///
+/// Whitespace is ignored. Returns `None` if the text is none of the supported encodings.
+pub fn decode_input(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.is_empty() {
+        return None;
+    }
+    if compact.len() % 2 == 0 && compact.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return (0..compact.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&compact[i..i + 2], 16).ok())
+            .collect();
+    }
+    let unpadded = compact.trim_end_matches('=');
+    get_base64()
+        .decode(unpadded)
+        .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(unpadded))
+        .ok()
+}
+
+/// Returns `true` if some bytes start like a (possibly tagged) COSE message
+///
+/// This is a cheap check on the first byte: a CBOR tag between 16 and 18, the
+/// CWT tag (61), or an array of 3 or 4 elements.
+pub fn looks_like_cwt(bytes: &[u8]) -> bool {
+    matches!(
+        bytes,
+        [0xd0..=0xd2, ..] | [0xd8, 0x3d, ..] | [0x83, ..] | [0x84, ..]
+    )
+}
+
+#[doc(hidden)]
+fn alg_name(alg: i128) -> Option<&'static str> {
+    let name = match alg {
+        -259 => "RS512",
+        -258 => "RS384",
+        -257 => "RS256",
+        -53 => "Ed448",
+        -47 => "ES256K",
+        -39 => "PS512",
+        -38 => "PS384",
+        -37 => "PS256",
+        -36 => "ES512",
+        -35 => "ES384",
+        -19 => "Ed25519",
+        -8 => "EdDSA",
+        -7 => "ES256",
+        1 => "A128GCM",
+        2 => "A192GCM",
+        3 => "A256GCM",
+        4 => "HMAC 256/64",
+        5 => "HMAC 256/256",
+        6 => "HMAC 384/384",
+        7 => "HMAC 512/512",
+        10 => "AES-CCM-16-64-128",
+        11 => "AES-CCM-16-64-256",
+        12 => "AES-CCM-64-64-128",
+        13 => "AES-CCM-64-64-256",
+        14 => "AES-MAC 128/64",
+        15 => "AES-MAC 256/64",
+        24 => "ChaCha20/Poly1305",
+        25 => "AES-MAC 128/128",
+        26 => "AES-MAC 256/128",
+        30 => "AES-CCM-16-128-128",
+        31 => "AES-CCM-16-128-256",
+        32 => "AES-CCM-64-128-128",
+        33 => "AES-CCM-64-128-256",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[doc(hidden)]
+fn header_label(label: i128) -> Option<&'static str> {
+    let name = match label {
+        1 => "alg",
+        2 => "crit",
+        3 => "content type",
+        4 => "kid",
+        5 => "IV",
+        6 => "Partial IV",
+        7 => "counter signature",
+        9 => "CounterSignature0",
+        10 => "kid context",
+        15 => "CWT Claims",
+        16 => "typ",
+        32 => "x5bag",
+        33 => "x5chain",
+        34 => "x5t",
+        35 => "x5u",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[doc(hidden)]
+fn claim_label(label: i128) -> Option<&'static str> {
+    let name = match label {
+        1 => "iss",
+        2 => "sub",
+        3 => "aud",
+        4 => "exp",
+        5 => "nbf",
+        6 => "iat",
+        7 => "cti",
+        8 => "cnf",
+        9 => "scope",
+        10 => "eat_nonce",
+        38 => "ace_profile",
+        39 => "cnonce",
+        40 => "exi",
+        256 => "ueid",
+        257 => "sueid",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Converts a CBOR value to JSON, without any label mapping
+#[doc(hidden)]
+fn to_json(value: &Cbor) -> Value {
+    match value {
+        Cbor::Integer(i) => {
+            let i = i128::from(*i);
+            i64::try_from(i)
+                .map(Value::from)
+                .or_else(|_| u64::try_from(i).map(Value::from))
+                .unwrap_or_else(|_| Value::String(i.to_string()))
+        }
+        Cbor::Bytes(bytes) => Value::String(get_base64().encode(bytes)),
+        Cbor::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        Cbor::Text(text) => Value::String(text.clone()),
+        Cbor::Bool(b) => Value::Bool(*b),
+        Cbor::Null => Value::Null,
+        Cbor::Tag(_, inner) => to_json(inner),
+        Cbor::Array(items) => Value::Array(items.iter().map(to_json).collect()),
+        Cbor::Map(entries) => map_to_json(entries, |_| None),
+        _ => Value::Null,
+    }
+}
+
+/// Converts a CBOR map to a JSON object, naming integer keys with `label`
+#[doc(hidden)]
+fn map_to_json<F: Fn(i128) -> Option<&'static str>>(entries: &[(Cbor, Cbor)], label: F) -> Value {
+    let mut map = Map::new();
+    for (key, value) in entries {
+        let key = match key {
+            Cbor::Text(text) => text.clone(),
+            Cbor::Integer(i) => {
+                let i = i128::from(*i);
+                label(i).map_or_else(|| i.to_string(), str::to_string)
+            }
+            other => to_json(other).to_string(),
+        };
+        map.insert(key, to_json(value));
+    }
+    Value::Object(map)
+}
+
+#[doc(hidden)]
+fn header_to_json(entries: &[(Cbor, Cbor)]) -> Value {
+    let mut header = map_to_json(entries, header_label);
+    let alg = entries.iter().find_map(|(key, value)| match (key, value) {
+        (Cbor::Integer(k), Cbor::Integer(alg)) if i128::from(*k) == 1 => alg_name(i128::from(*alg)),
+        _ => None,
+    });
+    if let Some(alg) = alg {
+        header["alg"] = Value::String(alg.to_string());
+    }
+    header
+}
+
+#[doc(hidden)]
+fn decode_cbor(bytes: &[u8]) -> Result<Cbor, CwtError> {
+    ciborium::de::from_reader(bytes).map_err(|e| CwtError::InvalidCbor(e.to_string()))
+}
+
+#[doc(hidden)]
+fn decode_protected(value: &Cbor) -> Result<Vec<(Cbor, Cbor)>, CwtError> {
+    match value {
+        Cbor::Bytes(bytes) if bytes.is_empty() => Ok(Vec::new()),
+        Cbor::Bytes(bytes) => match decode_cbor(bytes)? {
+            Cbor::Map(entries) => Ok(entries),
+            _ => Err(CwtError::InvalidStructure(
+                "the protected header is not a map",
+            )),
+        },
+        _ => Err(CwtError::InvalidStructure(
+            "the protected header is not a byte string",
+        )),
+    }
+}
+
+#[doc(hidden)]
+fn is_mac_algorithm(protected: &[(Cbor, Cbor)]) -> bool {
+    protected.iter().any(|(key, value)| match (key, value) {
+        (Cbor::Integer(k), Cbor::Integer(alg)) if i128::from(*k) == 1 => {
+            matches!(i128::from(*alg), 4..=7 | 14 | 15 | 25 | 26)
+        }
+        _ => false,
+    })
+}
+
+/// Parses a CWT from its binary (CBOR) representation
+///
+/// # Errors
+///
+/// This function will return a `CwtError` if the data is not valid CBOR or is
+/// not a `COSE_Sign1`, `COSE_Mac0` or `COSE_Encrypt0` structure
+pub fn parse(bytes: &[u8]) -> Result<Cwt, CwtError> {
+    let mut value = decode_cbor(bytes)?;
+    let mut tag = None;
+    while let Cbor::Tag(t, inner) = value {
+        match t {
+            CWT_TAG => {}
+            16..=18 => tag = Some(t),
+            other => return Err(CwtError::UnsupportedTag(other)),
+        }
+        value = *inner;
+    }
+
+    let items = match value {
+        Cbor::Array(items) => items,
+        _ => return Err(CwtError::InvalidStructure("expected an array")),
+    };
+    let protected = decode_protected(
+        items
+            .first()
+            .ok_or(CwtError::InvalidStructure("missing protected header"))?,
+    )?;
+    let unprotected = match items.get(1) {
+        Some(Cbor::Map(entries)) => entries.clone(),
+        _ => {
+            return Err(CwtError::InvalidStructure(
+                "the unprotected header is not a map",
+            ))
+        }
+    };
+
+    let kind = match (tag, items.len()) {
+        (Some(18), 4) => CoseKind::Sign1,
+        (Some(17), 4) => CoseKind::Mac0,
+        (Some(16), 3) => CoseKind::Encrypt0,
+        (None, 4) if is_mac_algorithm(&protected) => CoseKind::Mac0,
+        (None, 4) => CoseKind::Sign1,
+        (None, 3) => CoseKind::Encrypt0,
+        _ => return Err(CwtError::InvalidStructure("unexpected number of elements")),
+    };
+
+    let (claims, signature) = match kind {
+        CoseKind::Encrypt0 => (
+            Value::String("<encrypted COSE_Encrypt0 payload>".to_string()),
+            Vec::new(),
+        ),
+        CoseKind::Sign1 | CoseKind::Mac0 => {
+            let claims = match &items[2] {
+                Cbor::Bytes(payload) => match decode_cbor(payload)? {
+                    Cbor::Map(entries) => map_to_json(&entries, claim_label),
+                    _ => return Err(CwtError::InvalidStructure("the claims are not a map")),
+                },
+                Cbor::Null => Value::String("<detached payload>".to_string()),
+                _ => {
+                    return Err(CwtError::InvalidStructure(
+                        "the payload is not a byte string",
+                    ))
+                }
+            };
+            let signature = match &items[3] {
+                Cbor::Bytes(signature) => signature.clone(),
+                _ => {
+                    return Err(CwtError::InvalidStructure(
+                        "the signature is not a byte string",
+                    ))
+                }
+            };
+            (claims, signature)
+        }
+    };
+
+    Ok(Cwt {
+        kind,
+        protected: header_to_json(&protected),
+        unprotected: header_to_json(&unprotected),
+        claims,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,128 @@
+#[cfg(test)]
+use super::*;
+
+// The claims of RFC 8392, Appendix A.1, as a CBOR map
+#[doc(hidden)]
+const CLAIMS: &str = "a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b71";
+
+#[doc(hidden)]
+fn sign1() -> String {
+    // protected {1: -7}, unprotected {4: "AsymmetricECDSA256"}, 64 bytes signature
+    format!(
+        "d28443a10126a104524173796d6d657472696345434453413235365850{}5840{}",
+        CLAIMS,
+        "ab".repeat(64)
+    )
+}
+
+#[doc(hidden)]
+fn expected_claims() -> serde_json::Value {
+    serde_json::json!({
+        "iss": "coap://as.example.com",
+        "sub": "erikw",
+        "aud": "coap://light.example.com",
+        "exp": 1444064944,
+        "nbf": 1443944944,
+        "iat": 1443944944,
+        "cti": "C3E"
+    })
+}
+
+#[test]
+fn assert_parse_sign1() {
+    let cwt = parse(&decode_input(&sign1()).unwrap()).unwrap();
+    assert_eq!(cwt.kind, CoseKind::Sign1);
+    assert_eq!(cwt.protected, serde_json::json!({"alg": "ES256"}));
+    assert_eq!(
+        cwt.unprotected,
+        serde_json::json!({"kid": "QXN5bW1ldHJpY0VDRFNBMjU2"})
+    );
+    assert_eq!(cwt.claims, expected_claims());
+    assert_eq!(cwt.signature, vec![0xab; 64]);
+    assert_eq!(
+        cwt.to_json()["header"],
+        serde_json::json!({"alg": "ES256", "kid": "QXN5bW1ldHJpY0VDRFNBMjU2"})
+    );
+    assert_eq!(cwt.to_json()["cose"], "COSE_Sign1");
+}
+
+#[test]
+fn assert_parse_tagged_mac0() {
+    // CWT tag (61) wrapping a COSE_Mac0 tag (17) with alg "HMAC 256/64"
+    let hex = format!("d83dd18443a10104a05850{}48{}", CLAIMS, "cd".repeat(8));
+    let cwt = parse(&decode_input(&hex).unwrap()).unwrap();
+    assert_eq!(cwt.kind, CoseKind::Mac0);
+    assert_eq!(cwt.protected, serde_json::json!({"alg": "HMAC 256/64"}));
+    assert_eq!(cwt.claims, expected_claims());
+}
+
+#[test]
+fn assert_parse_untagged_mac0_from_algorithm() {
+    let hex = format!("8443a10104a05850{}48{}", CLAIMS, "cd".repeat(8));
+    let cwt = parse(&decode_input(&hex).unwrap()).unwrap();
+    assert_eq!(cwt.kind, CoseKind::Mac0);
+}
+
+#[test]
+fn assert_parse_encrypt0() {
+    // protected {1: 1} (A128GCM), unprotected {5: IV}
+    let hex = "d08343a10101a1054c010101010101010101010101581002020202020202020202020202020202";
+    let cwt = parse(&decode_input(hex).unwrap()).unwrap();
+    assert_eq!(cwt.kind, CoseKind::Encrypt0);
+    assert_eq!(cwt.protected, serde_json::json!({"alg": "A128GCM"}));
+    assert_eq!(
+        cwt.unprotected,
+        serde_json::json!({"IV": "AQEBAQEBAQEBAQEB"})
+    );
+    assert_eq!(cwt.claims, "<encrypted COSE_Encrypt0 payload>");
+}
+
+#[test]
+fn assert_decode_input_encodings() {
+    let bytes = decode_input(&sign1()).unwrap();
+    let base64url = get_base64().encode(&bytes);
+    let base64 = general_purpose::STANDARD.encode(&bytes);
+    assert_eq!(decode_input(&base64url).unwrap(), bytes);
+    assert_eq!(decode_input(&base64).unwrap(), bytes);
+    assert_eq!(decode_input(" d2 84\n43 ").unwrap(), vec![0xd2, 0x84, 0x43]);
+    assert!(decode_input("not valid!").is_none());
+    assert!(decode_input("").is_none());
+}
+
+#[test]
+fn assert_looks_like_cwt() {
+    assert!(looks_like_cwt(&[0xd2, 0x84]));
+    assert!(looks_like_cwt(&[0xd8, 0x3d, 0xd2]));
+    assert!(looks_like_cwt(&[0x84]));
+    assert!(!looks_like_cwt(b"{\"alg\""));
+    assert!(!looks_like_cwt(&[]));
+}
+
+#[test]
+fn assert_unknown_labels_are_kept_as_numbers() {
+    // Sign1 with protected {1: -7, 99: 1}, empty unprotected and claims {500: "x", "name": true}
+    let hex = "d28446a20126186301a04ca21901f46178646e616d65f54100";
+    let cwt = parse(&decode_input(hex).unwrap()).unwrap();
+    assert_eq!(cwt.protected, serde_json::json!({"alg": "ES256", "99": 1}));
+    assert_eq!(cwt.claims, serde_json::json!({"500": "x", "name": true}));
+}
+
+#[test]
+fn assert_parse_fails_with_invalid_data() {
+    assert!(parse(&[0xff])
+        .unwrap_err()
+        .to_string()
+        .starts_with("CBOR error"));
+    assert_eq!(
+        parse(&[0xd8, 0x20, 0x80]).unwrap_err().to_string(),
+        "Unsupported CBOR tag 32"
+    );
+    assert_eq!(
+        parse(&[0x82, 0x40, 0xa0]).unwrap_err().to_string(),
+        "Invalid COSE structure, unexpected number of elements"
+    );
+    assert_eq!(
+        parse(&[0xa0]).unwrap_err().to_string(),
+        "Invalid COSE structure, expected an array"
+    );
+}
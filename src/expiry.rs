@@ -0,0 +1,136 @@
+//! # Expiry
+//!
+//! `expiry` tells whether a token is about to expire, for scripts that renew
+//! long-lived tokens before it's too late (cron jobs, health probes, ...).
+//!
+//! The `exp` claim is compared with the current time and a minimum remaining
+//! lifetime: the token is valid, expiring soon, expired, or has no `exp` claim.
+//! Each status has its own exit code, returned by `Status::exit_code`. The codes
+//! start at 10, away from the codes of errors (`1`) and usage errors (`2`).
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::expiry;
+//!
+//! let claims = serde_json::json!({"exp": 1516242622});
+//! let expiry = expiry::check(&claims, 3600, 1516239022);
+//! assert_eq!(expiry.status, expiry::Status::Ok);
+//! assert_eq!(expiry.to_string(), "OK: the token expires in 1 hour (2018-01-18T02:30:22Z)");
+//!
+//! let expiry = expiry::check(&claims, 2 * 3600, 1516239022);
+//! assert_eq!(expiry.status, expiry::Status::ExpiringSoon);
+//! assert_eq!(expiry.status.exit_code(), 10);
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::timestamp;
+
+/// The exit code of a token that is valid for long enough
+pub const EXIT_OK: i32 = 0;
+
+/// The exit code of a token that expires before the minimum remaining lifetime
+pub const EXIT_EXPIRING_SOON: i32 = 10;
+
+/// The exit code of an expired token
+pub const EXIT_EXPIRED: i32 = 11;
+
+/// The exit code of a token without a numeric `exp` claim
+pub const EXIT_NO_EXP: i32 = 12;
+
+/// The expiration status of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// the token is valid for at least the minimum remaining lifetime
+    Ok,
+    /// the token is still valid, but for less than the minimum remaining lifetime
+    ExpiringSoon,
+    /// the token is expired
+    Expired,
+    /// the token has no numeric `exp` claim
+    NoExpiration,
+}
+
+impl Status {
+    /// Returns the exit code of the status (`EXIT_OK`, `EXIT_EXPIRING_SOON`,
+    /// `EXIT_EXPIRED` or `EXIT_NO_EXP`)
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Status::Ok => EXIT_OK,
+            Status::ExpiringSoon => EXIT_EXPIRING_SOON,
+            Status::Expired => EXIT_EXPIRED,
+            Status::NoExpiration => EXIT_NO_EXP,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Status::Ok => "OK",
+            Status::ExpiringSoon => "EXPIRING SOON",
+            Status::Expired => "EXPIRED",
+            Status::NoExpiration => "NO EXP",
+        })
+    }
+}
+
+/// The result of an expiration check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expiry {
+    /// the status of the token
+    pub status: Status,
+    /// the `exp` claim, if numeric
+    pub exp: Option<i64>,
+    /// the time of the check, in seconds since the Unix epoch
+    pub now: u64,
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let exp = match self.exp {
+            Some(exp) => exp,
+            None => return write!(f, "{}: the token has no numeric exp claim", self.status),
+        };
+        let now = i64::try_from(self.now).unwrap_or(i64::MAX);
+        let relative = timestamp::format_relative(exp, now);
+        let date = timestamp::format_iso8601(exp);
+        if self.status == Status::Expired {
+            write!(
+                f,
+                "{}: the token expired {} ({})",
+                self.status, relative, date
+            )
+        } else {
+            write!(
+                f,
+                "{}: the token expires {} ({})",
+                self.status, relative, date
+            )
+        }
+    }
+}
+
+/// Checks that a token is valid for at least `min_remaining` seconds after `now`
+///
+/// A token is expired from its `exp` time on, and expiring soon when less
+/// than `min_remaining` seconds are left.
+pub fn check(claims: &Value, min_remaining: u64, now: u64) -> Expiry {
+    let exp = claims.get("exp").and_then(timestamp::numeric_date);
+    // in i128: `exp` and `now` cover the ranges of an i64 and of a u64
+    let remaining = exp.map(|exp| i128::from(exp) - i128::from(now));
+    let status = match remaining {
+        None => Status::NoExpiration,
+        Some(remaining) if remaining <= 0 => Status::Expired,
+        Some(remaining) if remaining < i128::from(min_remaining) => Status::ExpiringSoon,
+        Some(_) => Status::Ok,
+    };
+    Expiry { status, exp, now }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,75 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+const NOW: u64 = 1516239022;
+
+#[test]
+fn assert_check_statuses() {
+    let claims = serde_json::json!({"exp": NOW + 600});
+    assert_eq!(check(&claims, 0, NOW).status, Status::Ok);
+    assert_eq!(check(&claims, 600, NOW).status, Status::Ok);
+    assert_eq!(check(&claims, 601, NOW).status, Status::ExpiringSoon);
+    assert_eq!(check(&claims, 0, NOW + 599).status, Status::Ok);
+    assert_eq!(check(&claims, 0, NOW + 600).status, Status::Expired);
+    assert_eq!(
+        check(
+            &serde_json::json!({"exp": (NOW + 60) as f64 + 0.5}),
+            60,
+            NOW
+        )
+        .status,
+        Status::Ok
+    );
+    assert_eq!(
+        check(&serde_json::json!({"exp": "tomorrow"}), 0, NOW).status,
+        Status::NoExpiration
+    );
+    // extreme claims and times don't overflow
+    let claims = serde_json::json!({"exp": i64::MAX});
+    assert_eq!(check(&claims, 60, u64::MAX).status, Status::Expired);
+    assert_eq!(check(&claims, i64::MAX as u64, 0).status, Status::Ok);
+    assert_eq!(check(&claims, u64::MAX, 0).status, Status::ExpiringSoon);
+    assert_eq!(
+        check(&claims, u64::MAX, i64::MAX as u64 - 1).status,
+        Status::ExpiringSoon
+    );
+    let claims = serde_json::json!({"exp": i64::MIN});
+    assert_eq!(check(&claims, u64::MAX, u64::MAX).status, Status::Expired);
+    assert!(check(&claims, 0, u64::MAX)
+        .to_string()
+        .starts_with("EXPIRED: the token expired "));
+    // encrypted tokens have no readable claims
+    assert_eq!(
+        check(&Value::from("<encrypted JWE body>"), 0, NOW).status,
+        Status::NoExpiration
+    );
+}
+
+#[test]
+fn assert_exit_codes_and_messages() {
+    let codes: Vec<i32> = [
+        Status::Ok,
+        Status::ExpiringSoon,
+        Status::Expired,
+        Status::NoExpiration,
+    ]
+    .iter()
+    .map(|status| status.exit_code())
+    .collect();
+    assert_eq!(codes, vec![0, 10, 11, 12]);
+
+    let claims = serde_json::json!({"exp": NOW});
+    assert_eq!(
+        check(&claims, 0, NOW + 7200).to_string(),
+        "EXPIRED: the token expired 2 hours ago (2018-01-18T01:30:22Z)"
+    );
+    assert_eq!(
+        check(&claims, 3600, NOW - 300).to_string(),
+        "EXPIRING SOON: the token expires in 5 minutes (2018-01-18T01:30:22Z)"
+    );
+    assert_eq!(
+        check(&serde_json::json!({}), 0, NOW).to_string(),
+        "NO EXP: the token has no numeric exp claim"
+    );
+}
@@ -0,0 +1,174 @@
+//! # Explain
+//!
+//! `explain` describes the meaning of the header parameters and claims of a
+//! token, so that cryptic names such as `tid`, `wids` or `token_use` do not
+//! have to be looked up by hand.
+//!
+//! The descriptions come from a bundled copy of the IANA "JSON Web Token
+//! Claims" and "JSON Web Signature and Encryption Header Parameters"
+//! registries, plus curated dictionaries for common identity providers (Azure
+//! AD / Entra ID, AWS Cognito, Google, Auth0, Keycloak, GitHub Actions and
+//! Kubernetes service accounts).
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::explain::{self, Location, Source};
+//!
+//! let definitions = explain::lookup(Location::Claims, "tid");
+//! assert_eq!(definitions[0].source, Source::Entra);
+//!
+//! let claims = serde_json::json!({"sub": "1234567890"});
+//! assert_eq!(
+//!     explain::explain(Location::Claims, &claims, None, 0),
+//!     "claims:\n  sub: \"1234567890\"\n    Subject: the principal (usually a user or a client) the token is about (RFC 7519) [IANA]\n"
+//! );
+//! ```
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::timestamp;
+
+mod dictionary;
+
+/// Where a name is found: in the header or in the claims of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// A header parameter
+    Header,
+    /// A claim
+    Claims,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Location::Header => "header",
+            Location::Claims => "claims",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The dictionary a definition comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// The IANA JWT claims and JOSE header parameters registries
+    Iana,
+    /// Azure AD / Microsoft Entra ID
+    Entra,
+    /// AWS Cognito user pools
+    Cognito,
+    /// Google (Google identity, Google Cloud and Firebase)
+    Google,
+    /// Auth0
+    Auth0,
+    /// Keycloak
+    Keycloak,
+    /// GitHub Actions OIDC tokens
+    GithubActions,
+    /// Kubernetes service account tokens
+    Kubernetes,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Source::Iana => "IANA",
+            Source::Entra => "Azure AD / Entra ID",
+            Source::Cognito => "AWS Cognito",
+            Source::Google => "Google",
+            Source::Auth0 => "Auth0",
+            Source::Keycloak => "Keycloak",
+            Source::GithubActions => "GitHub Actions",
+            Source::Kubernetes => "Kubernetes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The description of a header parameter or claim, in one dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Definition {
+    /// the dictionary the description comes from
+    pub source: Source,
+    /// a human readable description of the header parameter or claim
+    pub description: &'static str,
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]", self.description, self.source)
+    }
+}
+
+/// Returns the definitions of a header parameter or claim, IANA first
+///
+/// A name can have several definitions (e.g. `roles` is registered by IANA
+/// and has a specific meaning in Entra ID tokens). Returns an empty `Vec` if
+/// the name is not known.
+pub fn lookup(location: Location, name: &str) -> Vec<Definition> {
+    dictionary::DICTIONARIES
+        .iter()
+        .filter(|d| d.location == location)
+        .filter_map(|d| {
+            d.entries
+                .iter()
+                .find(|(entry, _)| match entry.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => *entry == name,
+                })
+                .map(|(_, description)| Definition {
+                    source: d.source,
+                    description,
+                })
+        })
+        .collect()
+}
+
+/// Describes every member of a header or claims object, one per line with its
+/// value (and the date of numeric time claims) followed by its definitions
+///
+/// If the provider that minted the token is known (see `provider::detect`),
+/// only the IANA definitions and the ones of `provider` are shown.
+pub fn explain(location: Location, object: &Value, provider: Option<Source>, now: u64) -> String {
+    let mut output = format!("{}:\n", location);
+    let members = match object.as_object() {
+        Some(members) => members,
+        None => {
+            output.push_str(&format!("  {}\n", object));
+            return output;
+        }
+    };
+    for (name, value) in members {
+        output.push_str(&format!("  {}: {}", name, value));
+        let is_time = matches!(
+            name.as_str(),
+            "exp" | "nbf" | "iat" | "auth_time" | "updated_at" | "toe"
+        );
+        if let (true, Some(secs)) = (is_time && location == Location::Claims, value.as_i64()) {
+            output.push_str(&format!(
+                " ({}, {})",
+                timestamp::format_iso8601(secs),
+                timestamp::format_relative(secs, now as i64)
+            ));
+        }
+        output.push('\n');
+        let definitions: Vec<Definition> = lookup(location, name)
+            .into_iter()
+            .filter(|d| provider.map_or(true, |p| d.source == Source::Iana || d.source == p))
+            .collect();
+        if definitions.is_empty() {
+            output.push_str("    unknown, not in the bundled dictionaries\n");
+        }
+        for definition in definitions {
+            output.push_str(&format!("    {}\n", definition));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,417 @@
+//! The bundled dictionaries used by `explain`.
+//!
+//! The IANA tables are a copy of the "JSON Web Token Claims" and "JSON Web
+//! Signature and Encryption Header Parameters" registries. The other tables
+//! describe the proprietary claims of common identity providers. A name ending
+//! with `*` matches every name with that prefix.
+
+use super::{Location, Source};
+
+/// A table of names and their descriptions, for one source and location
+#[doc(hidden)]
+pub(super) struct Dictionary {
+    pub source: Source,
+    pub location: Location,
+    pub entries: &'static [(&'static str, &'static str)],
+}
+
+#[doc(hidden)]
+pub(super) const DICTIONARIES: &[Dictionary] = &[
+    Dictionary {
+        source: Source::Iana,
+        location: Location::Header,
+        entries: IANA_HEADER,
+    },
+    Dictionary {
+        source: Source::Iana,
+        location: Location::Claims,
+        entries: IANA_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Entra,
+        location: Location::Header,
+        entries: ENTRA_HEADER,
+    },
+    Dictionary {
+        source: Source::Entra,
+        location: Location::Claims,
+        entries: ENTRA_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Cognito,
+        location: Location::Claims,
+        entries: COGNITO_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Google,
+        location: Location::Claims,
+        entries: GOOGLE_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Auth0,
+        location: Location::Claims,
+        entries: AUTH0_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Keycloak,
+        location: Location::Claims,
+        entries: KEYCLOAK_CLAIMS,
+    },
+    Dictionary {
+        source: Source::GithubActions,
+        location: Location::Claims,
+        entries: GITHUB_ACTIONS_CLAIMS,
+    },
+    Dictionary {
+        source: Source::Kubernetes,
+        location: Location::Claims,
+        entries: KUBERNETES_CLAIMS,
+    },
+];
+
+#[doc(hidden)]
+const IANA_HEADER: &[(&str, &str)] = &[
+    ("alg", "Algorithm: the algorithm used to sign or encrypt the token (RFC 7515, RFC 7516)"),
+    ("enc", "Encryption Algorithm: the content encryption algorithm of a JWE (RFC 7516)"),
+    ("zip", "Compression Algorithm: the compression applied to the plaintext before encryption (RFC 7516)"),
+    ("jku", "JWK Set URL: a URL of the JWK Set containing the key used to sign the token (RFC 7515)"),
+    ("jwk", "JSON Web Key: the public key used to sign the token (RFC 7515)"),
+    ("kid", "Key ID: a hint of which key was used to sign or encrypt the token (RFC 7515)"),
+    ("x5u", "X.509 URL: a URL of the certificate (chain) of the signing key (RFC 7515)"),
+    ("x5c", "X.509 Certificate Chain: the certificate (chain) of the signing key, base64 DER encoded (RFC 7515)"),
+    ("x5t", "X.509 Certificate SHA-1 Thumbprint: the SHA-1 thumbprint of the signing certificate (RFC 7515)"),
+    ("x5t#S256", "X.509 Certificate SHA-256 Thumbprint: the SHA-256 thumbprint of the signing certificate (RFC 7515)"),
+    ("typ", "Type: the media type of the whole token, e.g. JWT or at+jwt (RFC 7515)"),
+    ("cty", "Content Type: the media type of the payload, JWT for nested tokens (RFC 7515)"),
+    ("crit", "Critical: the header parameters that must be understood by the recipient (RFC 7515)"),
+    ("epk", "Ephemeral Public Key: the ephemeral key of an ECDH-ES key agreement (RFC 7518)"),
+    ("apu", "Agreement PartyUInfo: information about the producer, used by ECDH-ES (RFC 7518)"),
+    ("apv", "Agreement PartyVInfo: information about the recipient, used by ECDH-ES (RFC 7518)"),
+    ("iv", "Initialization Vector: the IV used to wrap the content encryption key with AES GCM (RFC 7518)"),
+    ("tag", "Authentication Tag: the tag of the AES GCM key wrapping (RFC 7518)"),
+    ("p2s", "PBES2 Salt Input: the salt of a password based key derivation (RFC 7518)"),
+    ("p2c", "PBES2 Count: the iteration count of a password based key derivation (RFC 7518)"),
+    ("iss", "Issuer: the issuer, replicated in the header of an encrypted token (RFC 7519)"),
+    ("sub", "Subject: the subject, replicated in the header of an encrypted token (RFC 7519)"),
+    ("aud", "Audience: the audience, replicated in the header of an encrypted token (RFC 7519)"),
+    ("b64", "Base64url-Encode Payload: false if the payload is not base64url encoded (RFC 7797)"),
+    ("ppt", "PASSporT extension identifier (RFC 8225)"),
+    ("url", "URL: the target URL of an ACME request (RFC 8555)"),
+    ("nonce", "Nonce: a server provided nonce, preventing replays of ACME requests (RFC 8555)"),
+    ("svt", "Signature Validation Token: tokens supporting the validation of the signature (RFC 9321)"),
+    ("trust_chain", "Trust Chain: the OpenID Federation entity statements supporting the signing key (OpenID Federation 1.0)"),
+];
+
+#[doc(hidden)]
+const IANA_CLAIMS: &[(&str, &str)] = &[
+    ("iss", "Issuer: who created and signed the token (RFC 7519)"),
+    ("sub", "Subject: the principal (usually a user or a client) the token is about (RFC 7519)"),
+    ("aud", "Audience: the recipients the token is intended for (RFC 7519)"),
+    ("exp", "Expiration Time: the token must not be accepted after this time (RFC 7519)"),
+    ("nbf", "Not Before: the token must not be accepted before this time (RFC 7519)"),
+    ("iat", "Issued At: when the token was issued (RFC 7519)"),
+    ("jti", "JWT ID: a unique identifier of the token, used to prevent replays (RFC 7519)"),
+    ("name", "Full name of the end-user (OpenID Connect Core 1.0)"),
+    ("given_name", "Given name(s) or first name(s) of the end-user (OpenID Connect Core 1.0)"),
+    ("family_name", "Surname(s) or last name(s) of the end-user (OpenID Connect Core 1.0)"),
+    ("middle_name", "Middle name(s) of the end-user (OpenID Connect Core 1.0)"),
+    ("nickname", "Casual name of the end-user (OpenID Connect Core 1.0)"),
+    ("preferred_username", "Shorthand name by which the end-user wishes to be referred to (OpenID Connect Core 1.0)"),
+    ("profile", "URL of the profile page of the end-user (OpenID Connect Core 1.0)"),
+    ("picture", "URL of the profile picture of the end-user (OpenID Connect Core 1.0)"),
+    ("website", "URL of the web page or blog of the end-user (OpenID Connect Core 1.0)"),
+    ("email", "Preferred e-mail address of the end-user (OpenID Connect Core 1.0)"),
+    ("email_verified", "True if the e-mail address of the end-user has been verified (OpenID Connect Core 1.0)"),
+    ("gender", "Gender of the end-user (OpenID Connect Core 1.0)"),
+    ("birthdate", "Birthday of the end-user (OpenID Connect Core 1.0)"),
+    ("zoneinfo", "Time zone of the end-user (OpenID Connect Core 1.0)"),
+    ("locale", "Locale of the end-user (OpenID Connect Core 1.0)"),
+    ("phone_number", "Preferred telephone number of the end-user (OpenID Connect Core 1.0)"),
+    ("phone_number_verified", "True if the phone number of the end-user has been verified (OpenID Connect Core 1.0)"),
+    ("address", "Preferred postal address of the end-user (OpenID Connect Core 1.0)"),
+    ("updated_at", "Time the information of the end-user was last updated (OpenID Connect Core 1.0)"),
+    ("azp", "Authorized Party: the client the token was issued to (OpenID Connect Core 1.0)"),
+    ("nonce", "Nonce: the value sent in the authentication request, binding the token to the client session (OpenID Connect Core 1.0)"),
+    ("auth_time", "Authentication Time: when the end-user authenticated (OpenID Connect Core 1.0)"),
+    ("at_hash", "Access Token hash: the left half of the hash of the access token issued with the ID token (OpenID Connect Core 1.0)"),
+    ("c_hash", "Code hash: the left half of the hash of the authorization code issued with the ID token (OpenID Connect Core 1.0)"),
+    ("acr", "Authentication Context Class Reference: the level of assurance of the authentication (OpenID Connect Core 1.0)"),
+    ("amr", "Authentication Methods References: how the end-user authenticated, e.g. pwd or mfa (OpenID Connect Core 1.0, RFC 8176)"),
+    ("sub_jwk", "Public key used to check the signature of a self-issued ID token (OpenID Connect Core 1.0)"),
+    ("sid", "Session ID: the identifier of the session of the end-user at the issuer (OpenID Connect Front-Channel Logout 1.0)"),
+    ("cnf", "Confirmation: the key (or its thumbprint, jkt) the token is bound to (RFC 7800, RFC 9449)"),
+    ("events", "Security Events: the events described by a security event token (RFC 8417)"),
+    ("toe", "Time of Event: when the event of a security event token occurred (RFC 8417)"),
+    ("txn", "Transaction Identifier: correlates the token with other tokens of the same transaction (RFC 8417)"),
+    ("act", "Actor: the party acting on behalf of the subject, after a token exchange (RFC 8693)"),
+    ("scope", "Scope: the space separated list of scopes granted to the client (RFC 8693, RFC 9068)"),
+    ("client_id", "Client Identifier: the OAuth client the token was issued to (RFC 8693, RFC 9068)"),
+    ("may_act", "Authorized Actor: the party authorized to act on behalf of the subject (RFC 8693)"),
+    ("roles", "Roles: the roles of the subject (RFC 7643, RFC 9068)"),
+    ("groups", "Groups: the groups the subject belongs to (RFC 7643, RFC 9068)"),
+    ("entitlements", "Entitlements: the entitlements of the subject (RFC 7643, RFC 9068)"),
+    ("token_introspection", "Token introspection response, for JWT introspection responses (RFC 9701)"),
+    ("vot", "Vector of Trust value (RFC 8485)"),
+    ("vtm", "Vector of Trust trustmark URL (RFC 8485)"),
+    ("orig", "Originating identity of a PASSporT (RFC 8225)"),
+    ("dest", "Destination identity of a PASSporT (RFC 8225)"),
+    ("attest", "Attestation level of a SHAKEN PASSporT (RFC 8588)"),
+    ("origid", "Originating identifier of a SHAKEN PASSporT (RFC 8588)"),
+    ("ace_profile", "The ACE profile a token is used with (RFC 9200)"),
+    ("cnonce", "Client nonce, proving the freshness of a token (RFC 9200)"),
+    ("exi", "Expires In: the lifetime of the token in seconds, for recipients without a clock (RFC 9200)"),
+    ("ueid", "Universal Entity ID of an attested device (RFC 9711)"),
+    ("sueid", "Semi-permanent Universal Entity IDs of an attested device (RFC 9711)"),
+    ("oemid", "Hardware OEM ID of an attested device (RFC 9711)"),
+    ("hwmodel", "Hardware model of an attested device (RFC 9711)"),
+    ("hwversion", "Hardware version of an attested device (RFC 9711)"),
+    ("secboot", "True if secure boot is enabled on the attested device (RFC 9711)"),
+    ("dbgstat", "Debug status of an attested device (RFC 9711)"),
+    ("location", "Location of an attested device (RFC 9711)"),
+    ("eat_profile", "Entity Attestation Token profile (RFC 9711)"),
+    ("submods", "Submodules of an attested device (RFC 9711)"),
+    ("authorization_details", "Rich authorization request details (RFC 9396)"),
+    ("verified_claims", "Claims verified by an identity assurance process (OpenID Connect for Identity Assurance 1.0)"),
+    ("place_of_birth", "Place of birth of the end-user (OpenID Connect for Identity Assurance 1.0)"),
+    ("nationalities", "Nationalities of the end-user (OpenID Connect for Identity Assurance 1.0)"),
+    ("birth_family_name", "Family name(s) of the end-user at birth (OpenID Connect for Identity Assurance 1.0)"),
+    ("birth_given_name", "Given name(s) of the end-user at birth (OpenID Connect for Identity Assurance 1.0)"),
+    ("salutation", "Salutation of the end-user, e.g. Mr. or Dr. (OpenID Connect for Identity Assurance 1.0)"),
+    ("title", "Title of the end-user (OpenID Connect for Identity Assurance 1.0)"),
+    ("msisdn", "Mobile phone number of the end-user (OpenID Connect for Identity Assurance 1.0)"),
+    ("also_known_as", "Stage, religious or other name of the end-user (OpenID Connect for Identity Assurance 1.0)"),
+    ("htm", "HTTP Method: the method of the request a DPoP proof is sent with (RFC 9449)"),
+    ("htu", "HTTP URI: the URL of the request a DPoP proof is sent with (RFC 9449)"),
+    ("ath", "Access Token hash: the hash of the access token a DPoP proof is sent with (RFC 9449)"),
+    ("sub_id", "Subject Identifier: the subject, in one of the formats of RFC 9493 (RFC 9493)"),
+    ("vc", "Verifiable Credential (W3C Verifiable Credentials Data Model)"),
+    ("vp", "Verifiable Presentation (W3C Verifiable Credentials Data Model)"),
+    ("_claim_names", "Aggregated or distributed claims: the claims provided by other sources (OpenID Connect Core 1.0)"),
+    ("_claim_sources", "Aggregated or distributed claims: the sources of the claims (OpenID Connect Core 1.0)"),
+    ("_sd", "Digests of the selectively disclosable claims (RFC 9901, SD-JWT)"),
+    ("_sd_alg", "Hash algorithm of the SD-JWT disclosure digests (RFC 9901, SD-JWT)"),
+    ("sd_hash", "Hash of the SD-JWT a key binding JWT is issued for (RFC 9901, SD-JWT)"),
+];
+
+#[doc(hidden)]
+const ENTRA_HEADER: &[(&str, &str)] = &[(
+    "nonce",
+    "Nonce added to Microsoft Graph access tokens: the signature can only be checked by Microsoft Graph",
+)];
+
+#[doc(hidden)]
+const ENTRA_CLAIMS: &[(&str, &str)] = &[
+    ("tid", "Tenant ID: the GUID of the Entra tenant the user signed in to"),
+    ("oid", "Object ID: the immutable identifier of the user (or service principal) across the applications of the tenant"),
+    ("wids", "Directory roles: the template IDs of the tenant-wide Entra roles assigned to the user"),
+    ("ver", "Token version: 1.0 (sts.windows.net issuer) or 2.0 (login.microsoftonline.com/{tenant}/v2.0 issuer)"),
+    ("appid", "Application ID: the client that requested the token (v1.0 tokens, azp in v2.0)"),
+    ("appidacr", "How the client authenticated: 0 public client, 1 client secret, 2 certificate (v1.0 tokens)"),
+    ("azpacr", "How the client authenticated: 0 public client, 1 client secret, 2 certificate (v2.0 tokens)"),
+    ("idp", "Identity provider that authenticated the user, when different from the issuer (e.g. for guests)"),
+    ("idtyp", "Identity type: app for app-only tokens, user for delegated tokens"),
+    ("ipaddr", "IP address the user authenticated from"),
+    ("uti", "Token identifier, used internally by Entra (similar to jti)"),
+    ("rh", "Opaque claim used internally by Entra to revalidate tokens"),
+    ("aio", "Opaque claim used internally by Entra to reuse tokens"),
+    ("scp", "Scopes: the space separated delegated permissions granted to the client"),
+    ("roles", "App roles: the application permissions or app roles assigned to the user or client"),
+    ("groups", "Object IDs of the groups of the user (see hasgroups and _claim_names for overages)"),
+    ("hasgroups", "True if the user is in too many groups to list them in the token"),
+    ("upn", "User principal name, e.g. user@contoso.com (not stable, do not use for authorization)"),
+    ("unique_name", "Human readable identifier of the user (v1.0 tokens, not stable)"),
+    ("acct", "Account status: 0 member, 1 guest"),
+    ("ctry", "Country or region of the user"),
+    ("tenant_ctry", "Country or region of the tenant"),
+    ("tenant_region_scope", "Region of the tenant"),
+    ("login_hint", "Opaque hint to sign the user in again"),
+    ("sid", "Session ID, used for sign-out"),
+    ("puid", "Passport user ID of the user"),
+    ("onprem_sid", "Security identifier of the user in the on-premises Active Directory"),
+    ("xms_*", "Microsoft extension claim (optional claims such as xms_cc, xms_pdl or xms_tpl)"),
+];
+
+#[doc(hidden)]
+const COGNITO_CLAIMS: &[(&str, &str)] = &[
+    (
+        "cognito:groups",
+        "Names of the user pool groups of the user",
+    ),
+    (
+        "cognito:username",
+        "User name of the user in the user pool (ID tokens)",
+    ),
+    (
+        "cognito:roles",
+        "IAM roles associated with the groups of the user",
+    ),
+    (
+        "cognito:preferred_role",
+        "IAM role of the group of the user with the highest priority",
+    ),
+    (
+        "token_use",
+        "Token type: id for ID tokens, access for access tokens",
+    ),
+    (
+        "username",
+        "User name of the user in the user pool (access tokens)",
+    ),
+    (
+        "origin_jti",
+        "Identifier of the original authentication, shared by the tokens refreshed from it",
+    ),
+    ("event_id", "Identifier of the authentication event"),
+    ("version", "Version of the access token format"),
+    (
+        "identities",
+        "Federated identities (social or SAML providers) linked to the user",
+    ),
+    ("custom:*", "Custom attribute of the user pool"),
+];
+
+#[doc(hidden)]
+const GOOGLE_CLAIMS: &[(&str, &str)] = &[
+    (
+        "hd",
+        "Hosted domain: the Google Workspace domain of the user",
+    ),
+    (
+        "google",
+        "Google Cloud details, e.g. the Compute Engine instance the token was requested from",
+    ),
+    (
+        "firebase",
+        "Firebase Authentication details: the sign-in provider and the linked identities",
+    ),
+    ("user_id", "Firebase user ID (same as sub)"),
+    (
+        "azp",
+        "Client ID of the authorized presenter (for service account tokens, its unique ID)",
+    ),
+];
+
+#[doc(hidden)]
+const AUTH0_CLAIMS: &[(&str, &str)] = &[
+    (
+        "gty",
+        "Grant type used to obtain the token, e.g. client-credentials or password",
+    ),
+    (
+        "permissions",
+        "API permissions granted to the user or client (RBAC)",
+    ),
+    (
+        "org_id",
+        "Identifier of the Auth0 organization the user signed in to",
+    ),
+    (
+        "org_name",
+        "Name of the Auth0 organization the user signed in to",
+    ),
+    (
+        "https://*",
+        "Namespaced custom claim, added by an Auth0 Action or Rule",
+    ),
+    (
+        "http://*",
+        "Namespaced custom claim, added by an Auth0 Action or Rule",
+    ),
+];
+
+#[doc(hidden)]
+const KEYCLOAK_CLAIMS: &[(&str, &str)] = &[
+    (
+        "realm_access",
+        "Realm roles of the user (realm_access.roles)",
+    ),
+    (
+        "resource_access",
+        "Client roles of the user, by client ID (resource_access.<client>.roles)",
+    ),
+    (
+        "typ",
+        "Token type: Bearer (access token), ID, Refresh, Logout or Offline",
+    ),
+    ("session_state", "Identifier of the Keycloak user session"),
+    (
+        "allowed-origins",
+        "Web origins allowed for CORS requests with this token",
+    ),
+    (
+        "authorization",
+        "Permissions granted by Keycloak Authorization Services (RPT tokens)",
+    ),
+];
+
+#[doc(hidden)]
+const GITHUB_ACTIONS_CLAIMS: &[(&str, &str)] = &[
+    ("repository", "Repository the workflow runs in (owner/name)"),
+    ("repository_id", "Immutable ID of the repository"),
+    (
+        "repository_owner",
+        "Owner (user or organization) of the repository",
+    ),
+    (
+        "repository_owner_id",
+        "Immutable ID of the owner of the repository",
+    ),
+    (
+        "repository_visibility",
+        "Visibility of the repository: public, private or internal",
+    ),
+    ("actor", "User that triggered the workflow run"),
+    (
+        "actor_id",
+        "Immutable ID of the user that triggered the workflow run",
+    ),
+    ("workflow", "Name of the workflow"),
+    ("workflow_ref", "Path and ref of the workflow file"),
+    ("workflow_sha", "Commit SHA of the workflow file"),
+    (
+        "job_workflow_ref",
+        "Path and ref of the (reusable) workflow the job runs",
+    ),
+    (
+        "job_workflow_sha",
+        "Commit SHA of the (reusable) workflow the job runs",
+    ),
+    (
+        "ref",
+        "Git ref that triggered the workflow run, e.g. refs/heads/main",
+    ),
+    ("ref_type", "Type of the ref: branch or tag"),
+    ("ref_protected", "True if the ref is protected"),
+    ("sha", "Commit SHA that triggered the workflow run"),
+    (
+        "environment",
+        "Name of the deployment environment of the job",
+    ),
+    (
+        "event_name",
+        "Event that triggered the workflow run, e.g. push or pull_request",
+    ),
+    ("head_ref", "Source branch of a pull request"),
+    ("base_ref", "Target branch of a pull request"),
+    ("run_id", "ID of the workflow run"),
+    ("run_number", "Number of the workflow run"),
+    ("run_attempt", "Attempt number of the workflow run"),
+    (
+        "runner_environment",
+        "Runner type: github-hosted or self-hosted",
+    ),
+    ("enterprise", "Enterprise the repository belongs to"),
+    (
+        "enterprise_id",
+        "Immutable ID of the enterprise the repository belongs to",
+    ),
+];
+
+#[doc(hidden)]
+const KUBERNETES_CLAIMS: &[(&str, &str)] = &[
+    ("kubernetes.io", "Kubernetes details of a projected service account token: namespace, serviceaccount, pod, node and warnafter"),
+    ("kubernetes.io/serviceaccount/namespace", "Namespace of the service account (legacy secret based tokens)"),
+    ("kubernetes.io/serviceaccount/secret.name", "Name of the secret holding the token (legacy secret based tokens)"),
+    ("kubernetes.io/serviceaccount/service-account.name", "Name of the service account (legacy secret based tokens)"),
+    ("kubernetes.io/serviceaccount/service-account.uid", "UID of the service account (legacy secret based tokens)"),
+];
@@ -0,0 +1,87 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn assert_lookup_iana() {
+    let definitions = lookup(Location::Header, "kid");
+    assert_eq!(definitions.len(), 1);
+    assert_eq!(definitions[0].source, Source::Iana);
+    assert!(definitions[0].description.starts_with("Key ID"));
+    assert!(lookup(Location::Header, "tid").is_empty());
+}
+
+#[test]
+fn assert_lookup_providers() {
+    assert_eq!(lookup(Location::Claims, "wids")[0].source, Source::Entra);
+    assert_eq!(
+        lookup(Location::Claims, "cognito:groups")[0].source,
+        Source::Cognito
+    );
+    assert_eq!(
+        lookup(Location::Claims, "realm_access")[0].source,
+        Source::Keycloak
+    );
+    assert_eq!(
+        lookup(Location::Claims, "job_workflow_ref")[0].source,
+        Source::GithubActions
+    );
+    assert_eq!(
+        lookup(Location::Claims, "kubernetes.io")[0].source,
+        Source::Kubernetes
+    );
+    assert_eq!(lookup(Location::Claims, "hd")[0].source, Source::Google);
+}
+
+#[test]
+fn assert_lookup_multiple_definitions() {
+    let sources: Vec<Source> = lookup(Location::Claims, "roles")
+        .iter()
+        .map(|d| d.source)
+        .collect();
+    assert_eq!(sources, [Source::Iana, Source::Entra]);
+}
+
+#[test]
+fn assert_lookup_prefix() {
+    assert_eq!(
+        lookup(Location::Claims, "custom:department")[0].source,
+        Source::Cognito
+    );
+    assert_eq!(
+        lookup(Location::Claims, "https://example.com/roles")[0].source,
+        Source::Auth0
+    );
+    assert!(lookup(Location::Claims, "custom").is_empty());
+}
+
+#[test]
+fn assert_explain() {
+    let claims = serde_json::json!({"exp": 1516242622, "foo": true});
+    assert_eq!(
+        explain(Location::Claims, &claims, None, 1516239022),
+        "claims:\n  exp: 1516242622 (2018-01-18T02:30:22Z, in 1 hour)\n    Expiration Time: the token must not be accepted after this time (RFC 7519) [IANA]\n  foo: true\n    unknown, not in the bundled dictionaries\n"
+    );
+}
+
+#[test]
+fn assert_explain_not_an_object() {
+    assert_eq!(
+        explain(
+            Location::Claims,
+            &Value::from("<encrypted JWE body>"),
+            None,
+            0
+        ),
+        "claims:\n  \"<encrypted JWE body>\"\n"
+    );
+}
+
+#[test]
+fn assert_explain_with_provider() {
+    let claims = serde_json::json!({"roles": ["admin"]});
+    let explained = explain(Location::Claims, &claims, Some(Source::Keycloak), 0);
+    assert!(explained.contains("[IANA]"));
+    assert!(!explained.contains("[Azure AD / Entra ID]"));
+    let explained = explain(Location::Claims, &claims, Some(Source::Entra), 0);
+    assert!(explained.contains("App roles"));
+}
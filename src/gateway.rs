@@ -0,0 +1,459 @@
+//! # Gateway
+//!
+//! `gateway` is the forward-auth verifier started by `jwtinfo gateway`: a
+//! reverse proxy (nginx `auth_request`, Traefik `ForwardAuth` or the HTTP
+//! service of Envoy `ext_authz`) sends it the headers of every request, and it
+//! answers:
+//!
+//! - `200` if the bearer token is valid and satisfies the claim rules, with
+//!   the selected claims as response headers (e.g. `X-Auth-Sub`), so that the
+//!   proxy can forward them to the upstream
+//! - `401` if the token is missing, cannot be verified with the configured
+//!   keys, is expired, not yet valid, or has another issuer or audience
+//! - `403` if the token is valid but doesn't satisfy a claim rule
+//!
+//! Tokens must be signed with one of the configured public keys (`alg: none`
+//! and symmetric algorithms are never accepted) and must have an `exp` claim.
+//! The gateway answers every method and path in the same way and never logs
+//! tokens.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::gateway;
+//!
+//! let mut config = gateway::Config::new(vec![]);
+//! config.rules.push("scope=read".parse().unwrap());
+//! config.forward.push("sub".parse().unwrap());
+//!
+//! let decision = gateway::authorize(&config, None, 1516239022);
+//! assert_eq!(decision.status, 401);
+//! assert_eq!(decision.checks[0].to_string(), "[FAIL] token: the request has no Authorization header");
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::{io, str};
+
+use serde_json::Value;
+
+use crate::jwk;
+use crate::jwt;
+use crate::server::{self, Response};
+use crate::timestamp;
+use crate::validation::{self, Check};
+
+/// The default header containing the bearer token
+pub const DEFAULT_HEADER: &str = "Authorization";
+
+/// The default tolerated clock skew, in seconds, for `exp` and `nbf`
+pub const DEFAULT_LEEWAY: u64 = 60;
+
+/// The prefix of the headers of the forwarded claims, when no name is given
+pub const FORWARD_PREFIX: &str = "X-Auth-";
+
+/// Represents an error in the configuration of the gateway
+#[derive(Debug, PartialEq, Eq)]
+pub enum GatewayError {
+    /// Indicates that a claim rule is not in the `claim=value` form
+    InvalidRule(String),
+    /// Indicates that the name of a forwarded header is not a valid header name
+    InvalidHeaderName(String),
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GatewayError::InvalidRule(rule) => {
+                write!(f, "Invalid rule `{}`, expected CLAIM=VALUE", rule)
+            }
+            GatewayError::InvalidHeaderName(name) => {
+                write!(f, "Invalid header name `{}`", name)
+            }
+        }
+    }
+}
+
+impl Error for GatewayError {}
+
+/// A claim that the token must contain, with a given value
+///
+/// The rule is satisfied if the claim is equal to the value, if it is an array
+/// containing the value or, for `scope` and `scp`, if it is a space separated
+/// list containing the value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// the name of the claim
+    pub claim: String,
+    /// the expected value (numbers and booleans are compared as JSON)
+    pub value: String,
+}
+
+impl FromStr for Rule {
+    type Err = GatewayError;
+
+    /// Parses a rule in the `claim=value` form
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((claim, value)) if !claim.is_empty() => Ok(Self {
+                claim: claim.to_string(),
+                value: value.to_string(),
+            }),
+            _ => Err(GatewayError::InvalidRule(s.to_string())),
+        }
+    }
+}
+
+impl Rule {
+    /// Returns `true` if a claim value satisfies the rule
+    pub fn matches(&self, claim: &Value) -> bool {
+        let equals = |value: &Value| match value {
+            Value::String(s) => *s == self.value,
+            other => serde_json::from_str::<Value>(&self.value).is_ok_and(|v| v == *other),
+        };
+        match claim {
+            Value::Array(items) => items.iter().any(equals),
+            Value::String(s) if self.claim == "scope" || self.claim == "scp" => {
+                s.split(' ').any(|scope| scope == self.value)
+            }
+            other => equals(other),
+        }
+    }
+}
+
+/// A claim sent back to the proxy as a response header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forward {
+    /// the name of the claim
+    pub claim: String,
+    /// the name of the header
+    pub header: String,
+}
+
+/// Returns `true` if a string is a valid header name (an RFC 9110 token)
+#[doc(hidden)]
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+impl FromStr for Forward {
+    type Err = GatewayError;
+
+    /// Parses a forwarded claim in the `claim=Header-Name` form, or just
+    /// `claim` for an `X-Auth-Claim` header (e.g. `X-Auth-Sub` for `sub`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (claim, header) = match s.split_once('=') {
+            Some((claim, header)) => (claim.to_string(), header.to_string()),
+            None => {
+                let name: String = s
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .filter(|part| !part.is_empty())
+                    .map(|part| {
+                        let mut chars = part.chars();
+                        chars.next().map_or_else(String::new, |first| {
+                            first.to_ascii_uppercase().to_string() + chars.as_str()
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .join("-");
+                (s.to_string(), format!("{}{}", FORWARD_PREFIX, name))
+            }
+        };
+        if claim.is_empty() || !is_header_name(&header) || header == FORWARD_PREFIX {
+            return Err(GatewayError::InvalidHeaderName(header));
+        }
+        Ok(Self { claim, header })
+    }
+}
+
+/// The configuration of the gateway
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// the public keys the tokens can be signed with
+    pub keys: Vec<Value>,
+    /// the header containing the token (with or without the `Bearer ` prefix)
+    pub header: String,
+    /// the expected issuer (`iss`)
+    pub issuer: Option<String>,
+    /// the expected audience (one of the values of `aud`)
+    pub audience: Option<String>,
+    /// the claims the token must contain
+    pub rules: Vec<Rule>,
+    /// the claims sent back to the proxy as response headers
+    pub forward: Vec<Forward>,
+    /// the tolerated clock skew, in seconds, for `exp` and `nbf`
+    pub leeway: u64,
+}
+
+impl Config {
+    /// Creates a configuration accepting the tokens signed with the given keys
+    pub fn new(keys: Vec<Value>) -> Self {
+        Self {
+            keys,
+            header: DEFAULT_HEADER.to_string(),
+            issuer: None,
+            audience: None,
+            rules: Vec::new(),
+            forward: Vec::new(),
+            leeway: DEFAULT_LEEWAY,
+        }
+    }
+}
+
+/// The answer of the gateway to a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    /// `200`, `401` or `403`
+    pub status: u16,
+    /// the rules the token was checked against (the first failed one explains the status)
+    pub checks: Vec<Check>,
+    /// the forwarded claims, as (header, value) pairs
+    pub headers: Vec<(String, String)>,
+}
+
+impl Decision {
+    #[doc(hidden)]
+    fn new(status: u16, checks: Vec<Check>) -> Self {
+        Self {
+            status,
+            checks,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Returns the first failed check, if any
+    pub fn failure(&self) -> Option<&Check> {
+        self.checks.iter().find(|check| !check.passed)
+    }
+}
+
+/// Reads the value of a time claim
+#[doc(hidden)]
+fn time_claim(claims: &Value, name: &str) -> Option<i64> {
+    let value = claims.get(name)?;
+    value.as_i64().or_else(|| value.as_f64().map(|f| f as i64))
+}
+
+/// Checks the signature, the time claims, the issuer and the audience of a token
+#[doc(hidden)]
+fn authenticate(config: &Config, token: &str, now: u64, checks: &mut Vec<Check>) -> Option<Value> {
+    let parsed = match jwt::parse(token) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            checks.push(Check::fail("token", e.to_string()));
+            return None;
+        }
+    };
+    match jwk::verify_jws_with_keys(token, &config.keys) {
+        Ok(key) => {
+            let kid = key.get("kid").and_then(|kid| kid.as_str()).unwrap_or("-");
+            checks.push(Check::pass(
+                "signature",
+                format!("verified with key {}", kid),
+            ))
+        }
+        Err(e) => {
+            checks.push(Check::fail("signature", e.to_string()));
+            return None;
+        }
+    }
+
+    let claims = parsed.body;
+    let now = now as i64;
+    // claims are untrusted: extreme values must not overflow
+    let leeway = i64::try_from(config.leeway).unwrap_or(i64::MAX);
+    checks.push(match time_claim(&claims, "exp") {
+        None => Check::fail("exp", "the token has no numeric exp claim"),
+        Some(exp) if exp.saturating_add(leeway) <= now => Check::fail(
+            "exp",
+            format!("the token expired {}", timestamp::format_relative(exp, now)),
+        ),
+        Some(exp) => Check::pass(
+            "exp",
+            format!("the token expires {}", timestamp::format_relative(exp, now)),
+        ),
+    });
+    if let Some(nbf) = time_claim(&claims, "nbf") {
+        checks.push(if nbf > now.saturating_add(leeway) {
+            Check::fail(
+                "nbf",
+                format!(
+                    "the token is valid {}",
+                    timestamp::format_relative(nbf, now)
+                ),
+            )
+        } else {
+            Check::pass("nbf", "the token is valid")
+        });
+    }
+    if let Some(issuer) = &config.issuer {
+        let iss = claims.get("iss").and_then(|iss| iss.as_str());
+        checks.push(if iss == Some(issuer.as_str()) {
+            Check::pass("iss", format!("issued by {}", issuer))
+        } else {
+            Check::fail("iss", format!("expected {}, found {:?}", issuer, iss))
+        });
+    }
+    if let Some(audience) = &config.audience {
+        let matches = match claims.get("aud") {
+            Some(Value::String(aud)) => aud == audience,
+            Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+            _ => false,
+        };
+        checks.push(if matches {
+            Check::pass("aud", format!("intended for {}", audience))
+        } else {
+            Check::fail(
+                "aud",
+                format!("expected {}, found {}", audience, claims["aud"]),
+            )
+        });
+    }
+    Some(claims)
+}
+
+/// Returns the value of a forwarded claim, or `None` if it cannot be sent in a header
+#[doc(hidden)]
+fn header_value(claim: &Value) -> Option<String> {
+    let value = match claim {
+        Value::Null => return None,
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|item| item.is_string()) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    };
+    // control characters (e.g. new lines) would split the header
+    if value.chars().any(|c| c.is_control()) {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Decides whether a request is authorized, given the value of the header
+/// containing its token (see `Config::header`)
+pub fn authorize(config: &Config, header: Option<&str>, now: u64) -> Decision {
+    let Some(header) = header.map(str::trim).filter(|h| !h.is_empty()) else {
+        let detail = format!("the request has no {} header", config.header);
+        return Decision::new(401, vec![Check::fail("token", detail)]);
+    };
+    let token = match header.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim(),
+        Some((scheme, _)) => {
+            let detail = format!("expected a Bearer token, found a {} token", scheme);
+            return Decision::new(401, vec![Check::fail("token", detail)]);
+        }
+        None => header,
+    };
+
+    let mut checks = Vec::new();
+    let claims = authenticate(config, token, now, &mut checks);
+    let claims = match claims {
+        Some(claims) if validation::all_passed(&checks) => claims,
+        _ => return Decision::new(401, checks),
+    };
+
+    for rule in &config.rules {
+        checks.push(match claims.get(&rule.claim) {
+            Some(claim) if rule.matches(claim) => {
+                Check::pass(&rule.claim, format!("matches {}", rule.value))
+            }
+            Some(claim) => Check::fail(
+                &rule.claim,
+                format!("expected {}, found {}", rule.value, claim),
+            ),
+            None => Check::fail(
+                &rule.claim,
+                format!("expected {}, found nothing", rule.value),
+            ),
+        });
+    }
+    if !validation::all_passed(&checks) {
+        return Decision::new(403, checks);
+    }
+
+    let headers = config
+        .forward
+        .iter()
+        .filter_map(|forward| {
+            let value = header_value(claims.get(&forward.claim)?)?;
+            Some((forward.header.clone(), value))
+        })
+        .collect();
+    Decision {
+        status: 200,
+        checks,
+        headers,
+    }
+}
+
+/// Converts a decision to the HTTP response expected by the proxies
+#[doc(hidden)]
+fn to_response(decision: &Decision) -> Response {
+    let mut response = Response::json(
+        decision.status,
+        &serde_json::json!({
+            "status": decision.status,
+            "checks": decision.checks.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+        }),
+    );
+    let challenge = match decision.status {
+        401 => Some("Bearer error=\"invalid_token\""),
+        403 => Some("Bearer error=\"insufficient_scope\""),
+        _ => None,
+    };
+    if let Some(challenge) = challenge {
+        response
+            .headers
+            .push(("WWW-Authenticate".to_string(), challenge.to_string()));
+    }
+    response.headers.extend(decision.headers.iter().cloned());
+    response
+}
+
+/// The path of the original request, as sent by the proxy (without the query
+/// string, which may contain secrets)
+#[doc(hidden)]
+fn original_path(request: &tiny_http::Request) -> String {
+    let uri = ["X-Forwarded-Uri", "X-Original-URI"]
+        .iter()
+        .find_map(|name| server::header_value(request, name))
+        .unwrap_or_else(|| request.url().to_string());
+    uri.split('?').next().unwrap_or_default().to_string()
+}
+
+/// Starts the gateway on `addr` and answers requests until the process ends
+///
+/// `on_ready` is called with the bound address before the first request is
+/// accepted, `log` with every request and its decision (tokens are never
+/// part of it).
+///
+/// # Errors
+///
+/// This function will return an error if the address cannot be bound
+pub fn serve<F, L>(config: &Config, addr: SocketAddr, on_ready: F, mut log: L) -> io::Result<()>
+where
+    F: FnOnce(SocketAddr),
+    L: FnMut(&str, &str, &Decision),
+{
+    server::listen(addr, on_ready, |request, _| {
+        let header = server::header_value(request, &config.header);
+        let decision = authorize(config, header.as_deref(), timestamp::now());
+        let method = server::header_value(request, "X-Forwarded-Method")
+            .unwrap_or_else(|| request.method().as_str().to_string());
+        log(&method, &original_path(request), &decision);
+        to_response(&decision)
+    })
+}
+
+#[cfg(test)]
+mod test;
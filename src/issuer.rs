@@ -0,0 +1,568 @@
+//! # Issuer
+//!
+//! `issuer` is the mock OpenID Provider started by `jwtinfo issuer serve`: an
+//! offline stand-in for an identity provider, for integration tests and local
+//! development. It serves:
+//!
+//! - `GET /.well-known/openid-configuration` (and `/.well-known/oauth-authorization-server`),
+//!   the discovery document
+//! - `GET /jwks`, the public keys
+//! - `POST /token`, the token endpoint, with the `client_credentials` and
+//!   `password` grants
+//!
+//! Access tokens are [RFC 9068](https://datatracker.ietf.org/doc/html/rfc9068)
+//! JWTs (`typ: at+jwt`) signed with the first key. The `password` grant also
+//! returns an ID token when the `openid` scope is requested. Tokens only depend
+//! on the configuration, the request and the current time (`jti` is a counter),
+//! so that test suites get the same tokens on every run.
+//!
+//! This is a testing tool: it has no authorization endpoint, no consent and
+//! no persistence, and with no configured clients or users every credential
+//! is accepted.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::{issuer, jwk};
+//!
+//! let key = jwk::generate(jwk::KeySpec::P256).unwrap();
+//! let mut config = issuer::Config::new(vec![key]);
+//! config.clients.push("ci:secret".parse().unwrap());
+//! let mut mock = issuer::Issuer::new(config).unwrap();
+//!
+//! let params = [("grant_type", "client_credentials"), ("client_id", "ci"), ("client_secret", "secret")];
+//! let params: Vec<(String, String)> = params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+//! let response = mock.token(&params, None, "http://localhost:8082", 1516239022).unwrap();
+//! let token = jwtinfo::jwt::parse(response["access_token"].as_str().unwrap()).unwrap();
+//! assert_eq!(token.header["typ"], "at+jwt");
+//! assert_eq!(token.body["sub"], "ci");
+//! assert_eq!(token.body["exp"], 1516239022 + 3600);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::{io, str};
+
+use base64::Engine as _;
+use serde_json::{Map, Value};
+
+use crate::jwk::{self, JwkError};
+use crate::server::{self, Response};
+use crate::timestamp;
+
+/// The default lifetime of the issued tokens, in seconds
+pub const DEFAULT_LIFETIME: u64 = 3600;
+
+/// Represents an error in the configuration of the issuer
+#[derive(Debug)]
+pub enum IssuerError {
+    /// Indicates that no signing key is configured
+    NoSigningKey(),
+    /// Indicates that the signing key is not a supported private key
+    InvalidKey(JwkError),
+    /// Indicates that credentials are not in the `id:secret` form
+    InvalidCredentials(String),
+}
+
+impl fmt::Display for IssuerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IssuerError::NoSigningKey() => write!(f, "No signing key"),
+            IssuerError::InvalidKey(e) => write!(f, "Invalid signing key: {}", e),
+            IssuerError::InvalidCredentials(c) => {
+                write!(f, "Invalid credentials `{}`, expected ID:SECRET", c)
+            }
+        }
+    }
+}
+
+impl Error for IssuerError {}
+
+/// The credentials of a client (`client_id` and `client_secret`) or of a user
+/// (`username` and `password`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// the client id or the username
+    pub id: String,
+    /// the client secret or the password
+    pub secret: String,
+}
+
+impl FromStr for Credentials {
+    type Err = IssuerError;
+
+    /// Parses credentials in the `id:secret` form
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((id, secret)) if !id.is_empty() => Ok(Self {
+                id: id.to_string(),
+                secret: secret.to_string(),
+            }),
+            _ => Err(IssuerError::InvalidCredentials(s.to_string())),
+        }
+    }
+}
+
+/// The configuration of the issuer
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// the private keys: the first one signs the tokens, all of them are published
+    pub keys: Vec<Value>,
+    /// the issuer identifier (`iss`), which is also the base URL of the
+    /// endpoints (defaults to `http://` followed by the address of the server)
+    pub issuer: Option<String>,
+    /// the audience of the access tokens (defaults to the `audience` parameter
+    /// of the request, then to the client id)
+    pub audience: Option<String>,
+    /// the accepted clients (any client is accepted if empty)
+    pub clients: Vec<Credentials>,
+    /// the accepted users of the `password` grant (any user is accepted if empty)
+    pub users: Vec<Credentials>,
+    /// claims added to every token, replacing the generated ones with the same name
+    pub claims: Map<String, Value>,
+    /// the lifetime of the tokens, in seconds
+    pub lifetime: u64,
+}
+
+impl Config {
+    /// Creates a configuration signing the tokens with the first of the given keys
+    pub fn new(keys: Vec<Value>) -> Self {
+        Self {
+            keys,
+            issuer: None,
+            audience: None,
+            clients: Vec::new(),
+            users: Vec::new(),
+            claims: Map::new(),
+            lifetime: DEFAULT_LIFETIME,
+        }
+    }
+}
+
+/// An error of the token endpoint (RFC 6749, section 5.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenError {
+    /// the HTTP status (`400` or `401`)
+    pub status: u16,
+    /// the error code (e.g. `invalid_grant`)
+    pub error: &'static str,
+    /// a human readable description of the error
+    pub description: String,
+}
+
+impl TokenError {
+    #[doc(hidden)]
+    fn new<D: Into<String>>(status: u16, error: &'static str, description: D) -> Self {
+        Self {
+            status,
+            error,
+            description: description.into(),
+        }
+    }
+
+    /// Returns the error as a JSON object with `error` and `error_description` fields
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "error": self.error,
+            "error_description": self.description,
+        })
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.error, self.description)
+    }
+}
+
+impl Error for TokenError {}
+
+/// Decodes a `application/x-www-form-urlencoded` component
+#[doc(hidden)]
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Parses a `application/x-www-form-urlencoded` body
+pub fn parse_form(body: &[u8]) -> Vec<(String, String)> {
+    String::from_utf8_lossy(body)
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+/// The mock OpenID Provider
+#[derive(Debug)]
+pub struct Issuer {
+    #[doc(hidden)]
+    config: Config,
+    #[doc(hidden)]
+    algorithm: String,
+    #[doc(hidden)]
+    issued: u64,
+}
+
+impl Issuer {
+    /// Creates an issuer, checking that the first key can sign tokens
+    ///
+    /// # Errors
+    ///
+    /// This function will return an `IssuerError` if there is no key or if
+    /// the first one is not a supported private key
+    pub fn new(config: Config) -> Result<Self, IssuerError> {
+        let key = config.keys.first().ok_or(IssuerError::NoSigningKey())?;
+        let algorithm = jwk::default_algorithm(key).map_err(IssuerError::InvalidKey)?;
+        jwk::sign(key, &algorithm, b"").map_err(IssuerError::InvalidKey)?;
+        Ok(Self {
+            config,
+            algorithm,
+            issued: 0,
+        })
+    }
+
+    /// Returns the issuer identifier, given the base URL of the server
+    pub fn issuer(&self, base: &str) -> String {
+        self.config
+            .issuer
+            .clone()
+            .unwrap_or_else(|| base.to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Returns the discovery document
+    pub fn discovery(&self, base: &str) -> Value {
+        let issuer = self.issuer(base);
+        serde_json::json!({
+            "issuer": issuer,
+            "jwks_uri": format!("{}/jwks", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "grant_types_supported": ["client_credentials", "password"],
+            "response_types_supported": ["token"],
+            "subject_types_supported": ["public"],
+            "id_token_signing_alg_values_supported": [self.algorithm],
+            "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post"],
+            "scopes_supported": ["openid"],
+        })
+    }
+
+    /// Returns the public keys, as a JWK Set
+    pub fn jwks(&self) -> Value {
+        let keys = Value::from(self.config.keys.clone());
+        jwk::to_public_jwks(&serde_json::json!({ "keys": keys }))
+            .unwrap_or_else(|_| serde_json::json!({ "keys": [] }))
+    }
+
+    /// Authenticates the client of a token request, returning its id (if any)
+    #[doc(hidden)]
+    fn client(
+        &self,
+        params: &[(String, String)],
+        authorization: Option<&str>,
+    ) -> Result<Option<String>, TokenError> {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.clone())
+        };
+        let basic = authorization.and_then(|header| {
+            let (scheme, credentials) = header.trim().split_once(' ')?;
+            if !scheme.eq_ignore_ascii_case("basic") {
+                return None;
+            }
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(credentials.trim())
+                .ok()?;
+            let decoded = String::from_utf8(decoded).ok()?;
+            let (id, secret) = decoded.split_once(':')?;
+            Some((percent_decode(id), percent_decode(secret)))
+        });
+        let (id, secret) = match basic {
+            Some((id, secret)) => (Some(id), Some(secret)),
+            None => (param("client_id"), param("client_secret")),
+        };
+        if self.config.clients.is_empty() {
+            return Ok(id);
+        }
+        let known = self
+            .config
+            .clients
+            .iter()
+            .any(|c| Some(&c.id) == id.as_ref() && Some(&c.secret) == secret.as_ref());
+        if known {
+            Ok(id)
+        } else {
+            Err(TokenError::new(
+                401,
+                "invalid_client",
+                "unknown client or wrong secret",
+            ))
+        }
+    }
+
+    /// Signs a token with the first key, adding the configured claims
+    #[doc(hidden)]
+    fn sign(&self, typ: &str, mut claims: Map<String, Value>) -> Result<String, TokenError> {
+        for (name, value) in &self.config.claims {
+            claims.insert(name.clone(), value.clone());
+        }
+        let header = serde_json::json!({ "typ": typ, "alg": self.algorithm });
+        jwk::sign_jws(&header, &Value::Object(claims), &self.config.keys[0])
+            .map_err(|e| TokenError::new(500, "server_error", e.to_string()))
+    }
+
+    /// Answers a token request, given its form parameters and its
+    /// `Authorization` header (for `client_secret_basic`)
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `TokenError` if the request is not valid,
+    /// the grant is not supported or the credentials are wrong
+    pub fn token(
+        &mut self,
+        params: &[(String, String)],
+        authorization: Option<&str>,
+        base: &str,
+        now: u64,
+    ) -> Result<Value, TokenError> {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+                .filter(|v| !v.is_empty())
+        };
+        let client_id = self.client(params, authorization)?;
+        let scope = param("scope");
+        let (sub, user) = match param("grant_type") {
+            Some("client_credentials") => {
+                let sub = client_id.clone().ok_or_else(|| {
+                    TokenError::new(
+                        401,
+                        "invalid_client",
+                        "the client_credentials grant requires a client",
+                    )
+                })?;
+                (sub, false)
+            }
+            Some("password") => {
+                let (username, password) = match (param("username"), param("password")) {
+                    (Some(username), Some(password)) => (username, password),
+                    _ => {
+                        return Err(TokenError::new(
+                            400,
+                            "invalid_request",
+                            "the password grant requires a username and a password",
+                        ))
+                    }
+                };
+                let known = self.config.users.is_empty()
+                    || self
+                        .config
+                        .users
+                        .iter()
+                        .any(|u| u.id == username && u.secret == password);
+                if !known {
+                    return Err(TokenError::new(
+                        400,
+                        "invalid_grant",
+                        "unknown user or wrong password",
+                    ));
+                }
+                (username.to_string(), true)
+            }
+            Some(other) => {
+                return Err(TokenError::new(
+                    400,
+                    "unsupported_grant_type",
+                    format!("the {} grant is not supported", other),
+                ))
+            }
+            None => {
+                return Err(TokenError::new(
+                    400,
+                    "invalid_request",
+                    "the grant_type parameter is missing",
+                ))
+            }
+        };
+
+        let issuer = self.issuer(base);
+        let audience = param("audience")
+            .map(String::from)
+            .or_else(|| self.config.audience.clone())
+            .or_else(|| client_id.clone());
+        self.issued += 1;
+        let mut claims = Map::new();
+        claims.insert("iss".to_string(), Value::from(issuer.as_str()));
+        claims.insert("sub".to_string(), Value::from(sub.as_str()));
+        if let Some(audience) = audience {
+            claims.insert("aud".to_string(), Value::from(audience));
+        }
+        if let Some(client_id) = &client_id {
+            claims.insert("client_id".to_string(), Value::from(client_id.as_str()));
+        }
+        claims.insert("iat".to_string(), Value::from(now));
+        claims.insert("exp".to_string(), Value::from(now + self.config.lifetime));
+        claims.insert("jti".to_string(), Value::from(self.issued.to_string()));
+        if let Some(scope) = scope {
+            claims.insert("scope".to_string(), Value::from(scope));
+        }
+        let mut response = serde_json::json!({
+            "access_token": self.sign("at+jwt", claims)?,
+            "token_type": "Bearer",
+            "expires_in": self.config.lifetime,
+        });
+        if let Some(scope) = scope {
+            response["scope"] = Value::from(scope);
+        }
+
+        let openid = scope.is_some_and(|s| s.split(' ').any(|s| s == "openid"));
+        if let (true, true, Some(client_id)) = (user, openid, &client_id) {
+            let mut claims = Map::new();
+            claims.insert("iss".to_string(), Value::from(issuer));
+            claims.insert("sub".to_string(), Value::from(sub));
+            claims.insert("aud".to_string(), Value::from(client_id.as_str()));
+            claims.insert("iat".to_string(), Value::from(now));
+            claims.insert("exp".to_string(), Value::from(now + self.config.lifetime));
+            claims.insert("auth_time".to_string(), Value::from(now));
+            response["id_token"] = Value::from(self.sign("JWT", claims)?);
+        }
+        Ok(response)
+    }
+
+    /// Routes a request, given its `Authorization` header and the base URL of
+    /// the server (e.g. `http://127.0.0.1:8082`)
+    pub fn handle(
+        &mut self,
+        request: &server::Request,
+        authorization: Option<&str>,
+        base: &str,
+        now: u64,
+    ) -> Response {
+        // the endpoints are relative to the path of the issuer, if any
+        let issuer = self.issuer(base);
+        let prefix = issuer
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]))
+            .unwrap_or_default();
+        let path = request.url.split('?').next().unwrap_or_default();
+        let path = path.strip_prefix(prefix).unwrap_or(path);
+        match (request.method, path) {
+            ("GET", "/.well-known/openid-configuration")
+            | ("GET", "/.well-known/oauth-authorization-server") => {
+                Response::json(200, &self.discovery(base))
+            }
+            ("GET", "/jwks") => Response::json(200, &self.jwks()),
+            ("POST", "/token") => {
+                let params = parse_form(request.body);
+                match self.token(&params, authorization, base, now) {
+                    Ok(response) => Response::json(200, &response),
+                    Err(e) => {
+                        let mut response = Response::json(e.status, &e.to_json());
+                        if e.status == 401 {
+                            response.headers.push((
+                                "WWW-Authenticate".to_string(),
+                                "Basic realm=\"jwtinfo\"".to_string(),
+                            ));
+                        }
+                        response
+                    }
+                }
+            }
+            (_, "/.well-known/openid-configuration")
+            | (_, "/.well-known/oauth-authorization-server")
+            | (_, "/jwks")
+            | (_, "/token") => Response::error(405, "Method not allowed"),
+            _ => Response::error(404, "Not found"),
+        }
+    }
+}
+
+/// Starts the issuer on `addr` and answers requests until the process ends
+///
+/// `on_ready` is called with the bound address and the issuer identifier,
+/// `log` with the method, the path and the status of every request (tokens
+/// and credentials are never part of it).
+///
+/// # Errors
+///
+/// This function will return an error if the address cannot be bound
+pub fn serve<F, L>(mut issuer: Issuer, addr: SocketAddr, on_ready: F, mut log: L) -> io::Result<()>
+where
+    F: FnOnce(SocketAddr, &str),
+    L: FnMut(&str, &str, u16),
+{
+    let base = |bound: SocketAddr| format!("http://{}", bound);
+    let issuer_url = issuer.issuer(&base(addr));
+    let configured = issuer.config.issuer.is_some();
+    server::listen(
+        addr,
+        |bound| {
+            if configured {
+                on_ready(bound, &issuer_url)
+            } else {
+                on_ready(bound, &base(bound))
+            }
+        },
+        |request, bound| {
+            let body = match server::read_body(request) {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            let authorization = server::header_value(request, "Authorization");
+            let method = request.method().as_str().to_string();
+            let path = request
+                .url()
+                .split('?')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let response = issuer.handle(
+                &server::Request {
+                    method: &method,
+                    url: request.url(),
+                    host: None,
+                    body: &body,
+                },
+                authorization.as_deref(),
+                &base(bound),
+                timestamp::now(),
+            );
+            log(&method, &path, response.status);
+            response
+        },
+    )
+}
+
+#[cfg(test)]
+mod test;
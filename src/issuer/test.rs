@@ -0,0 +1,212 @@
+#[cfg(test)]
+use super::*;
+
+#[cfg(test)]
+use crate::jwt;
+
+#[doc(hidden)]
+const NOW: u64 = 1516239022;
+
+#[doc(hidden)]
+const BASE: &str = "http://127.0.0.1:8082";
+
+#[doc(hidden)]
+fn issuer(config: impl FnOnce(&mut Config)) -> Issuer {
+    let key = jwk::generate(jwk::KeySpec::P256).unwrap();
+    let mut c = Config::new(vec![key]);
+    config(&mut c);
+    Issuer::new(c).unwrap()
+}
+
+#[doc(hidden)]
+fn form(params: &[(&str, &str)]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Parses a token and verifies it with the published keys
+#[doc(hidden)]
+fn verified(issuer: &Issuer, token: &Value) -> jwt::Token {
+    let token = token.as_str().unwrap();
+    let keys = issuer.jwks()["keys"].as_array().unwrap().clone();
+    jwk::verify_jws_with_keys(token, &keys).unwrap();
+    jwt::parse(token).unwrap()
+}
+
+#[test]
+fn assert_client_credentials_grant() {
+    let mut issuer = issuer(|config| {
+        config.clients.push("ci:s3cret".parse().unwrap());
+        config
+            .claims
+            .insert("tenant".to_string(), Value::from("acme"));
+    });
+    let params = form(&[
+        ("grant_type", "client_credentials"),
+        ("scope", "read write"),
+    ]);
+    // client_secret_basic
+    let response = issuer
+        .token(&params, Some("Basic Y2k6czNjcmV0"), BASE, NOW)
+        .unwrap();
+    assert_eq!(response["token_type"], "Bearer");
+    assert_eq!(response["expires_in"], 3600);
+    assert_eq!(response["scope"], "read write");
+    assert!(response.get("id_token").is_none());
+
+    let token = verified(&issuer, &response["access_token"]);
+    assert_eq!(token.header["typ"], "at+jwt");
+    assert_eq!(token.header["alg"], "ES256");
+    assert_eq!(
+        token.body,
+        serde_json::json!({
+            "iss": BASE,
+            "sub": "ci",
+            "aud": "ci",
+            "client_id": "ci",
+            "iat": NOW,
+            "exp": NOW + 3600,
+            "jti": "1",
+            "scope": "read write",
+            "tenant": "acme",
+        })
+    );
+
+    // client_secret_post
+    let params = form(&[
+        ("grant_type", "client_credentials"),
+        ("client_id", "ci"),
+        ("client_secret", "wrong"),
+    ]);
+    let error = issuer.token(&params, None, BASE, NOW).unwrap_err();
+    assert_eq!(error.status, 401);
+    assert_eq!(error.error, "invalid_client");
+}
+
+#[test]
+fn assert_password_grant() {
+    let mut issuer = issuer(|config| {
+        config.issuer = Some("https://idp.example/realms/test".to_string());
+        config.audience = Some("api".to_string());
+        config.users.push("alice:wonderland".parse().unwrap());
+        config.lifetime = 300;
+    });
+    let params = form(&[
+        ("grant_type", "password"),
+        ("client_id", "web"),
+        ("username", "alice"),
+        ("password", "wonderland"),
+        ("scope", "openid profile"),
+    ]);
+    let response = issuer.token(&params, None, BASE, NOW).unwrap();
+    let access = verified(&issuer, &response["access_token"]);
+    assert_eq!(access.body["iss"], "https://idp.example/realms/test");
+    assert_eq!(access.body["sub"], "alice");
+    assert_eq!(access.body["aud"], "api");
+    assert_eq!(access.body["exp"], NOW + 300);
+    let id = verified(&issuer, &response["id_token"]);
+    assert_eq!(id.header["typ"], "JWT");
+    assert_eq!(id.body["aud"], "web");
+    assert_eq!(id.body["auth_time"], NOW);
+
+    let params = form(&[
+        ("grant_type", "password"),
+        ("username", "alice"),
+        ("password", "looking-glass"),
+    ]);
+    let error = issuer.token(&params, None, BASE, NOW).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid_grant: unknown user or wrong password"
+    );
+    let error = issuer
+        .token(&form(&[("grant_type", "password")]), None, BASE, NOW)
+        .unwrap_err();
+    assert_eq!(error.error, "invalid_request");
+    let error = issuer
+        .token(&form(&[("grant_type", "implicit")]), None, BASE, NOW)
+        .unwrap_err();
+    assert_eq!(error.error, "unsupported_grant_type");
+}
+
+#[test]
+fn assert_handle_routes() {
+    let mut issuer = issuer(|config| {
+        config.issuer = Some("http://localhost:9000/realms/test/".to_string());
+    });
+    let get = |issuer: &mut Issuer, url| {
+        let request = server::Request {
+            method: "GET",
+            url,
+            host: None,
+            body: b"",
+        };
+        issuer.handle(&request, None, BASE, NOW)
+    };
+
+    let response = get(&mut issuer, "/realms/test/.well-known/openid-configuration");
+    assert_eq!(response.status, 200);
+    let discovery: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(discovery["issuer"], "http://localhost:9000/realms/test");
+    assert_eq!(
+        discovery["token_endpoint"],
+        "http://localhost:9000/realms/test/token"
+    );
+    assert_eq!(
+        discovery["id_token_signing_alg_values_supported"][0],
+        "ES256"
+    );
+
+    let response = get(&mut issuer, "/realms/test/jwks");
+    let jwks: Value = serde_json::from_slice(&response.body).unwrap();
+    // private members are never published
+    assert!(jwks["keys"][0].get("d").is_none());
+    assert_eq!(get(&mut issuer, "/realms/test/token").status, 405);
+    assert_eq!(get(&mut issuer, "/other").status, 404);
+
+    let request = server::Request {
+        method: "POST",
+        url: "/realms/test/token",
+        host: None,
+        body: b"grant_type=client_credentials&client_id=my%20app",
+    };
+    let response = issuer.handle(&request, None, BASE, NOW);
+    assert_eq!(response.status, 200);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(
+        verified(&issuer, &body["access_token"]).body["sub"],
+        "my app"
+    );
+
+    let request = server::Request {
+        body: b"grant_type=client_credentials",
+        ..request
+    };
+    let response = issuer.handle(&request, None, BASE, NOW);
+    assert_eq!(response.status, 401);
+    assert!(response
+        .headers
+        .iter()
+        .any(|(name, _)| name == "WWW-Authenticate"));
+}
+
+#[test]
+fn assert_config_errors() {
+    assert_eq!(
+        Issuer::new(Config::new(vec![])).unwrap_err().to_string(),
+        "No signing key"
+    );
+    let key = jwk::generate(jwk::KeySpec::P256).unwrap();
+    let public = jwk::to_public_jwks(&key).unwrap()["keys"][0].clone();
+    assert!(Issuer::new(Config::new(vec![public])).is_err());
+    assert_eq!(
+        "ci".parse::<Credentials>().unwrap_err().to_string(),
+        "Invalid credentials `ci`, expected ID:SECRET"
+    );
+    assert_eq!(
+        parse_form(b"a=1+2&b=%41%zz&c"),
+        form(&[("a", "1 2"), ("b", "A%zz"), ("c", "")])
+    );
+}
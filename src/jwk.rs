@@ -0,0 +1,783 @@
+//! # JWK
+//!
+//! `jwk` is a collection of utilities to work with [JSON Web Keys](https://datatracker.ietf.org/doc/html/rfc7517)
+//! (JWKs): computing their [thumbprint](https://datatracker.ietf.org/doc/html/rfc7638),
+//! verifying the signature of a JWT with a public key, signing one with a
+//! private key, converting keys from and to PEM (or DER) and generating new keys.
+//!
+//! Supported keys are `EC` (P-256 and P-384), `RSA` and `OKP` (Ed25519), used
+//! with the `ES256`, `ES384`, `RS256`, `RS384`, `RS512`, `PS256`, `PS384`,
+//! `PS512` and `EdDSA` algorithms.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::jwk;
+//!
+//! let key = serde_json::json!({
+//!     "kty": "EC",
+//!     "crv": "P-256",
+//!     "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+//!     "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0",
+//!     "kid": "key-1"
+//! });
+//! // only the required members (crv, kty, x and y) are part of the thumbprint
+//! assert_eq!(jwk::thumbprint(&key).unwrap(), "oKIywvGUpTVTyxMQ3bwIIeQUudfr_CkLMjCE19ECD-U");
+//! assert!(!jwk::is_private(&key));
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use base64::Engine as _;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use ed25519_dalek::{Signer as _, Verifier as _};
+use rand_core::{OsRng, RngCore};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs8::LineEnding;
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+use rsa::{BigUint, Pkcs1v15Sign, Pss, RsaPrivateKey, RsaPublicKey};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::jwt::get_base64;
+
+/// Represents an error while reading a JWK or verifying a signature with it
+#[derive(Debug)]
+pub enum JwkError {
+    /// Indicates that a required member of the key is missing (or is not a string)
+    MissingMember(String),
+    /// Indicates that the key type or curve is not supported
+    UnsupportedKey(String),
+    /// Indicates that a member of the key does not contain a valid value
+    InvalidKey(String),
+    /// Indicates that the signature algorithm is not supported
+    UnsupportedAlgorithm(String),
+    /// Indicates that the algorithm cannot be used with the type of the key
+    AlgorithmMismatch(String, String),
+    /// Indicates that the token is not a compact JWS
+    InvalidToken(String),
+    /// Indicates that the signature does not match
+    InvalidSignature(),
+    /// Indicates that no key of a set can verify the token (with its `kid`, if any)
+    NoMatchingKey(Option<String>),
+}
+
+impl fmt::Display for JwkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            JwkError::MissingMember(name) => format!("JWK member `{}` is missing", name),
+            JwkError::UnsupportedKey(key) => format!("Unsupported JWK {}", key),
+            JwkError::InvalidKey(e) => format!("Invalid JWK, {}", e),
+            JwkError::UnsupportedAlgorithm(alg) => format!("Unsupported algorithm `{}`", alg),
+            JwkError::AlgorithmMismatch(alg, kty) => {
+                format!("Algorithm `{}` cannot be used with a `{}` key", alg, kty)
+            }
+            JwkError::InvalidToken(e) => format!("Invalid JWS, {}", e),
+            JwkError::InvalidSignature() => "Invalid signature".to_string(),
+            JwkError::NoMatchingKey(Some(kid)) => format!("No key with kid `{}`", kid),
+            JwkError::NoMatchingKey(None) => "No key for the algorithm of the token".to_string(),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for JwkError {}
+
+#[doc(hidden)]
+fn member<'a>(jwk: &'a Value, name: &str) -> Result<&'a str, JwkError> {
+    jwk.get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| JwkError::MissingMember(name.to_string()))
+}
+
+#[doc(hidden)]
+fn decode_member(jwk: &Value, name: &str) -> Result<Vec<u8>, JwkError> {
+    get_base64()
+        .decode(member(jwk, name)?)
+        .map_err(|e| JwkError::InvalidKey(format!("`{}` is not base64url, {}", name, e)))
+}
+
+/// Returns the names of the members that identify a key of the given type
+/// (the ones used by the thumbprint)
+#[doc(hidden)]
+fn required_members(kty: &str) -> Result<&'static [&'static str], JwkError> {
+    match kty {
+        "EC" => Ok(&["crv", "kty", "x", "y"]),
+        "RSA" => Ok(&["e", "kty", "n"]),
+        "OKP" => Ok(&["crv", "kty", "x"]),
+        "oct" => Ok(&["k", "kty"]),
+        other => Err(JwkError::UnsupportedKey(format!("key type `{}`", other))),
+    }
+}
+
+/// Computes the SHA-256 thumbprint of a JWK, base64url encoded (RFC 7638)
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key type is not supported or
+/// a required member is missing
+pub fn thumbprint(jwk: &Value) -> Result<String, JwkError> {
+    let mut members = Map::new();
+    for name in required_members(member(jwk, "kty")?)? {
+        members.insert(name.to_string(), Value::from(member(jwk, name)?));
+    }
+    // the members are serialized in lexicographic order and without whitespace
+    let canonical = Value::Object(members).to_string();
+    Ok(get_base64().encode(Sha256::digest(canonical.as_bytes())))
+}
+
+/// The names of the members that contain private key material
+#[doc(hidden)]
+const PRIVATE_MEMBERS: [&str; 8] = ["d", "p", "q", "dp", "dq", "qi", "oth", "k"];
+
+/// Returns `true` if the JWK contains private (or symmetric) key material
+pub fn is_private(jwk: &Value) -> bool {
+    PRIVATE_MEMBERS.iter().any(|name| jwk.get(name).is_some())
+}
+
+#[doc(hidden)]
+fn rsa_key(jwk: &Value) -> Result<RsaPublicKey, JwkError> {
+    let n = BigUint::from_bytes_be(&decode_member(jwk, "n")?);
+    let e = BigUint::from_bytes_be(&decode_member(jwk, "e")?);
+    RsaPublicKey::new(n, e).map_err(|e| JwkError::InvalidKey(e.to_string()))
+}
+
+#[doc(hidden)]
+fn rsa_private_key(jwk: &Value) -> Result<RsaPrivateKey, JwkError> {
+    let mut primes = Vec::new();
+    if let (Ok(p), Ok(q)) = (decode_member(jwk, "p"), decode_member(jwk, "q")) {
+        primes.push(BigUint::from_bytes_be(&p));
+        primes.push(BigUint::from_bytes_be(&q));
+    }
+    RsaPrivateKey::from_components(
+        BigUint::from_bytes_be(&decode_member(jwk, "n")?),
+        BigUint::from_bytes_be(&decode_member(jwk, "e")?),
+        BigUint::from_bytes_be(&decode_member(jwk, "d")?),
+        primes,
+    )
+    .map_err(|e| JwkError::InvalidKey(e.to_string()))
+}
+
+/// Returns the SEC1 uncompressed point of an `EC` key, checking its curve
+#[doc(hidden)]
+fn ec_point(jwk: &Value, crv: &str) -> Result<Vec<u8>, JwkError> {
+    let actual = member(jwk, "crv")?;
+    if actual != crv {
+        return Err(JwkError::InvalidKey(format!(
+            "expected curve {}, found {}",
+            crv, actual
+        )));
+    }
+    let mut point = vec![0x04];
+    point.extend(decode_member(jwk, "x")?);
+    point.extend(decode_member(jwk, "y")?);
+    Ok(point)
+}
+
+/// Verifies a signature with a public JWK
+///
+/// `alg` is the JWS algorithm (e.g. `ES256`) and ECDSA signatures are expected
+/// in the JWS format (the concatenation of `r` and `s`).
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key or the algorithm is not
+/// supported, if they do not match or if the signature is not valid
+pub fn verify(jwk: &Value, alg: &str, message: &[u8], signature: &[u8]) -> Result<(), JwkError> {
+    let kty = member(jwk, "kty")?;
+    let expected_kty = match alg {
+        "ES256" | "ES384" => "EC",
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => "RSA",
+        "EdDSA" | "Ed25519" => "OKP",
+        other => return Err(JwkError::UnsupportedAlgorithm(other.to_string())),
+    };
+    if kty != expected_kty {
+        return Err(JwkError::AlgorithmMismatch(
+            alg.to_string(),
+            kty.to_string(),
+        ));
+    }
+
+    let valid = match alg {
+        "ES256" => {
+            let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&ec_point(jwk, "P-256")?)
+                .map_err(|_| JwkError::InvalidKey("not a valid P-256 point".to_string()))?;
+            p256::ecdsa::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok())
+        }
+        "ES384" => {
+            let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(&ec_point(jwk, "P-384")?)
+                .map_err(|_| JwkError::InvalidKey("not a valid P-384 point".to_string()))?;
+            p384::ecdsa::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok())
+        }
+        "EdDSA" | "Ed25519" => {
+            let crv = member(jwk, "crv")?;
+            if crv != "Ed25519" {
+                return Err(JwkError::UnsupportedKey(format!("curve `{}`", crv)));
+            }
+            let x = <[u8; 32]>::try_from(decode_member(jwk, "x")?.as_slice())
+                .map_err(|_| JwkError::InvalidKey("`x` must be 32 bytes long".to_string()))?;
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&x)
+                .map_err(|e| JwkError::InvalidKey(e.to_string()))?;
+            ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok())
+        }
+        rsa_alg => {
+            let key = rsa_key(jwk)?;
+            let result = match rsa_alg {
+                "RS256" => key.verify(
+                    Pkcs1v15Sign::new::<Sha256>(),
+                    &Sha256::digest(message),
+                    signature,
+                ),
+                "RS384" => key.verify(
+                    Pkcs1v15Sign::new::<Sha384>(),
+                    &Sha384::digest(message),
+                    signature,
+                ),
+                "RS512" => key.verify(
+                    Pkcs1v15Sign::new::<Sha512>(),
+                    &Sha512::digest(message),
+                    signature,
+                ),
+                "PS256" => key.verify(Pss::new::<Sha256>(), &Sha256::digest(message), signature),
+                "PS384" => key.verify(Pss::new::<Sha384>(), &Sha384::digest(message), signature),
+                _ => key.verify(Pss::new::<Sha512>(), &Sha512::digest(message), signature),
+            };
+            result.is_ok()
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(JwkError::InvalidSignature())
+    }
+}
+
+/// Returns the algorithm a key signs with: its `alg` member or, without it,
+/// `RS256`, `ES256`, `ES384` or `EdDSA`, depending on the type of the key
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key type or curve is not supported
+pub fn default_algorithm(jwk: &Value) -> Result<String, JwkError> {
+    if let Some(alg) = jwk.get("alg").and_then(|alg| alg.as_str()) {
+        return Ok(alg.to_string());
+    }
+    let alg = match (member(jwk, "kty")?, jwk.get("crv").and_then(|c| c.as_str())) {
+        ("RSA", _) => "RS256",
+        ("EC", Some("P-256")) => "ES256",
+        ("EC", Some("P-384")) => "ES384",
+        ("OKP", Some("Ed25519")) => "EdDSA",
+        (kty, crv) => {
+            return Err(JwkError::UnsupportedKey(match crv {
+                Some(crv) => format!("curve `{}`", crv),
+                None => format!("key type `{}`", kty),
+            }))
+        }
+    };
+    Ok(alg.to_string())
+}
+
+/// Signs a message with a private JWK, with the same algorithms as `verify`
+///
+/// ECDSA signatures are returned in the JWS format (the concatenation of `r` and `s`).
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key is not a supported
+/// private key or cannot be used with the algorithm
+pub fn sign(jwk: &Value, alg: &str, message: &[u8]) -> Result<Vec<u8>, JwkError> {
+    let kty = member(jwk, "kty")?;
+    let expected_kty = match alg {
+        "ES256" | "ES384" => "EC",
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => "RSA",
+        "EdDSA" | "Ed25519" => "OKP",
+        other => return Err(JwkError::UnsupportedAlgorithm(other.to_string())),
+    };
+    if kty != expected_kty {
+        return Err(JwkError::AlgorithmMismatch(
+            alg.to_string(),
+            kty.to_string(),
+        ));
+    }
+    let invalid = |e: &dyn fmt::Display| JwkError::InvalidKey(e.to_string());
+    match alg {
+        "ES256" => {
+            ec_point(jwk, "P-256")?;
+            let key = p256::ecdsa::SigningKey::from_slice(&decode_member(jwk, "d")?)
+                .map_err(|e| invalid(&e))?;
+            let signature: p256::ecdsa::Signature = key.sign(message);
+            Ok(signature.to_vec())
+        }
+        "ES384" => {
+            ec_point(jwk, "P-384")?;
+            let key = p384::ecdsa::SigningKey::from_slice(&decode_member(jwk, "d")?)
+                .map_err(|e| invalid(&e))?;
+            let signature: p384::ecdsa::Signature = key.sign(message);
+            Ok(signature.to_vec())
+        }
+        "EdDSA" | "Ed25519" => {
+            let crv = member(jwk, "crv")?;
+            if crv != "Ed25519" {
+                return Err(JwkError::UnsupportedKey(format!("curve `{}`", crv)));
+            }
+            let d = <[u8; 32]>::try_from(decode_member(jwk, "d")?.as_slice())
+                .map_err(|_| JwkError::InvalidKey("`d` must be 32 bytes long".to_string()))?;
+            let key = ed25519_dalek::SigningKey::from_bytes(&d);
+            Ok(key.sign(message).to_bytes().to_vec())
+        }
+        rsa_alg => {
+            let key = rsa_private_key(jwk)?;
+            let result = match rsa_alg {
+                "RS256" => key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(message)),
+                "RS384" => key.sign(Pkcs1v15Sign::new::<Sha384>(), &Sha384::digest(message)),
+                "RS512" => key.sign(Pkcs1v15Sign::new::<Sha512>(), &Sha512::digest(message)),
+                "PS256" => {
+                    key.sign_with_rng(&mut OsRng, Pss::new::<Sha256>(), &Sha256::digest(message))
+                }
+                "PS384" => {
+                    key.sign_with_rng(&mut OsRng, Pss::new::<Sha384>(), &Sha384::digest(message))
+                }
+                _ => key.sign_with_rng(&mut OsRng, Pss::new::<Sha512>(), &Sha512::digest(message)),
+            };
+            result.map_err(|e| invalid(&e))
+        }
+    }
+}
+
+/// Creates a compact JWS (e.g. a JWT) signed with a private JWK
+///
+/// The `alg` of the header defaults to `default_algorithm` and its `kid` to
+/// the `kid` of the key, if any.
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the header is not a JSON object
+/// or if the payload cannot be signed (see `sign`)
+pub fn sign_jws(header: &Value, payload: &Value, jwk: &Value) -> Result<String, JwkError> {
+    let mut header = header.clone();
+    let members = header
+        .as_object_mut()
+        .ok_or_else(|| JwkError::InvalidToken("the header must be an object".to_string()))?;
+    if !members.contains_key("alg") {
+        members.insert("alg".to_string(), Value::from(default_algorithm(jwk)?));
+    }
+    if let (false, Some(kid)) = (members.contains_key("kid"), jwk.get("kid")) {
+        members.insert("kid".to_string(), kid.clone());
+    }
+    let alg = members["alg"].as_str().unwrap_or_default().to_string();
+    let signing_input = format!(
+        "{}.{}",
+        get_base64().encode(header.to_string()),
+        get_base64().encode(payload.to_string())
+    );
+    let signature = sign(jwk, &alg, signing_input.as_bytes())?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        get_base64().encode(signature)
+    ))
+}
+
+/// Verifies the signature of a compact JWS (e.g. a JWT) with a public JWK,
+/// using the algorithm declared in its header
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the token is not a compact JWS or
+/// if the signature cannot be verified (see `verify`)
+pub fn verify_jws(token: &str, jwk: &Value) -> Result<(), JwkError> {
+    let (signing_input, signature) = token
+        .rsplit_once('.')
+        .ok_or_else(|| JwkError::InvalidToken("missing signature".to_string()))?;
+    let header = jws_header(signing_input)?;
+    let alg = header
+        .get("alg")
+        .and_then(|a| a.as_str())
+        .ok_or_else(|| JwkError::InvalidToken("missing alg".to_string()))?;
+    let signature = get_base64()
+        .decode(signature)
+        .map_err(|e| JwkError::InvalidToken(e.to_string()))?;
+    verify(jwk, alg, signing_input.as_bytes(), &signature)
+}
+
+/// Decodes the header of a compact JWS
+#[doc(hidden)]
+fn jws_header(token: &str) -> Result<Value, JwkError> {
+    token
+        .split('.')
+        .next()
+        .and_then(|h| get_base64().decode(h).ok())
+        .and_then(|h| serde_json::from_slice::<Value>(&h).ok())
+        .ok_or_else(|| JwkError::InvalidToken("invalid header".to_string()))
+}
+
+/// Verifies the signature of a compact JWS with a set of public JWKs and
+/// returns the key that verified it
+///
+/// Only the keys with the same `kid` as the token (if it has one) and, when
+/// they declare it, the same `alg` are tried.
+///
+/// # Errors
+///
+/// This function will return `JwkError::NoMatchingKey` if no key can be tried,
+/// or the error of the last key tried (see `verify_jws`)
+pub fn verify_jws_with_keys<'a>(token: &str, keys: &'a [Value]) -> Result<&'a Value, JwkError> {
+    let header = jws_header(token)?;
+    let kid = header.get("kid").and_then(|kid| kid.as_str());
+    let alg = header.get("alg").and_then(|alg| alg.as_str());
+    let mut error = JwkError::NoMatchingKey(kid.map(String::from));
+    for key in keys {
+        let key_kid = key.get("kid").and_then(|kid| kid.as_str());
+        let key_alg = key.get("alg").and_then(|alg| alg.as_str());
+        if (kid.is_some() && key_kid != kid) || (key_alg.is_some() && key_alg != alg) {
+            continue;
+        }
+        match verify_jws(token, key) {
+            Ok(()) => return Ok(key),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// Reads keys given as a JWK, a JWK Set (`{"keys": [...]}`) or a PEM or DER
+/// encoded key (see `from_pem`)
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the input is none of them
+pub fn read_keys(input: &[u8]) -> Result<Vec<Value>, JwkError> {
+    let is_json = std::str::from_utf8(input).is_ok_and(|text| text.trim_start().starts_with('{'));
+    if !is_json {
+        return Ok(vec![from_pem(input)?]);
+    }
+    let value: Value = serde_json::from_slice(input)
+        .map_err(|e| JwkError::InvalidKey(format!("not valid JSON, {}", e)))?;
+    match value.get("keys") {
+        Some(Value::Array(keys)) => Ok(keys.clone()),
+        Some(_) => Err(JwkError::InvalidKey("`keys` must be an array".to_string())),
+        None => Ok(vec![value]),
+    }
+}
+
+/// The keys that can be generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySpec {
+    /// an `RSA` key with the given modulus size, in bits
+    Rsa(usize),
+    /// an `EC` key on the P-256 curve
+    P256,
+    /// an `EC` key on the P-384 curve
+    P384,
+    /// an `OKP` key on the Ed25519 curve
+    Ed25519,
+    /// an `oct` (symmetric) key with the given size, in bits
+    Oct(usize),
+}
+
+/// Generates a new private (or symmetric) JWK, with its thumbprint as `kid`
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the size of the key is not valid
+pub fn generate(spec: KeySpec) -> Result<Value, JwkError> {
+    let mut jwk = match spec {
+        KeySpec::Rsa(bits) => {
+            if bits < 2048 {
+                return Err(JwkError::InvalidKey(
+                    "RSA keys must be at least 2048 bits long".to_string(),
+                ));
+            }
+            let key = RsaPrivateKey::new(&mut OsRng, bits)
+                .map_err(|e| JwkError::InvalidKey(e.to_string()))?;
+            rsa_private_jwk(&key)
+        }
+        KeySpec::P256 => ec_jwk_value(&p256::SecretKey::random(&mut OsRng).to_jwk_string()),
+        KeySpec::P384 => ec_jwk_value(&p384::SecretKey::random(&mut OsRng).to_jwk_string()),
+        KeySpec::Ed25519 => ed25519_private_jwk(&ed25519_dalek::SigningKey::generate(&mut OsRng)),
+        KeySpec::Oct(bits) => {
+            if bits == 0 || bits % 8 != 0 {
+                return Err(JwkError::InvalidKey(
+                    "the size of oct keys must be a multiple of 8 bits".to_string(),
+                ));
+            }
+            let mut k = vec![0u8; bits / 8];
+            OsRng.fill_bytes(&mut k);
+            serde_json::json!({"kty": "oct", "k": get_base64().encode(k)})
+        }
+    };
+    jwk["kid"] = Value::from(thumbprint(&jwk)?);
+    Ok(jwk)
+}
+
+/// Returns the public part of a JWK (all its members but the private ones)
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key is symmetric (`oct`)
+pub fn to_public(jwk: &Value) -> Result<Value, JwkError> {
+    if member(jwk, "kty")? == "oct" {
+        return Err(JwkError::UnsupportedKey(
+            "symmetric (oct) key, it has no public part".to_string(),
+        ));
+    }
+    let mut public = jwk.clone();
+    if let Some(members) = public.as_object_mut() {
+        for name in PRIVATE_MEMBERS.iter() {
+            members.remove(*name);
+        }
+    }
+    Ok(public)
+}
+
+/// Returns a JWK Set with the public part of the given keys
+///
+/// `keys` can be a single JWK or a JWK Set; symmetric (`oct`) keys are skipped.
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if a key has no `kty`
+pub fn to_public_jwks(keys: &Value) -> Result<Value, JwkError> {
+    let keys = match keys.get("keys").and_then(|k| k.as_array()) {
+        Some(keys) => keys.clone(),
+        None => vec![keys.clone()],
+    };
+    let mut public = Vec::new();
+    for key in &keys {
+        if member(key, "kty")? != "oct" {
+            public.push(to_public(key)?);
+        }
+    }
+    Ok(serde_json::json!({ "keys": public }))
+}
+
+#[doc(hidden)]
+fn encode_biguint(value: &BigUint) -> Value {
+    Value::from(get_base64().encode(value.to_bytes_be()))
+}
+
+#[doc(hidden)]
+fn rsa_public_jwk(key: &RsaPublicKey) -> Value {
+    serde_json::json!({
+        "kty": "RSA",
+        "n": encode_biguint(key.n()),
+        "e": encode_biguint(key.e()),
+    })
+}
+
+#[doc(hidden)]
+fn rsa_private_jwk(key: &RsaPrivateKey) -> Value {
+    let mut jwk = rsa_public_jwk(&key.to_public_key());
+    let primes = key.primes();
+    jwk["d"] = encode_biguint(key.d());
+    if let [p, q] = primes {
+        let one = BigUint::from(1u8);
+        jwk["p"] = encode_biguint(p);
+        jwk["q"] = encode_biguint(q);
+        jwk["dp"] = encode_biguint(&(key.d() % (p - &one)));
+        jwk["dq"] = encode_biguint(&(key.d() % (q - &one)));
+        if let Some(qi) = key.crt_coefficient() {
+            jwk["qi"] = encode_biguint(&qi);
+        }
+    }
+    jwk
+}
+
+#[doc(hidden)]
+fn ec_jwk_value(jwk: &str) -> Value {
+    serde_json::from_str(jwk).unwrap_or_default()
+}
+
+#[doc(hidden)]
+fn ed25519_public_jwk(key: &ed25519_dalek::VerifyingKey) -> Value {
+    serde_json::json!({
+        "kty": "OKP",
+        "crv": "Ed25519",
+        "x": get_base64().encode(key.as_bytes()),
+    })
+}
+
+#[doc(hidden)]
+fn ed25519_private_jwk(key: &ed25519_dalek::SigningKey) -> Value {
+    let mut jwk = ed25519_public_jwk(&key.verifying_key());
+    jwk["d"] = Value::from(get_base64().encode(key.to_bytes()));
+    jwk
+}
+
+/// Converts a PEM or DER encoded key to a JWK
+///
+/// Private keys can be PKCS#8, PKCS#1 (RSA) or SEC1 (EC) encoded, public keys
+/// SubjectPublicKeyInfo or PKCS#1 (RSA) encoded. Supported keys are RSA, EC
+/// (P-256 and P-384) and Ed25519.
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key cannot be decoded
+pub fn from_pem(input: &[u8]) -> Result<Value, JwkError> {
+    let jwk = match std::str::from_utf8(input) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => {
+            let pem = pem.trim();
+            RsaPrivateKey::from_pkcs8_pem(pem)
+                .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+                .map(|key| rsa_private_jwk(&key))
+                .or_else(|_| RsaPublicKey::from_public_key_pem(pem).map(|key| rsa_public_jwk(&key)))
+                .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem).map(|key| rsa_public_jwk(&key)))
+                .ok()
+                .or_else(|| {
+                    p256::SecretKey::from_pkcs8_pem(pem)
+                        .or_else(|_| p256::SecretKey::from_sec1_pem(pem))
+                        .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                        .ok()
+                })
+                .or_else(|| {
+                    p384::SecretKey::from_pkcs8_pem(pem)
+                        .or_else(|_| p384::SecretKey::from_sec1_pem(pem))
+                        .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                        .ok()
+                })
+                .or_else(|| {
+                    p256::PublicKey::from_public_key_pem(pem)
+                        .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                        .ok()
+                })
+                .or_else(|| {
+                    p384::PublicKey::from_public_key_pem(pem)
+                        .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                        .ok()
+                })
+                .or_else(|| {
+                    ed25519_dalek::SigningKey::from_pkcs8_pem(pem)
+                        .map(|key| ed25519_private_jwk(&key))
+                        .ok()
+                })
+                .or_else(|| {
+                    ed25519_dalek::VerifyingKey::from_public_key_pem(pem)
+                        .map(|key| ed25519_public_jwk(&key))
+                        .ok()
+                })
+        }
+        _ => RsaPrivateKey::from_pkcs8_der(input)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_der(input))
+            .map(|key| rsa_private_jwk(&key))
+            .or_else(|_| RsaPublicKey::from_public_key_der(input).map(|key| rsa_public_jwk(&key)))
+            .or_else(|_| RsaPublicKey::from_pkcs1_der(input).map(|key| rsa_public_jwk(&key)))
+            .ok()
+            .or_else(|| {
+                p256::SecretKey::from_pkcs8_der(input)
+                    .or_else(|_| p256::SecretKey::from_sec1_der(input))
+                    .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                    .ok()
+            })
+            .or_else(|| {
+                p384::SecretKey::from_pkcs8_der(input)
+                    .or_else(|_| p384::SecretKey::from_sec1_der(input))
+                    .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                    .ok()
+            })
+            .or_else(|| {
+                p256::PublicKey::from_public_key_der(input)
+                    .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                    .ok()
+            })
+            .or_else(|| {
+                p384::PublicKey::from_public_key_der(input)
+                    .map(|key| ec_jwk_value(&key.to_jwk_string()))
+                    .ok()
+            })
+            .or_else(|| {
+                ed25519_dalek::SigningKey::from_pkcs8_der(input)
+                    .map(|key| ed25519_private_jwk(&key))
+                    .ok()
+            })
+            .or_else(|| {
+                ed25519_dalek::VerifyingKey::from_public_key_der(input)
+                    .map(|key| ed25519_public_jwk(&key))
+                    .ok()
+            }),
+    };
+    jwk.ok_or_else(|| JwkError::InvalidKey("not a supported PEM or DER encoded key".to_string()))
+}
+
+/// Converts a JWK to PEM: PKCS#8 for private keys, SubjectPublicKeyInfo for public keys
+///
+/// # Errors
+///
+/// This function will return a `JwkError` if the key is not valid, or if it
+/// is not an RSA, EC (P-256 or P-384) or Ed25519 key
+pub fn to_pem(jwk: &Value) -> Result<String, JwkError> {
+    let private = jwk.get("d").is_some();
+    let invalid = |e: &dyn fmt::Display| JwkError::InvalidKey(e.to_string());
+    match member(jwk, "kty")? {
+        "RSA" if private => rsa_private_key(jwk)?
+            .to_pkcs8_pem(LineEnding::LF)
+            .map(|pem| pem.to_string())
+            .map_err(|e| invalid(&e)),
+        "RSA" => rsa_key(jwk)?
+            .to_public_key_pem(LineEnding::LF)
+            .map_err(|e| invalid(&e)),
+        "EC" => {
+            // the EC parsers reject members they don't know, such as `kid`
+            let mut members = Map::new();
+            for name in ["kty", "crv", "x", "y", "d"].iter() {
+                if let Some(value) = jwk.get(*name) {
+                    members.insert(name.to_string(), value.clone());
+                }
+            }
+            let encoded = Value::Object(members).to_string();
+            match (member(jwk, "crv")?, private) {
+                ("P-256", true) => p256::SecretKey::from_jwk_str(&encoded)
+                    .map_err(|e| invalid(&e))?
+                    .to_pkcs8_pem(LineEnding::LF)
+                    .map(|pem| pem.to_string())
+                    .map_err(|e| invalid(&e)),
+                ("P-256", false) => p256::PublicKey::from_jwk_str(&encoded)
+                    .map_err(|e| invalid(&e))?
+                    .to_public_key_pem(LineEnding::LF)
+                    .map_err(|e| invalid(&e)),
+                ("P-384", true) => p384::SecretKey::from_jwk_str(&encoded)
+                    .map_err(|e| invalid(&e))?
+                    .to_pkcs8_pem(LineEnding::LF)
+                    .map(|pem| pem.to_string())
+                    .map_err(|e| invalid(&e)),
+                ("P-384", false) => p384::PublicKey::from_jwk_str(&encoded)
+                    .map_err(|e| invalid(&e))?
+                    .to_public_key_pem(LineEnding::LF)
+                    .map_err(|e| invalid(&e)),
+                (crv, _) => Err(JwkError::UnsupportedKey(format!("curve `{}`", crv))),
+            }
+        }
+        "OKP" => {
+            let crv = member(jwk, "crv")?;
+            if crv != "Ed25519" {
+                return Err(JwkError::UnsupportedKey(format!("curve `{}`", crv)));
+            }
+            let name = if private { "d" } else { "x" };
+            let bytes = <[u8; 32]>::try_from(decode_member(jwk, name)?.as_slice())
+                .map_err(|_| JwkError::InvalidKey(format!("`{}` must be 32 bytes long", name)))?;
+            if private {
+                ed25519_dalek::SigningKey::from_bytes(&bytes)
+                    .to_pkcs8_pem(LineEnding::LF)
+                    .map(|pem| pem.to_string())
+                    .map_err(|e| invalid(&e))
+            } else {
+                ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                    .map_err(|e| invalid(&e))?
+                    .to_public_key_pem(LineEnding::LF)
+                    .map_err(|e| invalid(&e))
+            }
+        }
+        other => Err(JwkError::UnsupportedKey(format!(
+            "key type `{}`, it has no PEM encoding",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,250 @@
+//! # Lint
+//!
+//! `lint` looks for common mistakes and risky choices in the header and claims
+//! of a JWT: unsigned tokens, missing or inconsistent time claims, very long
+//! lifetimes and header parameters that point to keys chosen by the sender.
+//!
+//! Unlike the checks of a `profile`, findings don't mean that a token must be
+//! rejected: they are hints for whoever is debugging or reviewing a token.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::lint;
+//!
+//! let header = serde_json::json!({"alg": "none"});
+//! let claims = serde_json::json!({"sub": "1234567890", "iat": 1516239022});
+//! let findings = lint::lint(&header, &claims, 1516239022);
+//! assert_eq!(findings[0].to_string(), "[error] alg-none: the token is not signed (alg is none)");
+//! assert_eq!(findings[1].rule, "missing-exp");
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::timestamp;
+
+/// Tokens valid for longer than this (in seconds) are reported by `long-lifetime`
+pub const MAX_LIFETIME: u64 = 24 * 3600;
+
+/// The tolerated clock skew (in seconds) when comparing `iat` with the current time
+pub const CLOCK_SKEW: u64 = 60;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// worth knowing, but not a problem in itself
+    Info,
+    /// a risky choice, or a token that cannot be used right now
+    Warning,
+    /// a token that no verifier should accept
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Represents a problem found in a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// a short identifier of the rule (e.g. `alg-none`)
+    pub rule: String,
+    /// how serious the problem is
+    pub severity: Severity,
+    /// a human readable description of the problem
+    pub message: String,
+}
+
+impl Finding {
+    #[doc(hidden)]
+    fn new<M: Into<String>>(rule: &str, severity: Severity, message: M) -> Self {
+        Self {
+            rule: rule.to_string(),
+            severity,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the finding as a JSON object with `rule`, `severity` and `message` fields
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "rule": self.rule,
+            "severity": self.severity.to_string(),
+            "message": self.message,
+        })
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.rule, self.message)
+    }
+}
+
+/// Checks the header parameters of a token
+#[doc(hidden)]
+fn lint_header(header: &Value, findings: &mut Vec<Finding>) {
+    match header.get("alg").and_then(|alg| alg.as_str()) {
+        None => findings.push(Finding::new(
+            "missing-alg",
+            Severity::Error,
+            "the header has no alg",
+        )),
+        Some(alg) if alg.eq_ignore_ascii_case("none") => findings.push(Finding::new(
+            "alg-none",
+            Severity::Error,
+            "the token is not signed (alg is none)",
+        )),
+        Some(alg) if alg.starts_with("HS") => findings.push(Finding::new(
+            "symmetric-alg",
+            Severity::Info,
+            format!(
+                "{} uses a shared secret: every party able to verify the token can also mint one",
+                alg
+            ),
+        )),
+        Some(_) if header.get("kid").is_none() && header.get("enc").is_none() => {
+            findings.push(Finding::new(
+                "missing-kid",
+                Severity::Info,
+                "the header has no kid: verifiers cannot pick the key when it is rotated",
+            ))
+        }
+        Some(_) => {}
+    }
+    for name in ["jku", "x5u"] {
+        if header.get(name).is_some() {
+            findings.push(Finding::new(
+                "remote-key-url",
+                Severity::Warning,
+                format!(
+                    "{} points to a key chosen by the sender: it must only be followed for trusted URLs",
+                    name
+                ),
+            ));
+        }
+    }
+    let is_dpop = header.get("typ").and_then(|typ| typ.as_str()) == Some("dpop+jwt");
+    if header.get("jwk").is_some() && !is_dpop {
+        findings.push(Finding::new(
+            "embedded-jwk",
+            Severity::Warning,
+            "jwk embeds the key of the sender: verifying with it proves nothing about the issuer",
+        ));
+    }
+}
+
+/// Reads a time claim, reporting it if it is not a NumericDate
+#[doc(hidden)]
+fn time_claim(claims: &Value, name: &str, findings: &mut Vec<Finding>) -> Option<i64> {
+    let value = claims.get(name)?;
+    let secs = value.as_i64().or_else(|| value.as_f64().map(|f| f as i64));
+    if secs.is_none() {
+        findings.push(Finding::new(
+            "invalid-time-claim",
+            Severity::Error,
+            format!("{} must be a number of seconds, found {}", name, value),
+        ));
+    }
+    secs
+}
+
+/// Checks the time claims of a token
+#[doc(hidden)]
+fn lint_claims(claims: &Value, now: i64, findings: &mut Vec<Finding>) {
+    let exp = time_claim(claims, "exp", findings);
+    let nbf = time_claim(claims, "nbf", findings);
+    let iat = time_claim(claims, "iat", findings);
+    time_claim(claims, "auth_time", findings);
+
+    match exp {
+        None if claims.get("exp").is_none() => findings.push(Finding::new(
+            "missing-exp",
+            Severity::Warning,
+            "the token has no exp: it never expires",
+        )),
+        Some(exp) if exp <= now => findings.push(Finding::new(
+            "expired",
+            Severity::Error,
+            format!("the token expired {}", timestamp::format_relative(exp, now)),
+        )),
+        _ => {}
+    }
+    if let Some(nbf) = nbf.filter(|&nbf| nbf > now) {
+        findings.push(Finding::new(
+            "not-yet-valid",
+            Severity::Warning,
+            format!(
+                "the token is not valid before {} ({})",
+                timestamp::format_iso8601(nbf),
+                timestamp::format_relative(nbf, now)
+            ),
+        ));
+    }
+    if let Some(iat) = iat.filter(|&iat| iat > now.saturating_add(CLOCK_SKEW as i64)) {
+        findings.push(Finding::new(
+            "iat-in-future",
+            Severity::Warning,
+            format!(
+                "the token is issued {}: the clock of the issuer may be wrong",
+                timestamp::format_relative(iat, now)
+            ),
+        ));
+    }
+
+    if let Some(exp) = exp {
+        for (name, start) in [("iat", iat), ("nbf", nbf)] {
+            if start.is_some_and(|start| start >= exp) {
+                findings.push(Finding::new(
+                    "inconsistent-times",
+                    Severity::Error,
+                    format!("exp is not after {}: the token is never valid", name),
+                ));
+            }
+        }
+        if let Some(start) = nbf.or(iat).filter(|&start| start < exp) {
+            let lifetime = exp.abs_diff(start);
+            if lifetime > MAX_LIFETIME {
+                findings.push(Finding::new(
+                    "long-lifetime",
+                    Severity::Info,
+                    format!(
+                        "the token is valid for {}: stolen tokens stay usable for long",
+                        timestamp::format_duration(lifetime)
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Returns the findings for a token, the most serious first
+///
+/// The claims are only checked when they are a JSON object (they are a string
+/// for encrypted tokens and for tokens nesting another token).
+pub fn lint(header: &Value, claims: &Value, now: u64) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    lint_header(header, &mut findings);
+    if claims.is_object() {
+        lint_claims(
+            claims,
+            i64::try_from(now).unwrap_or(i64::MAX),
+            &mut findings,
+        );
+    }
+    // a stable sort keeps the order of the rules for the same severity
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+    findings
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,107 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+const NOW: u64 = 1516239022;
+
+#[doc(hidden)]
+fn rules(findings: &[Finding]) -> Vec<&str> {
+    findings.iter().map(|f| f.rule.as_str()).collect()
+}
+
+#[test]
+fn assert_clean_token_has_no_findings() {
+    let header = serde_json::json!({"alg": "RS256", "kid": "key-1"});
+    let claims = serde_json::json!({"iat": NOW, "exp": NOW + 3600});
+    assert_eq!(lint(&header, &claims, NOW), vec![]);
+}
+
+#[test]
+fn assert_header_findings() {
+    let claims = serde_json::json!({"exp": NOW + 60});
+    let findings = lint(&serde_json::json!({"typ": "JWT"}), &claims, NOW);
+    assert_eq!(rules(&findings), vec!["missing-alg"]);
+
+    let findings = lint(&serde_json::json!({"alg": "HS256"}), &claims, NOW);
+    assert_eq!(rules(&findings), vec!["symmetric-alg"]);
+    assert_eq!(findings[0].severity, Severity::Info);
+
+    let header = serde_json::json!({"alg": "ES256", "jku": "https://example.com/keys", "jwk": {}});
+    let findings = lint(&header, &claims, NOW);
+    assert_eq!(
+        rules(&findings),
+        vec!["remote-key-url", "embedded-jwk", "missing-kid"]
+    );
+
+    // the jwk of a DPoP proof is expected
+    let header = serde_json::json!({"alg": "ES256", "typ": "dpop+jwt", "jwk": {}, "kid": "k"});
+    assert_eq!(lint(&header, &claims, NOW), vec![]);
+}
+
+#[test]
+fn assert_time_findings() {
+    let header = serde_json::json!({"alg": "none"});
+    let claims = serde_json::json!({"iat": NOW + 3600, "nbf": NOW + 7200, "exp": NOW - 10});
+    let findings = lint(&header, &claims, NOW);
+    assert_eq!(
+        rules(&findings),
+        vec![
+            "alg-none",
+            "expired",
+            "inconsistent-times",
+            "inconsistent-times",
+            "not-yet-valid",
+            "iat-in-future",
+        ]
+    );
+    assert_eq!(
+        findings[1].to_string(),
+        "[error] expired: the token expired 10 seconds ago"
+    );
+}
+
+#[test]
+fn assert_lifetime_and_invalid_claims() {
+    let header = serde_json::json!({"alg": "EdDSA", "kid": "k"});
+    let claims = serde_json::json!({"iat": NOW, "exp": NOW + 30 * 86_400});
+    let findings = lint(&header, &claims, NOW);
+    assert_eq!(
+        findings[0].message,
+        "the token is valid for 30 days: stolen tokens stay usable for long"
+    );
+
+    let claims = serde_json::json!({"iat": i64::MIN, "exp": i64::MAX});
+    let findings = lint(&header, &claims, NOW);
+    assert_eq!(
+        findings[0].message,
+        "the token is valid for 584942417355 years: stolen tokens stay usable for long"
+    );
+
+    // the current time and the clock skew saturate instead of overflowing
+    let claims = serde_json::json!({"iat": i64::MAX, "exp": i64::MAX});
+    for now in [i64::MAX as u64 - 1, u64::MAX] {
+        let findings = lint(&header, &claims, now);
+        assert!(!rules(&findings).contains(&"iat-in-future"));
+    }
+
+    let claims = serde_json::json!({"exp": "tomorrow"});
+    let findings = lint(&header, &claims, NOW);
+    assert_eq!(rules(&findings), vec!["invalid-time-claim"]);
+    assert_eq!(
+        findings[0].to_json(),
+        serde_json::json!({
+            "rule": "invalid-time-claim",
+            "severity": "error",
+            "message": "exp must be a number of seconds, found \"tomorrow\"",
+        })
+    );
+}
+
+#[test]
+fn assert_claims_of_encrypted_tokens_are_skipped() {
+    let header = serde_json::json!({"alg": "RSA-OAEP", "enc": "A256GCM"});
+    assert_eq!(
+        lint(&header, &Value::String("...".to_string()), NOW),
+        vec![]
+    );
+}
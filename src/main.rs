@@ -41,4 +41,33 @@
 //! assert_eq!(token.body.to_string(), "{\"iat\":1516239022,\"name\":\"John Doe\",\"sub\":\"1234567890\"}");
 //! ```
 
+pub mod batch;
+pub mod color;
+pub mod cwt;
+#[cfg(feature = "crypto")]
+pub mod dpop;
+pub mod expiry;
+pub mod explain;
+#[cfg(feature = "server")]
+pub mod gateway;
+pub mod har;
+#[cfg(feature = "server")]
+pub mod issuer;
+#[cfg(feature = "crypto")]
+pub mod jwk;
 pub mod jwt;
+pub mod lint;
+#[cfg(feature = "crypto")]
+pub mod paseto;
+pub mod profile;
+pub mod provider;
+pub mod redact;
+pub mod sdjwt;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod template;
+pub mod timestamp;
+pub mod validation;
+pub mod watch;
+#[cfg(feature = "crypto")]
+pub mod x5c;
@@ -0,0 +1,533 @@
+//! # PASETO
+//!
+//! `paseto` is a collection of utilities to inspect [PASETO](https://github.com/paseto-standard/paseto-spec)
+//! tokens (Platform-Agnostic SEcurity TOkens), versions 1 to 4.
+//!
+//! A PASETO has the form `version.purpose.payload[.footer]`. The payload of a
+//! `public` token is the JSON message followed by its signature, so the claims
+//! can always be decoded, while the payload of a `local` token is encrypted and
+//! can only be read with the symmetric key.
+//!
+//! Keys can be given as hex, as [PASERK](https://github.com/paseto-standard/paserk)
+//! strings (e.g. `k4.public.…` or `k4.local.…`) or, for public keys, as PEM.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::paseto;
+//!
+//! let token = paseto::parse("v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA").unwrap();
+//! assert_eq!(token.header().to_string(), "{\"purpose\":\"public\",\"version\":\"v4\"}");
+//! assert_eq!(token.claims["data"], "this is a signed message");
+//! assert!(token.verify("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2", b"").is_ok());
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str;
+
+use aes::Aes256;
+use base64::Engine as _;
+use blake2::digest::consts::{U32, U56};
+use blake2::Blake2bMac;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::XChaCha20;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::XChaCha20Poly1305;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use p384::ecdsa::signature::Verifier;
+use p384::pkcs8::DecodePublicKey;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::{Pss, RsaPublicKey};
+use serde_json::Value;
+use sha2::{Digest, Sha384};
+
+use crate::jwt::get_base64;
+
+/// The placeholder shown as claims of a `local` token that has not been decrypted
+const ENCRYPTED_PAYLOAD: &str = "<encrypted PASETO payload>";
+
+/// Represents an error while parsing, verifying or decrypting a PASETO
+#[derive(Debug)]
+pub enum PasetoError {
+    /// Indicates that the token does not start with a supported `version.purpose.` prefix
+    UnsupportedHeader(String),
+    /// Indicates that the token does not have the expected structure
+    InvalidFormat(&'static str),
+    /// Indicates that the payload or the footer was not correctly Base64-encoded
+    InvalidBase64(base64::DecodeError),
+    /// Indicates that the (decrypted) message is not valid JSON
+    InvalidJSON(serde_json::error::Error),
+    /// Indicates that the given key cannot be used with the token
+    InvalidKey(String),
+    /// Indicates that the signature of a `public` token does not match
+    InvalidSignature(),
+    /// Indicates that the authentication tag of a `local` token does not match
+    DecryptionFailed(),
+}
+
+impl fmt::Display for PasetoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            PasetoError::UnsupportedHeader(header) => {
+                format!("Unsupported PASETO header `{}`", header)
+            }
+            PasetoError::InvalidFormat(e) => format!("Invalid PASETO, {}", e),
+            PasetoError::InvalidBase64(e) => format!("Base64 error, {}", e),
+            PasetoError::InvalidJSON(e) => format!("JSON error, {}", e),
+            PasetoError::InvalidKey(e) => format!("Invalid key, {}", e),
+            PasetoError::InvalidSignature() => "Invalid PASETO signature".to_string(),
+            PasetoError::DecryptionFailed() => {
+                "Cannot decrypt the PASETO (wrong key, footer or implicit assertion)".to_string()
+            }
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for PasetoError {}
+
+impl From<base64::DecodeError> for PasetoError {
+    fn from(err: base64::DecodeError) -> PasetoError {
+        PasetoError::InvalidBase64(err)
+    }
+}
+
+impl From<serde_json::error::Error> for PasetoError {
+    fn from(err: serde_json::error::Error) -> PasetoError {
+        PasetoError::InvalidJSON(err)
+    }
+}
+
+/// The purpose of a PASETO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// An encrypted token (symmetric key)
+    Local,
+    /// A signed token (asymmetric key)
+    Public,
+}
+
+impl fmt::Display for Purpose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Purpose::Local => "local",
+            Purpose::Public => "public",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Represents a decoded PASETO
+#[derive(Debug)]
+pub struct Paseto {
+    /// the protocol version (1 to 4)
+    pub version: u8,
+    /// whether the token is encrypted (`local`) or signed (`public`)
+    pub purpose: Purpose,
+    /// the claims (a placeholder string for `local` tokens, until decrypted)
+    pub claims: Value,
+    /// the footer, as JSON if it contains JSON, as a string otherwise (`null` if missing)
+    pub footer: Value,
+    /// the message of `public` tokens or the nonce, ciphertext and tag of `local` tokens
+    body: Vec<u8>,
+    /// the signature of `public` tokens (empty for `local` tokens)
+    signature: Vec<u8>,
+    /// the raw footer
+    raw_footer: Vec<u8>,
+}
+
+impl Paseto {
+    /// Returns the `version.purpose.` prefix of the token (e.g. `v4.public.`)
+    fn prefix(&self) -> String {
+        format!("v{}.{}.", self.version, self.purpose)
+    }
+
+    /// Returns the metadata of the token: `version`, `purpose`, the `footer`
+    /// (if any) and, for `local` tokens, the base64url encoded `nonce`
+    pub fn header(&self) -> Value {
+        let mut header = serde_json::json!({
+            "version": format!("v{}", self.version),
+            "purpose": self.purpose.to_string(),
+        });
+        if self.purpose == Purpose::Local {
+            let nonce = &self.body[..nonce_size(self.version)];
+            header["nonce"] = Value::String(get_base64().encode(nonce));
+        }
+        if !self.footer.is_null() {
+            header["footer"] = self.footer.clone();
+        }
+        header
+    }
+
+    /// Returns the token as a JSON object with the `header` metadata and the `claims`
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "header": self.header(),
+            "claims": self.claims,
+        })
+    }
+
+    /// Verifies the signature of a `public` token
+    ///
+    /// The key must be an Ed25519 public key for v2 and v4, a P-384 public key
+    /// for v3 and an RSA public key (PEM only) for v1. The implicit assertion
+    /// is only used by v3 and v4.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `PasetoError` if the token is not a `public`
+    /// token, if the key is not valid or if the signature does not match
+    pub fn verify(&self, key: &str, implicit_assertion: &[u8]) -> Result<(), PasetoError> {
+        if self.purpose != Purpose::Public {
+            return Err(PasetoError::InvalidKey(
+                "a public key cannot be used with a local token".to_string(),
+            ));
+        }
+        let h = self.prefix();
+        let (h, m, f, i) = (
+            h.as_bytes(),
+            &self.body[..],
+            &self.raw_footer[..],
+            implicit_assertion,
+        );
+        let valid = match self.version {
+            1 => {
+                let key = rsa_public_key(key)?;
+                let hashed = Sha384::digest(pae(&[h, m, f]));
+                key.verify(Pss::new::<Sha384>(), &hashed, &self.signature)
+                    .is_ok()
+            }
+            3 => {
+                let key = p384_public_key(key)?;
+                let compressed = key.to_encoded_point(true);
+                let message = pae(&[compressed.as_bytes(), h, m, f, i]);
+                p384::ecdsa::Signature::from_slice(&self.signature)
+                    .is_ok_and(|signature| key.verify(&message, &signature).is_ok())
+            }
+            version => {
+                let key = ed25519_public_key(version, key)?;
+                let message = if version == 2 {
+                    pae(&[h, m, f])
+                } else {
+                    pae(&[h, m, f, i])
+                };
+                ed25519_dalek::Signature::from_slice(&self.signature)
+                    .is_ok_and(|signature| key.verify(&message, &signature).is_ok())
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(PasetoError::InvalidSignature())
+        }
+    }
+
+    /// Decrypts a `local` token and returns its claims
+    ///
+    /// The key is a 256-bit symmetric key. The implicit assertion is only used
+    /// by v3 and v4.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `PasetoError` if the token is not a `local`
+    /// token, if the key is not valid, if the authentication tag does not match
+    /// or if the decrypted message is not JSON
+    pub fn decrypt(&self, key: &str, implicit_assertion: &[u8]) -> Result<Value, PasetoError> {
+        if self.purpose != Purpose::Local {
+            return Err(PasetoError::InvalidKey(
+                "a symmetric key cannot be used with a public token".to_string(),
+            ));
+        }
+        let key = local_key(self.version, key)?;
+        let h = self.prefix();
+        let (h, f, i) = (h.as_bytes(), &self.raw_footer[..], implicit_assertion);
+        let (n, rest) = self.body.split_at(nonce_size(self.version));
+        let message = match self.version {
+            1 => {
+                let (c, t) = rest.split_at(rest.len() - 48);
+                let ek = hkdf_sha384::<32>(&key, Some(&n[..16]), b"paseto-encryption-key")?;
+                let ak = hkdf_sha384::<32>(&key, Some(&n[..16]), b"paseto-auth-key-for-aead")?;
+                verify_hmac_sha384(&ak, &pae(&[h, n, c, f]), t)?;
+                aes_256_ctr(&ek, &n[16..], c)
+            }
+            2 => XChaCha20Poly1305::new((&key).into())
+                .decrypt(
+                    n.into(),
+                    Payload {
+                        msg: rest,
+                        aad: &pae(&[h, n, f]),
+                    },
+                )
+                .map_err(|_| PasetoError::DecryptionFailed())?,
+            3 => {
+                let (c, t) = rest.split_at(rest.len() - 48);
+                let tmp =
+                    hkdf_sha384::<48>(&key, None, &[&b"paseto-encryption-key"[..], n].concat())?;
+                let ak =
+                    hkdf_sha384::<48>(&key, None, &[&b"paseto-auth-key-for-aead"[..], n].concat())?;
+                verify_hmac_sha384(&ak, &pae(&[h, n, c, f, i]), t)?;
+                aes_256_ctr(&tmp[..32], &tmp[32..], c)
+            }
+            _ => {
+                let (c, t) = rest.split_at(rest.len() - 32);
+                let tmp = blake2b_mac::<Blake2bMac<U56>>(&key, &[b"paseto-encryption-key", n]);
+                let ak = blake2b_mac::<Blake2bMac<U32>>(&key, &[b"paseto-auth-key-for-aead", n]);
+                let mut mac = <Blake2bMac<U32> as Mac>::new_from_slice(&ak)
+                    .map_err(|_| PasetoError::DecryptionFailed())?;
+                mac.update(&pae(&[h, n, c, f, i]));
+                mac.verify_slice(t)
+                    .map_err(|_| PasetoError::DecryptionFailed())?;
+                let mut message = c.to_vec();
+                XChaCha20::new(tmp[..32].into(), tmp[32..].into()).apply_keystream(&mut message);
+                message
+            }
+        };
+        Ok(serde_json::from_slice(&message)?)
+    }
+}
+
+impl str::FromStr for Paseto {
+    type Err = PasetoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+/// Returns `true` if a string starts with a PASETO prefix (`v1.` to `v4.`
+/// followed by `local.` or `public.`)
+pub fn is_paseto(token: &str) -> bool {
+    split_header(token).is_some()
+}
+
+#[doc(hidden)]
+fn split_header(token: &str) -> Option<(u8, Purpose, &str)> {
+    let mut parts = token.splitn(3, '.');
+    let version = match parts.next()? {
+        "v1" => 1,
+        "v2" => 2,
+        "v3" => 3,
+        "v4" => 4,
+        _ => return None,
+    };
+    let purpose = match parts.next()? {
+        "local" => Purpose::Local,
+        "public" => Purpose::Public,
+        _ => return None,
+    };
+    Some((version, purpose, parts.next()?))
+}
+
+/// The size of the nonce at the beginning of the payload of `local` tokens
+#[doc(hidden)]
+fn nonce_size(version: u8) -> usize {
+    if version == 2 {
+        24
+    } else {
+        32
+    }
+}
+
+/// The size of the authentication tag (or signature) at the end of the payload
+#[doc(hidden)]
+fn trailer_size(version: u8, purpose: Purpose) -> usize {
+    match (version, purpose) {
+        (1, Purpose::Public) => 256,
+        (3, Purpose::Public) => 96,
+        (_, Purpose::Public) => 64,
+        (1, Purpose::Local) | (3, Purpose::Local) => 48,
+        (_, Purpose::Local) => 32,
+    }
+}
+
+/// Parses a PASETO, decoding the claims of `public` tokens and the footer
+///
+/// # Errors
+///
+/// This function will return a `PasetoError` if the prefix is not supported,
+/// if the token is not correctly encoded or if the claims are not JSON
+pub fn parse(token: &str) -> Result<Paseto, PasetoError> {
+    let (version, purpose, rest) = split_header(token).ok_or_else(|| {
+        PasetoError::UnsupportedHeader(token.splitn(3, '.').take(2).collect::<Vec<_>>().join("."))
+    })?;
+    let mut parts = rest.split('.');
+    let body = get_base64().decode(parts.next().unwrap_or_default())?;
+    let raw_footer = match parts.next() {
+        Some(footer) => get_base64().decode(footer)?,
+        None => Vec::new(),
+    };
+    if parts.next().is_some() {
+        return Err(PasetoError::InvalidFormat(
+            "unexpected fragment after the footer",
+        ));
+    }
+
+    let trailer = trailer_size(version, purpose);
+    let minimum = match purpose {
+        Purpose::Public => trailer,
+        Purpose::Local => nonce_size(version) + trailer,
+    };
+    if body.len() < minimum {
+        return Err(PasetoError::InvalidFormat("the payload is too short"));
+    }
+
+    let (body, signature, claims) = match purpose {
+        Purpose::Public => {
+            let (message, signature) = body.split_at(body.len() - trailer);
+            let claims = serde_json::from_slice(message)?;
+            (message.to_vec(), signature.to_vec(), claims)
+        }
+        Purpose::Local => (
+            body,
+            Vec::new(),
+            Value::String(ENCRYPTED_PAYLOAD.to_string()),
+        ),
+    };
+
+    let footer = if raw_footer.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&raw_footer)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&raw_footer).to_string()))
+    };
+
+    Ok(Paseto {
+        version,
+        purpose,
+        claims,
+        footer,
+        body,
+        signature,
+        raw_footer,
+    })
+}
+
+/// Pre-Authentication Encoding (PAE) of a list of byte strings
+#[doc(hidden)]
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut out = (pieces.len() as u64).to_le_bytes().to_vec();
+    for piece in pieces {
+        out.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+#[doc(hidden)]
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Decodes a key given as PASERK (`k<version>.<type>.<base64url>`) or hex
+#[doc(hidden)]
+fn decode_key(version: u8, kind: &str, key: &str) -> Result<Vec<u8>, PasetoError> {
+    let key = key.trim();
+    if let Some((prefix, data)) = key.rsplit_once('.') {
+        // v2 and v4 (and v1 and v3) share the same local key format
+        let expected = format!("k{}.{}", version, kind);
+        if prefix != expected {
+            return Err(PasetoError::InvalidKey(format!(
+                "expected a `{}.` PASERK, found `{}.`",
+                expected, prefix
+            )));
+        }
+        return get_base64()
+            .decode(data)
+            .map_err(|e| PasetoError::InvalidKey(e.to_string()));
+    }
+    decode_hex(key).ok_or_else(|| {
+        PasetoError::InvalidKey("expected a hex encoded key, a PASERK or a PEM".to_string())
+    })
+}
+
+#[doc(hidden)]
+fn local_key(version: u8, key: &str) -> Result<[u8; 32], PasetoError> {
+    let bytes = decode_key(version, "local", key)?;
+    <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|_| PasetoError::InvalidKey("a local key must be 32 bytes long".to_string()))
+}
+
+#[doc(hidden)]
+fn ed25519_public_key(version: u8, key: &str) -> Result<ed25519_dalek::VerifyingKey, PasetoError> {
+    if key.contains("-----BEGIN") {
+        return ed25519_dalek::VerifyingKey::from_public_key_pem(key.trim())
+            .map_err(|e| PasetoError::InvalidKey(e.to_string()));
+    }
+    let bytes = decode_key(version, "public", key)?;
+    <[u8; 32]>::try_from(bytes.as_slice())
+        .ok()
+        .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| {
+            PasetoError::InvalidKey("expected a 32 bytes Ed25519 public key".to_string())
+        })
+}
+
+#[doc(hidden)]
+fn p384_public_key(key: &str) -> Result<p384::ecdsa::VerifyingKey, PasetoError> {
+    if key.contains("-----BEGIN") {
+        return p384::ecdsa::VerifyingKey::from_public_key_pem(key.trim())
+            .map_err(|e| PasetoError::InvalidKey(e.to_string()));
+    }
+    let bytes = decode_key(3, "public", key)?;
+    p384::ecdsa::VerifyingKey::from_sec1_bytes(&bytes).map_err(|_| {
+        PasetoError::InvalidKey("expected a SEC1 encoded P-384 public key".to_string())
+    })
+}
+
+#[doc(hidden)]
+fn rsa_public_key(key: &str) -> Result<RsaPublicKey, PasetoError> {
+    let key = key.trim();
+    RsaPublicKey::from_public_key_pem(key)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(key))
+        .map_err(|_| PasetoError::InvalidKey("expected a PEM encoded RSA public key".to_string()))
+}
+
+#[doc(hidden)]
+fn hkdf_sha384<const N: usize>(
+    key: &[u8],
+    salt: Option<&[u8]>,
+    info: &[u8],
+) -> Result<[u8; N], PasetoError> {
+    let mut out = [0u8; N];
+    Hkdf::<Sha384>::new(salt, key)
+        .expand(info, &mut out)
+        .map_err(|_| PasetoError::DecryptionFailed())?;
+    Ok(out)
+}
+
+#[doc(hidden)]
+fn verify_hmac_sha384(key: &[u8], message: &[u8], tag: &[u8]) -> Result<(), PasetoError> {
+    let mut mac =
+        <Hmac<Sha384> as Mac>::new_from_slice(key).map_err(|_| PasetoError::DecryptionFailed())?;
+    mac.update(message);
+    mac.verify_slice(tag)
+        .map_err(|_| PasetoError::DecryptionFailed())
+}
+
+#[doc(hidden)]
+fn blake2b_mac<M: Mac + KeyInit>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("BLAKE2b accepts 32 bytes keys");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[doc(hidden)]
+fn aes_256_ctr(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut message = ciphertext.to_vec();
+    ctr::Ctr128BE::<Aes256>::new(key.into(), nonce.into()).apply_keystream(&mut message);
+    message
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,222 @@
+#[cfg(test)]
+use super::*;
+
+// Test vectors from https://github.com/paseto-standard/test-vectors
+
+#[doc(hidden)]
+const LOCAL_KEY: &str = "707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f";
+
+#[doc(hidden)]
+const ED25519_PUBLIC_KEY: &str = "1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2";
+
+#[doc(hidden)]
+const ED25519_PUBLIC_KEY_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAHrnbu7wEfAP9cGBOAHHwmH4Wsot1ciXBHwBBXQ4gsaI=
+-----END PUBLIC KEY-----";
+
+#[doc(hidden)]
+const P384_PUBLIC_KEY: &str = "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb";
+
+#[doc(hidden)]
+const RSA_PUBLIC_KEY_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAyaTgTt53ph3p5GHgwoGW
+wz5hRfWXSQA08NCOwe0FEgALWos9GCjNFCd723nCHxBtN1qd74MSh/uN88JPIbwx
+KheDp4kxo4YMN5trPaF0e9G6Bj1N02HnanxFLW+gmLbgYO/SZYfWF/M8yLBcu5Y1
+Ot0ZxDDDXS9wIQTtBE0ne3YbxgZJAZTU5XqyQ1DxdzYyC5lF6yBaR5UQtCYTnXAA
+pVRuUI2Sd6L1E2vl9bSBumZ5IpNxkRnAwIMjeTJB/0AIELh0mE5vwdihOCbdV6al
+UyhKC1+1w/FW6HWcp/JG1kKC8DPIidZ78Bbqv9YFzkAbNni5eSBOsXVBKG78Zsc8
+owIDAQAB
+-----END PUBLIC KEY-----";
+
+#[doc(hidden)]
+const FOOTER: &str = "{\"kid\":\"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN\"}";
+
+#[test]
+fn assert_is_paseto() {
+    assert!(is_paseto("v4.public.abc"));
+    assert!(is_paseto("v1.local.abc"));
+    assert!(!is_paseto("v5.public.abc"));
+    assert!(!is_paseto("v4.secret.abc"));
+    assert!(!is_paseto("eyJhbGciOiJub25lIn0.e30."));
+}
+
+#[test]
+fn assert_parse_unsupported_header() {
+    let error = parse("v9.public.abc").unwrap_err();
+    assert_eq!(error.to_string(), "Unsupported PASETO header `v9.public`");
+}
+
+#[test]
+fn assert_parse_too_short() {
+    let error = parse("v4.public.e30").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid PASETO, the payload is too short"
+    );
+}
+
+#[test]
+fn assert_pae() {
+    assert_eq!(pae(&[]), b"\x00\x00\x00\x00\x00\x00\x00\x00");
+    assert_eq!(
+        pae(&[b""]),
+        b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00"
+    );
+    assert_eq!(
+        pae(&[b"test"]),
+        b"\x01\x00\x00\x00\x00\x00\x00\x00\x04\x00\x00\x00\x00\x00\x00\x00test"
+    );
+}
+
+#[test]
+fn assert_v4_public() {
+    // 4-S-3
+    let token = parse("v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9").unwrap();
+    assert_eq!(token.version, 4);
+    assert_eq!(token.purpose, Purpose::Public);
+    assert_eq!(
+        token.claims.to_string(),
+        "{\"data\":\"this is a signed message\",\"exp\":\"2022-01-01T00:00:00+00:00\"}"
+    );
+    assert_eq!(token.footer.to_string(), FOOTER);
+    assert_eq!(
+        token.header(),
+        serde_json::json!({
+            "version": "v4",
+            "purpose": "public",
+            "footer": {"kid": "zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}
+        })
+    );
+
+    let assertion = b"{\"test-vector\":\"4-S-3\"}";
+    assert!(token.verify(ED25519_PUBLIC_KEY, assertion).is_ok());
+    assert!(token.verify(ED25519_PUBLIC_KEY_PEM, assertion).is_ok());
+    assert!(token
+        .verify(
+            "k4.public.Hrnbu7wEfAP9cGBOAHHwmH4Wsot1ciXBHwBBXQ4gsaI",
+            assertion
+        )
+        .is_ok());
+    assert!(matches!(
+        token.verify(ED25519_PUBLIC_KEY, b""),
+        Err(PasetoError::InvalidSignature())
+    ));
+}
+
+#[test]
+fn assert_v4_public_wrong_paserk_version() {
+    let token = parse("v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA").unwrap();
+    let error = token
+        .verify("k2.public.Hrnbu7wEfAP9cGBOAHHwmH4Wsot1ciXBHwBBXQ4gsaI", b"")
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid key, expected a `k4.public.` PASERK, found `k2.public.`"
+    );
+}
+
+#[test]
+fn assert_v3_public() {
+    // 3-S-3
+    let token = parse("v3.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9FrkqK6FaB39LisqmPmIHLnu5P8zBTdO_EyWqeworXkGMBChHk-ZZWPt2r7qSYpOqWmvf0oBgf9Elx1TKS4a3YKIcaYddPlu6B9w5LT_b76sCqdVDjE5bH8ZgvZ708c48.eyJraWQiOiJkWWtJU3lseFFlZWNFY0hFTGZ6Rjg4VVpyd2JMb2xOaUNkcHpVSEd3OVVxbiJ9").unwrap();
+    assert_eq!(token.claims["data"], "this is a signed message");
+    assert!(token
+        .verify(P384_PUBLIC_KEY, b"{\"test-vector\":\"3-S-3\"}")
+        .is_ok());
+    assert!(token.verify(P384_PUBLIC_KEY, b"").is_err());
+}
+
+#[test]
+fn assert_v2_public() {
+    // 2-S-2
+    let token = parse("v2.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAxOS0wMS0wMVQwMDowMDowMCswMDowMCJ9flsZsx_gYCR0N_Ec2QxJFFpvQAs7h9HtKwbVK2n1MJ3Rz-hwe8KUqjnd8FAnIJZ601tp7lGkguU63oGbomhoBw.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9").unwrap();
+    assert_eq!(token.footer.to_string(), FOOTER);
+    // v2 ignores the implicit assertion
+    assert!(token.verify(ED25519_PUBLIC_KEY, b"").is_ok());
+    assert!(token
+        .verify(ED25519_PUBLIC_KEY, b"discarded-anyway")
+        .is_ok());
+}
+
+#[test]
+fn assert_v1_public() {
+    // there is no v1.public vector with a deterministic signature, this token
+    // is signed with the private key of the v1 test vectors
+    let token = parse("v1.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAxOS0wMS0wMVQwMDowMDowMCswMDowMCJ9tvEffq0E4zwPLyshA12nIrRt8etYU4HvSWxEbzfA_U2bjk6anmmFC6oTrn6408CuG9cuRWeX6IpCDvFaBufEN-twm7uprwPVyb3b4HmobDWmIKZpqjjipvNQW2qezxc6l-1Ykhm6afyx785z66sJ-muV3IEUdTj1t5oOTAmGJ1nxd5qjdnuOCh2RuzwiW5vXWh-XWivHERbBU6dH8h_mze0l-dObmYQTiVkJMXLWRVDdGfXI-bR0fwdPeUPAbdt2rit-iu_sJdixGO44HH2g0FQEElYvPqAPple0HJXlD029g6YMHtgAZtmaWEqZMZRhb-K9uig_AgOs1IeeQFiQiA.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9").unwrap();
+    assert_eq!(token.claims["exp"], "2019-01-01T00:00:00+00:00");
+    assert!(token.verify(RSA_PUBLIC_KEY_PEM, b"").is_ok());
+}
+
+#[test]
+fn assert_v4_local() {
+    // 4-E-9 (the footer is not JSON)
+    let token = parse("v4.local.32VIErrEkmY4JVILovbmfPXKW9wT1OdQepjMTC_MOtjA4kiqw7_tcaOM5GNEcnTxl60WiA8rd3wgFSNb_UdJPXjpzm0KW9ojM5f4O2mRvE2IcweP-PRdoHjd5-RHCiExR1IK6t6tybdlmnMwcDMw0YxA_gFSE_IUWl78aMtOepFYSWYfQA.YXJiaXRyYXJ5LXN0cmluZy10aGF0LWlzbid0LWpzb24").unwrap();
+    assert_eq!(token.claims, "<encrypted PASETO payload>");
+    assert_eq!(
+        token.header(),
+        serde_json::json!({
+            "version": "v4",
+            "purpose": "local",
+            "nonce": "32VIErrEkmY4JVILovbmfPXKW9wT1OdQepjMTC_MOtg",
+            "footer": "arbitrary-string-that-isn't-json"
+        })
+    );
+
+    let claims = token
+        .decrypt(LOCAL_KEY, b"{\"test-vector\":\"4-E-9\"}")
+        .unwrap();
+    assert_eq!(
+        claims.to_string(),
+        "{\"data\":\"this is a hidden message\",\"exp\":\"2022-01-01T00:00:00+00:00\"}"
+    );
+    assert!(matches!(
+        token.decrypt(LOCAL_KEY, b""),
+        Err(PasetoError::DecryptionFailed())
+    ));
+    assert!(token
+        .decrypt(
+            "k4.local.cHFyc3R1dnd4eXp7fH1-f4CBgoOEhYaHiImKi4yNjo8",
+            b"{\"test-vector\":\"4-E-9\"}"
+        )
+        .is_ok());
+}
+
+#[test]
+fn assert_v3_local() {
+    // 3-E-7
+    let token = parse("v3.local.JvdVM1RIKh2R1HhGJ4VLjaa4BCp5ZlI8K0BOjbvn9_LwY78vQnDait-Q-sjhF88dG2B0ROIIykcrGHn8wzPbTrqObHhyoKpjy3cwZQzLdiwRsdEK5SDvl02_HjWKJW2oqGMOQJkzWACWAIoVa0bz7EWSBoTEnS8MvGBYHHo6t6mJunPrFR9JKXFCc0obwz5N-pxFLOc.eyJraWQiOiJVYmtLOFk2aXY0R1poRnA2VHgzSVdMV0xmTlhTRXZKY2RUM3pkUjY1WVp4byJ9").unwrap();
+    let claims = token
+        .decrypt(LOCAL_KEY, b"{\"test-vector\":\"3-E-7\"}")
+        .unwrap();
+    assert_eq!(claims["data"], "this is a secret message");
+}
+
+#[test]
+fn assert_v2_local() {
+    // 2-E-5
+    let token = parse("v2.local.5K4SCXNhItIhyNuVIZcwrdtaDKiyF81-eWHScuE0idiVqCo72bbjo07W05mqQkhLZdVbxEa5I_u5sgVk1QLkcWEcOSlLHwNpCkvmGGlbCdNExn6Qclw3qTKIIl5-zSLIrxZqOLwcFLYbVK1SrQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9").unwrap();
+    assert_eq!(token.header()["nonce"], "5K4SCXNhItIhyNuVIZcwrdtaDKiyF81-");
+    let claims = token.decrypt(LOCAL_KEY, b"").unwrap();
+    assert_eq!(claims["data"], "this is a signed message");
+}
+
+#[test]
+fn assert_v1_local() {
+    // 1-E-1
+    let token = parse("v1.local.WzhIh1MpbqVNXNt7-HbWvL-JwAym3Tomad9Pc2nl7wK87vGraUVvn2bs8BBNo7jbukCNrkVID0jCK2vr5bP18G78j1bOTbBcP9HZzqnraEdspcjd_PvrxDEhj9cS2MG5fmxtvuoHRp3M24HvxTtql9z26KTfPWxJN5bAJaAM6gos8fnfjJO8oKiqQMaiBP_Cqncmqw8").unwrap();
+    let claims = token.decrypt(LOCAL_KEY, b"").unwrap();
+    assert_eq!(
+        claims.to_string(),
+        "{\"data\":\"this is a signed message\",\"exp\":\"2019-01-01T00:00:00+00:00\"}"
+    );
+}
+
+#[test]
+fn assert_key_purpose_mismatch() {
+    let token = parse("v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA").unwrap();
+    let error = token.decrypt(LOCAL_KEY, b"").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid key, a symmetric key cannot be used with a public token"
+    );
+}
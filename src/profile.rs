@@ -0,0 +1,455 @@
+//! # Profile
+//!
+//! `profile` validates the claims of a token against a well known token profile,
+//! such as the [OpenID Connect ID token](https://openid.net/specs/openid-connect-core-1_0.html#IDToken)
+//! or the [JWT access token](https://datatracker.ietf.org/doc/html/rfc9068).
+//!
+//! Every profile also checks the `typ` header against the type of token it
+//! expects, so that a token of one kind cannot be mistaken for another.
+//!
+//! The expected values that only the relying party knows (e.g. its client id or
+//! the `nonce` it sent) are given as `Expectations`. Every rule produces a
+//! `validation::Check`; the rules whose expected value is not given are skipped.
+//! Signatures are not verified.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::{jwt, profile};
+//!
+//! let token = jwt::parse("eyJhbGciOiJSUzI1NiJ9.eyJpc3MiOiJodHRwczovL3NlcnZlci5leGFtcGxlLmNvbSIsInN1YiI6IjI0ODI4OTc2MTAwMSIsImF1ZCI6InM2QmhkUmtxdDMiLCJub25jZSI6Im4tMFM2X1d6QTJNaiIsImV4cCI6MTMxMTI4MTk3MCwiaWF0IjoxMzExMjgwOTcwLCJhdF9oYXNoIjoiNzdRbVVQdGpQZnpXdEYyQW5wSzlSUSJ9.c2ln").unwrap();
+//! let expectations = profile::Expectations {
+//!     audience: Some("s6BhdRkqt3".to_string()),
+//!     nonce: Some("n-0S6_WzA2Mj".to_string()),
+//!     access_token: Some("jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y".to_string()),
+//!     ..Default::default()
+//! };
+//! let checks = profile::Profile::OidcIdToken.check(&token.header, &token.body, &expectations, 1311281000);
+//! assert!(checks.iter().all(|c| c.passed));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str;
+
+use base64::Engine as _;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::jwt::get_base64;
+use crate::timestamp;
+use crate::validation::Check;
+
+/// Represents an error while selecting a profile
+#[derive(Debug)]
+pub enum ProfileError {
+    /// Indicates that the profile name is not known
+    UnknownProfile(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            ProfileError::UnknownProfile(name) => format!("Unknown profile `{}`", name),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for ProfileError {}
+
+/// The token profiles that can be validated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// An OpenID Connect ID token (`oidc-id-token`)
+    OidcIdToken,
+    /// An OAuth 2.0 JWT access token, as defined by RFC 9068 (`jwt-access-token`)
+    JwtAccessToken,
+}
+
+impl Profile {
+    /// The names of all the profiles, as accepted by `from_str`
+    pub const NAMES: [&'static str; 2] = ["oidc-id-token", "jwt-access-token"];
+
+    /// Checks the header and claims of a token against the profile
+    pub fn check(
+        &self,
+        header: &Value,
+        claims: &Value,
+        expectations: &Expectations,
+        now: u64,
+    ) -> Vec<Check> {
+        match self {
+            Profile::OidcIdToken => check_id_token(header, claims, expectations, now),
+            Profile::JwtAccessToken => check_access_token(header, claims, expectations, now),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Profile::OidcIdToken => "oidc-id-token",
+            Profile::JwtAccessToken => "jwt-access-token",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl str::FromStr for Profile {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "oidc-id-token" => Ok(Profile::OidcIdToken),
+            "jwt-access-token" => Ok(Profile::JwtAccessToken),
+            other => Err(ProfileError::UnknownProfile(other.to_string())),
+        }
+    }
+}
+
+/// The values a token is expected to contain (or to be consistent with)
+#[derive(Debug, Clone, Default)]
+pub struct Expectations {
+    /// the expected issuer (`iss`)
+    pub issuer: Option<String>,
+    /// the expected audience (the client id, for ID tokens)
+    pub audience: Option<String>,
+    /// the `nonce` sent in the authentication request
+    pub nonce: Option<String>,
+    /// the `max_age` sent in the authentication request, in seconds
+    pub max_age: Option<u64>,
+    /// the access token issued with the ID token (checked against `at_hash`)
+    pub access_token: Option<String>,
+    /// the authorization code issued with the ID token (checked against `c_hash`)
+    pub code: Option<String>,
+}
+
+/// Computes the `at_hash` or `c_hash` of a value: the base64url encoded left
+/// half of its hash, using the hash function of the JWS algorithm `alg`
+///
+/// Returns `None` if the hash function cannot be derived from the algorithm.
+pub fn half_hash(alg: &str, value: &str) -> Option<String> {
+    let digest = match alg {
+        "EdDSA" | "Ed25519" => Sha512::digest(value).to_vec(),
+        _ if alg.ends_with("256") => Sha256::digest(value).to_vec(),
+        _ if alg.ends_with("384") => Sha384::digest(value).to_vec(),
+        _ if alg.ends_with("512") => Sha512::digest(value).to_vec(),
+        _ => return None,
+    };
+    Some(get_base64().encode(&digest[..digest.len() / 2]))
+}
+
+/// Checks the `typ` header against the expected type of token
+///
+/// Media types are compared case-insensitively and without the optional
+/// `application/` prefix (RFC 7515, section 4.1.9), so `application/at+jwt`
+/// matches `at+jwt`. If `required` is `false`, a missing `typ` is accepted.
+pub fn check_typ(header: &Value, expected: &str, required: bool) -> Check {
+    let normalize = |typ: &str| {
+        let typ = typ.to_ascii_lowercase();
+        match typ.strip_prefix("application/") {
+            Some(stripped) => stripped.to_string(),
+            None => typ,
+        }
+    };
+    match header.get("typ") {
+        None if !required => Check::pass("typ", "typ is not set"),
+        None => Check::fail(
+            "typ",
+            format!("typ header is missing, expected {}", expected),
+        ),
+        Some(Value::String(typ)) if normalize(typ) == normalize(expected) => {
+            Check::pass("typ", format!("typ is {}", typ))
+        }
+        Some(Value::String(typ)) => {
+            Check::fail("typ", format!("typ is {}, expected {}", typ, expected))
+        }
+        Some(_) => Check::fail("typ", "typ is not a string"),
+    }
+}
+
+/// Returns `true` if `scope` is a list of scope tokens separated by single
+/// spaces (RFC 6749, section 3.3)
+#[doc(hidden)]
+fn is_valid_scope(scope: &str) -> bool {
+    !scope.is_empty()
+        && scope.split(' ').all(|token| {
+            !token.is_empty()
+                && token.chars().all(|c| {
+                    c == '\x21' || ('\x23'..='\x5B').contains(&c) || ('\x5D'..='\x7E').contains(&c)
+                })
+        })
+}
+
+/// Checks a `groups`, `roles` or `entitlements` claim (RFC 9068, section 2.2.3.1):
+/// an array of strings or of SCIM multi-valued attributes (objects with a `value`)
+#[doc(hidden)]
+fn check_authorization_claim(claims: &Value, name: &str) -> Option<Check> {
+    let values = claims.get(name)?;
+    let items = match values.as_array() {
+        Some(items) => items,
+        None => return Some(Check::fail(name, format!("{} is not an array", name))),
+    };
+    let valid = items
+        .iter()
+        .all(|item| item.is_string() || item.get("value").is_some_and(|value| value.is_string()));
+    Some(if valid {
+        Check::pass(name, format!("{} has {} value(s)", name, items.len()))
+    } else {
+        Check::fail(
+            name,
+            format!("{} must contain strings or objects with a value", name),
+        )
+    })
+}
+
+/// Returns the audiences of a token (`aud` can be a string or an array of strings)
+#[doc(hidden)]
+fn audiences(claims: &Value) -> Vec<&str> {
+    match claims.get("aud") {
+        Some(Value::String(aud)) => vec![aud.as_str()],
+        Some(Value::Array(items)) => items.iter().filter_map(|a| a.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Checks that all the given claims are present
+#[doc(hidden)]
+fn check_required(claims: &Value, names: &[&str]) -> Check {
+    let missing: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| claims.get(name).is_none())
+        .collect();
+    if missing.is_empty() {
+        Check::pass(
+            "required_claims",
+            format!("{} are present", names.join(", ")),
+        )
+    } else {
+        Check::fail("required_claims", format!("missing {}", missing.join(", ")))
+    }
+}
+
+/// Checks that a string claim is equal to an expected value
+#[doc(hidden)]
+fn check_equal(claims: &Value, name: &str, expected: &str) -> Check {
+    match claims.get(name).and_then(|v| v.as_str()) {
+        Some(actual) if actual == expected => Check::pass(name, format!("{} is {}", name, actual)),
+        Some(actual) => Check::fail(
+            name,
+            format!("{} is {}, expected {}", name, actual, expected),
+        ),
+        None => Check::fail(name, format!("{} claim is missing", name)),
+    }
+}
+
+/// Checks that the audiences contain the expected one
+#[doc(hidden)]
+fn check_audience(claims: &Value, expected: &str) -> Check {
+    let audiences = audiences(claims);
+    if audiences.contains(&expected) {
+        Check::pass("aud", format!("aud contains {}", expected))
+    } else {
+        Check::fail(
+            "aud",
+            format!(
+                "aud is {:?}, expected it to contain {}",
+                audiences, expected
+            ),
+        )
+    }
+}
+
+/// Returns a NumericDate as whole seconds (NumericDates can have a fraction)
+#[doc(hidden)]
+fn numeric_date(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_f64().map(|f| f as i64))
+}
+
+/// Checks that `exp` is in the future
+#[doc(hidden)]
+fn check_exp(claims: &Value, now: u64) -> Option<Check> {
+    let exp = numeric_date(claims.get("exp")?);
+    Some(match exp {
+        Some(exp) if exp > now as i64 => Check::pass(
+            "exp",
+            format!("expires {}", timestamp::format_relative(exp, now as i64)),
+        ),
+        Some(exp) => Check::fail(
+            "exp",
+            format!("expired {}", timestamp::format_relative(exp, now as i64)),
+        ),
+        None => Check::fail("exp", "exp is not a number"),
+    })
+}
+
+/// Checks that `iat` is not in the future
+#[doc(hidden)]
+fn check_iat(claims: &Value, now: u64) -> Option<Check> {
+    let iat = numeric_date(claims.get("iat")?);
+    Some(match iat {
+        Some(iat) if iat <= now as i64 => Check::pass("iat", "iat is not in the future"),
+        Some(_) => Check::fail("iat", "iat is in the future"),
+        None => Check::fail("iat", "iat is not a number"),
+    })
+}
+
+/// Checks an `at_hash` or `c_hash` claim
+#[doc(hidden)]
+fn check_half_hash(header: &Value, claims: &Value, name: &str, value: &str) -> Check {
+    let alg = header
+        .get("alg")
+        .and_then(|a| a.as_str())
+        .unwrap_or_default();
+    let expected = match half_hash(alg, value) {
+        Some(expected) => expected,
+        None => {
+            return Check::fail(
+                name,
+                format!("cannot derive the hash function from alg {:?}", alg),
+            )
+        }
+    };
+    match claims.get(name).and_then(|h| h.as_str()) {
+        Some(actual) if actual == expected => Check::pass(name, format!("{} matches", name)),
+        Some(actual) => Check::fail(
+            name,
+            format!("{} is {}, computed {}", name, actual, expected),
+        ),
+        None => Check::fail(name, format!("{} claim is missing", name)),
+    }
+}
+
+/// Checks an OpenID Connect ID token (OpenID Connect Core 1.0, section 3.1.3.7)
+#[doc(hidden)]
+fn check_id_token(
+    header: &Value,
+    claims: &Value,
+    expectations: &Expectations,
+    now: u64,
+) -> Vec<Check> {
+    let mut checks = vec![check_typ(header, "JWT", false)];
+
+    match header.get("alg").and_then(|a| a.as_str()) {
+        Some("none") | None => checks.push(Check::fail("alg", "ID tokens must be signed")),
+        Some(alg) => checks.push(Check::pass("alg", format!("alg is {}", alg))),
+    }
+
+    checks.push(check_required(claims, &["iss", "sub", "aud", "exp", "iat"]));
+
+    if let Some(issuer) = &expectations.issuer {
+        checks.push(check_equal(claims, "iss", issuer));
+    }
+    if let Some(audience) = &expectations.audience {
+        checks.push(check_audience(claims, audience));
+    }
+
+    // azp is required with multiple audiences and, if present, must be the client id
+    let azp = claims.get("azp").and_then(|a| a.as_str());
+    match (azp, &expectations.audience) {
+        (None, _) if audiences(claims).len() > 1 => checks.push(Check::fail(
+            "azp",
+            "azp claim is required when there are multiple audiences",
+        )),
+        (Some(_), Some(audience)) => checks.push(check_equal(claims, "azp", audience)),
+        (Some(azp), None) => checks.push(Check::pass("azp", format!("azp is {}", azp))),
+        (None, _) => {}
+    }
+
+    checks.extend(check_exp(claims, now));
+    checks.extend(check_iat(claims, now));
+
+    if let Some(nonce) = &expectations.nonce {
+        checks.push(check_equal(claims, "nonce", nonce));
+    }
+
+    if let Some(max_age) = expectations.max_age {
+        let check = match claims.get("auth_time").and_then(numeric_date) {
+            Some(auth_time) => {
+                let when = timestamp::format_relative(auth_time, now as i64);
+                if i128::from(now) - i128::from(auth_time) <= i128::from(max_age) {
+                    Check::pass("auth_time", format!("authenticated {}", when))
+                } else {
+                    Check::fail(
+                        "auth_time",
+                        format!(
+                            "authenticated {}, more than max_age ({} seconds)",
+                            when, max_age
+                        ),
+                    )
+                }
+            }
+            None => Check::fail(
+                "auth_time",
+                "auth_time claim is required when max_age is requested",
+            ),
+        };
+        checks.push(check);
+    }
+
+    if let Some(access_token) = &expectations.access_token {
+        checks.push(check_half_hash(header, claims, "at_hash", access_token));
+    }
+    if let Some(code) = &expectations.code {
+        checks.push(check_half_hash(header, claims, "c_hash", code));
+    }
+
+    checks
+}
+
+/// Checks an OAuth 2.0 JWT access token (RFC 9068, section 4)
+#[doc(hidden)]
+fn check_access_token(
+    header: &Value,
+    claims: &Value,
+    expectations: &Expectations,
+    now: u64,
+) -> Vec<Check> {
+    let mut checks = vec![check_typ(header, "at+jwt", true)];
+
+    match header.get("alg").and_then(|a| a.as_str()) {
+        Some("none") | None => checks.push(Check::fail("alg", "access tokens must be signed")),
+        Some(alg) => checks.push(Check::pass("alg", format!("alg is {}", alg))),
+    }
+
+    checks.push(check_required(
+        claims,
+        &["iss", "exp", "aud", "sub", "client_id", "iat", "jti"],
+    ));
+
+    if let Some(issuer) = &expectations.issuer {
+        checks.push(check_equal(claims, "iss", issuer));
+    }
+    if let Some(audience) = &expectations.audience {
+        checks.push(check_audience(claims, audience));
+    }
+
+    checks.extend(check_exp(claims, now));
+    checks.extend(check_iat(claims, now));
+
+    match claims.get("scope") {
+        None => {}
+        Some(Value::String(scope)) if is_valid_scope(scope) => {
+            checks.push(Check::pass("scope", format!("scope is {}", scope)))
+        }
+        Some(Value::String(scope)) => checks.push(Check::fail(
+            "scope",
+            format!(
+                "scope {:?} is not a space-delimited list of scope tokens",
+                scope
+            ),
+        )),
+        Some(_) => checks.push(Check::fail("scope", "scope is not a string")),
+    }
+
+    for name in ["groups", "roles", "entitlements"] {
+        checks.extend(check_authorization_claim(claims, name));
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,332 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+const NOW: u64 = 1311281000;
+
+// the ID token of OpenID Connect Core 1.0, appendix A.4 (code id_token flow)
+#[doc(hidden)]
+fn id_token_claims() -> Value {
+    serde_json::json!({
+        "iss": "https://server.example.com",
+        "sub": "248289761001",
+        "aud": "s6BhdRkqt3",
+        "nonce": "n-0S6_WzA2Mj",
+        "exp": 1311281970,
+        "iat": 1311280970,
+        "auth_time": 1311280969,
+        "c_hash": "LDktKdoQak3Pk0cnXxCltA"
+    })
+}
+
+#[doc(hidden)]
+fn failed(checks: &[Check]) -> Vec<String> {
+    checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.to_string())
+        .collect()
+}
+
+#[test]
+fn assert_profile_from_str() {
+    assert_eq!(
+        "oidc-id-token".parse::<Profile>().unwrap(),
+        Profile::OidcIdToken
+    );
+    assert_eq!(Profile::OidcIdToken.to_string(), "oidc-id-token");
+    assert_eq!(
+        "jwt-access-token".parse::<Profile>().unwrap(),
+        Profile::JwtAccessToken
+    );
+    for name in Profile::NAMES.iter() {
+        assert_eq!(name.parse::<Profile>().unwrap().to_string(), *name);
+    }
+    let error = "saml".parse::<Profile>().unwrap_err();
+    assert_eq!(error.to_string(), "Unknown profile `saml`");
+}
+
+#[test]
+fn assert_half_hash() {
+    // OpenID Connect Core 1.0, appendix A.3 and A.4
+    assert_eq!(
+        half_hash("RS256", "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y").unwrap(),
+        "77QmUPtjPfzWtF2AnpK9RQ"
+    );
+    assert_eq!(
+        half_hash(
+            "RS256",
+            "Qcb0Orv1zh30vL1MPRsbm-diHiMwcLyZvn1arpZv-Jxf_11jnpEX3Tgfvk"
+        )
+        .unwrap(),
+        "LDktKdoQak3Pk0cnXxCltA"
+    );
+    assert_eq!(half_hash("ES384", "abc").unwrap().len(), 32);
+    assert_eq!(half_hash("EdDSA", "abc").unwrap().len(), 43);
+    assert_eq!(half_hash("none", "abc"), None);
+}
+
+#[test]
+fn assert_id_token_valid() {
+    let expectations = Expectations {
+        issuer: Some("https://server.example.com".to_string()),
+        audience: Some("s6BhdRkqt3".to_string()),
+        nonce: Some("n-0S6_WzA2Mj".to_string()),
+        max_age: Some(3600),
+        code: Some("Qcb0Orv1zh30vL1MPRsbm-diHiMwcLyZvn1arpZv-Jxf_11jnpEX3Tgfvk".to_string()),
+        ..Default::default()
+    };
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &id_token_claims(),
+        &expectations,
+        NOW,
+    );
+    assert_eq!(failed(&checks), Vec::<String>::new());
+    assert_eq!(
+        checks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        [
+            "typ",
+            "alg",
+            "required_claims",
+            "iss",
+            "aud",
+            "exp",
+            "iat",
+            "nonce",
+            "auth_time",
+            "c_hash"
+        ]
+    );
+}
+
+#[test]
+fn assert_id_token_without_expectations() {
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &id_token_claims(),
+        &Expectations::default(),
+        NOW,
+    );
+    assert_eq!(
+        checks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        ["typ", "alg", "required_claims", "exp", "iat"]
+    );
+}
+
+#[test]
+fn assert_id_token_invalid() {
+    let expectations = Expectations {
+        audience: Some("client".to_string()),
+        nonce: Some("other".to_string()),
+        max_age: Some(0),
+        access_token: Some("jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y".to_string()),
+        code: Some("abc".to_string()),
+        ..Default::default()
+    };
+    let mut claims = id_token_claims();
+    claims["aud"] = serde_json::json!(["s6BhdRkqt3", "other"]);
+    claims.as_object_mut().unwrap().remove("sub");
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "none"}),
+        &claims,
+        &expectations,
+        NOW + 3600,
+    );
+    assert_eq!(
+        failed(&checks),
+        [
+            "[FAIL] alg: ID tokens must be signed",
+            "[FAIL] required_claims: missing sub",
+            "[FAIL] aud: aud is [\"s6BhdRkqt3\", \"other\"], expected it to contain client",
+            "[FAIL] azp: azp claim is required when there are multiple audiences",
+            "[FAIL] exp: expired 43 minutes ago",
+            "[FAIL] nonce: nonce is n-0S6_WzA2Mj, expected other",
+            "[FAIL] auth_time: authenticated 1 hour ago, more than max_age (0 seconds)",
+            "[FAIL] at_hash: cannot derive the hash function from alg \"none\"",
+            "[FAIL] c_hash: cannot derive the hash function from alg \"none\"",
+        ]
+    );
+}
+
+#[test]
+fn assert_id_token_extreme_auth_time() {
+    let expectations = Expectations {
+        max_age: Some(3600),
+        ..Default::default()
+    };
+    let mut claims = id_token_claims();
+    claims["auth_time"] = Value::from(i64::MIN);
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &claims,
+        &expectations,
+        NOW,
+    );
+    assert_eq!(
+        failed(&checks),
+        ["[FAIL] auth_time: authenticated 292471208719 years ago, more than max_age (3600 seconds)"]
+    );
+}
+
+#[test]
+fn assert_id_token_azp() {
+    let mut claims = id_token_claims();
+    claims["aud"] = serde_json::json!(["s6BhdRkqt3", "api"]);
+    claims["azp"] = Value::from("api");
+    let expectations = Expectations {
+        audience: Some("s6BhdRkqt3".to_string()),
+        ..Default::default()
+    };
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &claims,
+        &expectations,
+        NOW,
+    );
+    assert_eq!(
+        failed(&checks),
+        ["[FAIL] azp: azp is api, expected s6BhdRkqt3"]
+    );
+}
+
+#[test]
+fn assert_id_token_at_hash_mismatch() {
+    let mut claims = id_token_claims();
+    claims["at_hash"] = Value::from("77QmUPtjPfzWtF2AnpK9RQ");
+    let expectations = Expectations {
+        access_token: Some("another-token".to_string()),
+        ..Default::default()
+    };
+    let checks = Profile::OidcIdToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &claims,
+        &expectations,
+        NOW,
+    );
+    let failures = failed(&checks);
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].starts_with("[FAIL] at_hash: at_hash is 77QmUPtjPfzWtF2AnpK9RQ, computed "));
+}
+
+#[doc(hidden)]
+fn access_token_claims() -> Value {
+    // RFC 9068, section 2.2 (with aud as an array and the authorization claims)
+    serde_json::json!({
+        "iss": "https://authorization-server.example.com/",
+        "sub": "5ba552d67",
+        "aud": ["https://rs.example.com/"],
+        "exp": 1639528912,
+        "iat": 1618354090,
+        "jti": "dbe39bf3a3ba4238a513f51d6e1691c4",
+        "client_id": "s6BhdRkqt3",
+        "scope": "openid profile reademail",
+        "groups": ["admins"],
+        "roles": [{"value": "reader", "primary": true}]
+    })
+}
+
+#[test]
+fn assert_check_typ() {
+    let header = serde_json::json!({"typ": "application/AT+JWT"});
+    assert!(check_typ(&header, "at+jwt", true).passed);
+    assert_eq!(
+        check_typ(&serde_json::json!({"typ": "JWT"}), "at+jwt", true).to_string(),
+        "[FAIL] typ: typ is JWT, expected at+jwt"
+    );
+    assert!(check_typ(&serde_json::json!({}), "JWT", false).passed);
+    assert!(!check_typ(&serde_json::json!({}), "at+jwt", true).passed);
+}
+
+#[test]
+fn assert_fractional_numeric_dates() {
+    let claims = serde_json::json!({"exp": NOW as f64 + 0.5, "iat": NOW as f64 - 0.5});
+    assert_eq!(
+        check_exp(&claims, NOW - 60).unwrap().to_string(),
+        "[PASS] exp: expires in 1 minute"
+    );
+    assert!(check_iat(&claims, NOW).unwrap().passed);
+    assert!(
+        !check_exp(&serde_json::json!({"exp": "soon"}), NOW)
+            .unwrap()
+            .passed
+    );
+}
+
+#[test]
+fn assert_access_token_valid() {
+    let expectations = Expectations {
+        issuer: Some("https://authorization-server.example.com/".to_string()),
+        audience: Some("https://rs.example.com/".to_string()),
+        ..Default::default()
+    };
+    let checks = Profile::JwtAccessToken.check(
+        &serde_json::json!({"typ": "at+jwt", "alg": "RS256"}),
+        &access_token_claims(),
+        &expectations,
+        1618354100,
+    );
+    assert_eq!(failed(&checks), Vec::<String>::new());
+    assert_eq!(
+        checks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        [
+            "typ",
+            "alg",
+            "required_claims",
+            "iss",
+            "aud",
+            "exp",
+            "iat",
+            "scope",
+            "groups",
+            "roles"
+        ]
+    );
+}
+
+#[test]
+fn assert_access_token_invalid() {
+    let mut claims = access_token_claims();
+    claims["scope"] = Value::from("openid  profile");
+    claims["entitlements"] = Value::from("admin");
+    claims["roles"] = serde_json::json!([{"primary": true}]);
+    claims.as_object_mut().unwrap().remove("client_id");
+    let checks = Profile::JwtAccessToken.check(
+        &serde_json::json!({"typ": "JWT", "alg": "none"}),
+        &claims,
+        &Expectations::default(),
+        1618354100,
+    );
+    assert_eq!(
+        failed(&checks),
+        [
+            "[FAIL] typ: typ is JWT, expected at+jwt",
+            "[FAIL] alg: access tokens must be signed",
+            "[FAIL] required_claims: missing client_id",
+            "[FAIL] scope: scope \"openid  profile\" is not a space-delimited list of scope tokens",
+            "[FAIL] roles: roles must contain strings or objects with a value",
+            "[FAIL] entitlements: entitlements is not an array",
+        ]
+    );
+}
+
+#[test]
+fn assert_id_token_is_not_an_access_token() {
+    let header = serde_json::json!({"typ": "at+jwt", "alg": "RS256"});
+    let checks =
+        Profile::OidcIdToken.check(&header, &id_token_claims(), &Expectations::default(), NOW);
+    assert_eq!(failed(&checks), ["[FAIL] typ: typ is at+jwt, expected JWT"]);
+    let checks = Profile::JwtAccessToken.check(
+        &serde_json::json!({"alg": "RS256"}),
+        &id_token_claims(),
+        &Expectations::default(),
+        NOW,
+    );
+    assert_eq!(
+        failed(&checks)[..2],
+        [
+            "[FAIL] typ: typ header is missing, expected at+jwt",
+            "[FAIL] required_claims: missing client_id, jti",
+        ]
+    );
+}
@@ -0,0 +1,449 @@
+//! # Provider
+//!
+//! `provider` guesses which identity provider or platform minted a token, by
+//! looking at the shape of its `iss`, its header parameters (e.g. the format of
+//! the `kid`) and the claims that only some providers add.
+//!
+//! Every matching signal adds to the score of a provider; the provider with the
+//! highest score is reported, together with a confidence level and the signals
+//! that led to it.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::provider::{self, Confidence, Provider};
+//!
+//! let header = serde_json::json!({"alg": "RS256", "kid": "1"});
+//! let claims = serde_json::json!({
+//!     "iss": "https://token.actions.githubusercontent.com",
+//!     "sub": "repo:octo-org/octo-repo:ref:refs/heads/main",
+//!     "job_workflow_ref": "octo-org/octo-repo/.github/workflows/ci.yml@refs/heads/main",
+//! });
+//! let detection = provider::detect(&header, &claims).unwrap();
+//! assert_eq!(detection.provider, Provider::GithubActions);
+//! assert_eq!(detection.confidence, Confidence::High);
+//! ```
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::explain::Source;
+use crate::profile::Profile;
+
+/// The identity providers and platforms that can be detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// Azure AD / Entra ID, v1.0 tokens (`sts.windows.net` issuer)
+    EntraV1,
+    /// Azure AD / Entra ID, v2.0 tokens (`login.microsoftonline.com/{tenant}/v2.0` issuer)
+    EntraV2,
+    /// AWS Cognito user pool, ID token
+    CognitoIdToken,
+    /// AWS Cognito user pool, access token
+    CognitoAccessToken,
+    /// Google identity (Google accounts and service accounts)
+    Google,
+    /// Firebase Authentication
+    Firebase,
+    /// Auth0
+    Auth0,
+    /// Keycloak
+    Keycloak,
+    /// GitHub Actions OIDC token
+    GithubActions,
+    /// GitLab CI/CD ID token (`id_tokens`, formerly `CI_JOB_JWT`)
+    GitlabCi,
+    /// Kubernetes projected service account token
+    KubernetesProjected,
+    /// Kubernetes legacy (secret based) service account token
+    KubernetesLegacy,
+}
+
+impl Provider {
+    /// Returns the `explain` dictionary that describes the claims of the provider, if any
+    pub fn source(&self) -> Option<Source> {
+        match self {
+            Provider::EntraV1 | Provider::EntraV2 => Some(Source::Entra),
+            Provider::CognitoIdToken | Provider::CognitoAccessToken => Some(Source::Cognito),
+            Provider::Google | Provider::Firebase => Some(Source::Google),
+            Provider::Auth0 => Some(Source::Auth0),
+            Provider::Keycloak => Some(Source::Keycloak),
+            Provider::GithubActions => Some(Source::GithubActions),
+            Provider::KubernetesProjected | Provider::KubernetesLegacy => Some(Source::Kubernetes),
+            Provider::GitlabCi => None,
+        }
+    }
+
+    /// Returns the validation profile of the tokens of the provider, if it is
+    /// known from the provider alone
+    pub fn default_profile(&self) -> Option<Profile> {
+        match self {
+            Provider::CognitoIdToken
+            | Provider::Google
+            | Provider::Firebase
+            | Provider::GithubActions
+            | Provider::GitlabCi => Some(Profile::OidcIdToken),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Provider::EntraV1 => "Azure AD / Entra ID (v1.0)",
+            Provider::EntraV2 => "Azure AD / Entra ID (v2.0)",
+            Provider::CognitoIdToken => "AWS Cognito (ID token)",
+            Provider::CognitoAccessToken => "AWS Cognito (access token)",
+            Provider::Google => "Google",
+            Provider::Firebase => "Firebase Authentication",
+            Provider::Auth0 => "Auth0",
+            Provider::Keycloak => "Keycloak",
+            Provider::GithubActions => "GitHub Actions",
+            Provider::GitlabCi => "GitLab CI/CD",
+            Provider::KubernetesProjected => "Kubernetes (projected service account token)",
+            Provider::KubernetesLegacy => "Kubernetes (legacy service account token)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How likely a detection is to be right
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// A single weak signal (e.g. a claim name used by the provider)
+    Low,
+    /// A strong signal (e.g. the issuer) or several weak ones
+    Medium,
+    /// A strong signal confirmed by other signals
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The provider that most likely minted a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    /// the detected provider
+    pub provider: Provider,
+    /// how likely the detection is to be right
+    pub confidence: Confidence,
+    /// the signals that matched
+    pub reasons: Vec<String>,
+}
+
+impl Detection {
+    /// Returns the detection as a JSON object with `name`, `confidence` and `reasons` fields
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "name": self.provider.to_string(),
+            "confidence": self.confidence.to_string(),
+            "reasons": self.reasons,
+        })
+    }
+}
+
+/// Accumulates the signals of a provider
+#[doc(hidden)]
+struct Score<'a> {
+    header: &'a Value,
+    claims: &'a Value,
+    points: u32,
+    reasons: Vec<String>,
+}
+
+impl<'a> Score<'a> {
+    #[doc(hidden)]
+    fn new(header: &'a Value, claims: &'a Value) -> Self {
+        Self {
+            header,
+            claims,
+            points: 0,
+            reasons: Vec::new(),
+        }
+    }
+
+    /// Adds `points` if `matched` is `true`
+    #[doc(hidden)]
+    fn add(&mut self, points: u32, matched: bool, reason: &str) {
+        if matched {
+            self.points += points;
+            self.reasons.push(reason.to_string());
+        }
+    }
+
+    #[doc(hidden)]
+    fn claim(&self, name: &str) -> Option<&'a Value> {
+        self.claims.get(name)
+    }
+
+    #[doc(hidden)]
+    fn claim_str(&self, name: &str) -> &'a str {
+        self.claims
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    }
+
+    #[doc(hidden)]
+    fn header_str(&self, name: &str) -> &'a str {
+        self.header
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Detects the identity provider or platform that minted a token
+///
+/// Returns `None` if no signal of a known provider is found.
+pub fn detect(header: &Value, claims: &Value) -> Option<Detection> {
+    let candidates = vec![
+        entra(header, claims),
+        cognito(header, claims),
+        google(header, claims),
+        firebase(header, claims),
+        auth0(header, claims),
+        keycloak(header, claims),
+        github_actions(header, claims),
+        gitlab_ci(header, claims),
+        kubernetes(header, claims),
+    ];
+    // on a tie, the provider listed first wins
+    let mut best: Option<(Provider, Score)> = None;
+    for (provider, score) in candidates {
+        if score.points > best.as_ref().map_or(0, |(_, best)| best.points) {
+            best = Some((provider, score));
+        }
+    }
+    let (provider, score) = best?;
+    let confidence = match score.points {
+        1 => Confidence::Low,
+        2 => Confidence::Medium,
+        _ => Confidence::High,
+    };
+    Some(Detection {
+        provider,
+        confidence,
+        reasons: score.reasons,
+    })
+}
+
+#[doc(hidden)]
+fn entra<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    let iss = score.claim_str("iss");
+    let v1 = iss.starts_with("https://sts.windows.net/");
+    let v2 = iss.starts_with("https://login.microsoftonline.com/") && iss.ends_with("/v2.0");
+    score.add(2, v1, "iss is a v1.0 Entra ID issuer (sts.windows.net)");
+    score.add(
+        2,
+        v2,
+        "iss is a v2.0 Entra ID issuer (login.microsoftonline.com)",
+    );
+    score.add(
+        1,
+        score.claim("tid").is_some() && score.claim("oid").is_some(),
+        "has the tid and oid claims",
+    );
+    let x5t = score.header_str("x5t");
+    score.add(
+        1,
+        !x5t.is_empty() && x5t == score.header_str("kid"),
+        "kid is the x5t thumbprint",
+    );
+    let version = score.claim_str("ver");
+    let provider = if v1 || (!v2 && version == "1.0") {
+        Provider::EntraV1
+    } else {
+        Provider::EntraV2
+    };
+    (provider, score)
+}
+
+#[doc(hidden)]
+fn cognito<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    let iss = score.claim_str("iss");
+    score.add(
+        2,
+        iss.starts_with("https://cognito-idp.") && iss.contains(".amazonaws.com/"),
+        "iss is a Cognito user pool",
+    );
+    let token_use = score.claim_str("token_use");
+    score.add(
+        1,
+        token_use == "id" || token_use == "access",
+        "has the token_use claim",
+    );
+    score.add(
+        1,
+        score.claim("cognito:username").is_some() || score.claim("cognito:groups").is_some(),
+        "has cognito: claims",
+    );
+    let provider = if token_use == "access" {
+        Provider::CognitoAccessToken
+    } else {
+        Provider::CognitoIdToken
+    };
+    (provider, score)
+}
+
+#[doc(hidden)]
+fn google<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    let iss = score.claim_str("iss");
+    score.add(
+        2,
+        iss == "https://accounts.google.com" || iss == "accounts.google.com",
+        "iss is accounts.google.com",
+    );
+    score.add(
+        1,
+        score.claim("hd").is_some() || score.claim("google").is_some(),
+        "has the hd or google claims",
+    );
+    let kid = score.header_str("kid");
+    score.add(
+        1,
+        score.points > 0 && kid.len() == 40 && kid.chars().all(|c| c.is_ascii_hexdigit()),
+        "kid is a 40 characters hex string",
+    );
+    (Provider::Google, score)
+}
+
+#[doc(hidden)]
+fn firebase<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    score.add(
+        2,
+        score
+            .claim_str("iss")
+            .starts_with("https://securetoken.google.com/"),
+        "iss is securetoken.google.com",
+    );
+    score.add(
+        1,
+        score.claim("firebase").is_some(),
+        "has the firebase claim",
+    );
+    (Provider::Firebase, score)
+}
+
+#[doc(hidden)]
+fn auth0<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    let host = score
+        .claim_str("iss")
+        .strip_prefix("https://")
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    score.add(
+        2,
+        host.ends_with(".auth0.com"),
+        "iss is an auth0.com tenant",
+    );
+    score.add(1, score.claim("gty").is_some(), "has the gty claim");
+    let namespaced = score
+        .claims
+        .as_object()
+        .is_some_and(|claims| claims.keys().any(|name| name.starts_with("https://")));
+    score.add(1, namespaced, "has namespaced custom claims");
+    (Provider::Auth0, score)
+}
+
+#[doc(hidden)]
+fn keycloak<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    score.add(
+        2,
+        score.claim_str("iss").contains("/realms/"),
+        "iss is a Keycloak realm (/realms/)",
+    );
+    score.add(
+        1,
+        score.claim("realm_access").is_some() || score.claim("resource_access").is_some(),
+        "has the realm_access or resource_access claims",
+    );
+    score.add(
+        1,
+        matches!(score.claim_str("typ"), "Bearer" | "ID" | "Refresh"),
+        "has a Keycloak typ claim",
+    );
+    (Provider::Keycloak, score)
+}
+
+#[doc(hidden)]
+fn github_actions<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    score.add(
+        2,
+        score
+            .claim_str("iss")
+            .starts_with("https://token.actions.githubusercontent.com"),
+        "iss is token.actions.githubusercontent.com",
+    );
+    score.add(
+        1,
+        score.claim("job_workflow_ref").is_some() || score.claim("runner_environment").is_some(),
+        "has GitHub Actions workflow claims",
+    );
+    (Provider::GithubActions, score)
+}
+
+#[doc(hidden)]
+fn gitlab_ci<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    score.add(
+        2,
+        score.claim("project_path").is_some() && score.claim("pipeline_id").is_some(),
+        "has the project_path and pipeline_id claims",
+    );
+    score.add(
+        1,
+        score.claim_str("sub").starts_with("project_path:"),
+        "sub starts with project_path:",
+    );
+    score.add(
+        1,
+        score.points > 0 && score.claim_str("iss").contains("gitlab"),
+        "iss is a GitLab instance",
+    );
+    (Provider::GitlabCi, score)
+}
+
+#[doc(hidden)]
+fn kubernetes<'a>(header: &'a Value, claims: &'a Value) -> (Provider, Score<'a>) {
+    let mut score = Score::new(header, claims);
+    let legacy = score.claim_str("iss") == "kubernetes/serviceaccount";
+    score.add(
+        2,
+        score.claim("kubernetes.io").is_some(),
+        "has the kubernetes.io claim",
+    );
+    score.add(2, legacy, "iss is kubernetes/serviceaccount");
+    score.add(
+        1,
+        score.claim_str("sub").starts_with("system:serviceaccount:"),
+        "sub is a service account (system:serviceaccount:)",
+    );
+    let provider = if legacy {
+        Provider::KubernetesLegacy
+    } else {
+        Provider::KubernetesProjected
+    };
+    (provider, score)
+}
+
+#[cfg(test)]
+mod test;
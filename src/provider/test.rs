@@ -0,0 +1,161 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+fn detect_claims(claims: Value) -> Option<Detection> {
+    detect(&serde_json::json!({"alg": "RS256"}), &claims)
+}
+
+#[test]
+fn assert_detect_entra() {
+    let header = serde_json::json!({
+        "alg": "RS256",
+        "x5t": "nOo3ZDrODXEK1jKWhXslHR_KXEg",
+        "kid": "nOo3ZDrODXEK1jKWhXslHR_KXEg"
+    });
+    let claims = serde_json::json!({
+        "iss": "https://sts.windows.net/72f988bf-86f1-41af-91ab-2d7cd011db47/",
+        "tid": "72f988bf-86f1-41af-91ab-2d7cd011db47",
+        "oid": "690222be-ff1a-4d56-abd1-7e4f7d38e474",
+        "ver": "1.0"
+    });
+    let detection = detect(&header, &claims).unwrap();
+    assert_eq!(detection.provider, Provider::EntraV1);
+    assert_eq!(detection.confidence, Confidence::High);
+    assert_eq!(
+        detection.reasons,
+        [
+            "iss is a v1.0 Entra ID issuer (sts.windows.net)",
+            "has the tid and oid claims",
+            "kid is the x5t thumbprint"
+        ]
+    );
+
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://login.microsoftonline.com/72f988bf-86f1-41af-91ab-2d7cd011db47/v2.0",
+        "ver": "2.0"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::EntraV2);
+    assert_eq!(detection.confidence, Confidence::Medium);
+}
+
+#[test]
+fn assert_detect_cognito() {
+    let iss = "https://cognito-idp.us-east-1.amazonaws.com/us-east-1_example";
+    let detection = detect_claims(serde_json::json!({
+        "iss": iss,
+        "token_use": "access",
+        "client_id": "abc"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::CognitoAccessToken);
+    assert_eq!(detection.confidence, Confidence::High);
+    let detection = detect_claims(serde_json::json!({
+        "iss": iss,
+        "token_use": "id",
+        "cognito:username": "jdoe"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::CognitoIdToken);
+    assert_eq!(
+        detection.provider.default_profile(),
+        Some(Profile::OidcIdToken)
+    );
+}
+
+#[test]
+fn assert_detect_kubernetes() {
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://kubernetes.default.svc.cluster.local",
+        "sub": "system:serviceaccount:default:app",
+        "kubernetes.io": {"namespace": "default", "serviceaccount": {"name": "app"}}
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::KubernetesProjected);
+    assert_eq!(detection.confidence, Confidence::High);
+    let detection = detect_claims(serde_json::json!({
+        "iss": "kubernetes/serviceaccount",
+        "kubernetes.io/serviceaccount/namespace": "default"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::KubernetesLegacy);
+    assert_eq!(detection.provider.source(), Some(Source::Kubernetes));
+}
+
+#[test]
+fn assert_detect_ci() {
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://token.actions.githubusercontent.com",
+        "runner_environment": "github-hosted"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::GithubActions);
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://gitlab.example.com",
+        "sub": "project_path:group/project:ref_type:branch:ref:main",
+        "project_path": "group/project",
+        "pipeline_id": "1212"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::GitlabCi);
+    assert_eq!(detection.confidence, Confidence::High);
+    assert_eq!(detection.provider.source(), None);
+}
+
+#[test]
+fn assert_detect_google_and_firebase() {
+    let header = serde_json::json!({
+        "alg": "RS256",
+        "kid": "a50f6e70ef4b548a5fd9142eecd1fb8f54dce9ee"
+    });
+    let detection = detect(
+        &header,
+        &serde_json::json!({"iss": "https://accounts.google.com"}),
+    )
+    .unwrap();
+    assert_eq!(detection.provider, Provider::Google);
+    assert_eq!(detection.confidence, Confidence::High);
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://securetoken.google.com/my-project",
+        "firebase": {"sign_in_provider": "password"}
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::Firebase);
+}
+
+#[test]
+fn assert_detect_weak_signals() {
+    let detection = detect_claims(serde_json::json!({"gty": "client-credentials"})).unwrap();
+    assert_eq!(detection.provider, Provider::Auth0);
+    assert_eq!(detection.confidence, Confidence::Low);
+    let detection = detect_claims(serde_json::json!({
+        "iss": "https://sso.example.com/realms/demo",
+        "realm_access": {"roles": ["user"]},
+        "typ": "Bearer"
+    }))
+    .unwrap();
+    assert_eq!(detection.provider, Provider::Keycloak);
+    assert_eq!(detection.confidence, Confidence::High);
+}
+
+#[test]
+fn assert_detect_nothing() {
+    assert_eq!(
+        detect_claims(serde_json::json!({"sub": "1234567890", "name": "John Doe"})),
+        None
+    );
+}
+
+#[test]
+fn assert_detection_to_json() {
+    let detection = detect_claims(serde_json::json!({"gty": "password"})).unwrap();
+    assert_eq!(
+        detection.to_json(),
+        serde_json::json!({
+            "name": "Auth0",
+            "confidence": "low",
+            "reasons": ["has the gty claim"]
+        })
+    );
+}
@@ -0,0 +1,164 @@
+//! # Redact
+//!
+//! `redact` hides the personal data of a token so that its structure can be
+//! shared (e.g. in a bug report) without leaking who it was issued to.
+//!
+//! The values of the personal claims (names, email addresses, phone numbers,
+//! postal addresses, ... and any other configured claim) are replaced, at any
+//! depth, with a salted hash: the same value gives the same hash with the same
+//! salt, so that two tokens of the same user can still be matched. The other
+//! claims (`iss`, `exp`, `scope`, ...) are kept as they are. Tokens found in
+//! string values (a claim containing a JWT, a URL with an `id_token`, ...) are
+//! replaced by their redacted `safe_token`, or by a hash if they can't be
+//! decoded.
+//!
+//! `safe_token` re-encodes the redacted header and claims as an unsigned
+//! compact JWT, which can be decoded again with `jwt::parse`.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::{jwt, redact};
+//!
+//! let claims = serde_json::json!({"sub": "42", "email": "jane@example.com"});
+//! let redactor = redact::Redactor::new("ticket-1234");
+//! let redacted = redactor.redact(&claims);
+//! assert_eq!(redacted["sub"], "42");
+//! assert!(redacted["email"].as_str().unwrap().starts_with("redacted:"));
+//! assert_eq!(redactor.redact(&claims), redacted);
+//!
+//! let header = serde_json::json!({"alg": "RS256", "kid": "1"});
+//! let token = redact::safe_token(&header, &redacted);
+//! assert!(token.ends_with('.'));
+//! assert_eq!(jwt::parse(&token).unwrap().body, redacted);
+//! ```
+
+use base64::Engine as _;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::har;
+use crate::jwt::{self, get_base64};
+
+/// The claims redacted by default: the personal data of the OpenID Connect
+/// standard claims
+pub const DEFAULT_CLAIMS: &[&str] = &[
+    "name",
+    "given_name",
+    "family_name",
+    "middle_name",
+    "nickname",
+    "preferred_username",
+    "email",
+    "phone",
+    "phone_number",
+    "address",
+    "birthdate",
+];
+
+/// The prefix of the values replaced by a hash
+pub const PREFIX: &str = "redacted:";
+
+/// Replaces the values of personal claims with salted hashes
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    /// the salt of the hashes
+    pub salt: Vec<u8>,
+    /// the names of the redacted claims (compared case-insensitively)
+    pub claims: Vec<String>,
+}
+
+impl Redactor {
+    /// Creates a redactor of the `DEFAULT_CLAIMS` with the given salt
+    pub fn new<T: AsRef<[u8]>>(salt: T) -> Self {
+        Self {
+            salt: salt.as_ref().to_vec(),
+            claims: DEFAULT_CLAIMS.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// Returns `true` if the claim with the given name is redacted
+    pub fn is_redacted(&self, name: &str) -> bool {
+        self.claims.iter().any(|c| c.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the salted hash of a value (e.g. `redacted:Jx3Ghd0nqZ1w`)
+    ///
+    /// Strings are hashed without their JSON quotes, other values as JSON.
+    pub fn hash(&self, value: &Value) -> String {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(&self.salt);
+        hasher.update([0]);
+        hasher.update(text.as_bytes());
+        format!("{}{}", PREFIX, get_base64().encode(&hasher.finalize()[..9]))
+    }
+
+    /// Returns a copy of a JSON value with the redacted claims replaced by
+    /// their hash, at any depth
+    ///
+    /// Objects with a redacted `name` and a `value` (like the SD-JWT
+    /// disclosures of the `--full` output) have their `value` hashed too, and
+    /// the tokens found in strings are redacted with `redact_text`.
+    pub fn redact(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(members) => {
+                let disclosed = members
+                    .get("name")
+                    .and_then(|name| name.as_str())
+                    .is_some_and(|name| self.is_redacted(name));
+                let members: Map<String, Value> = members
+                    .iter()
+                    .map(|(name, member)| {
+                        let redacted = if self.is_redacted(name) || (disclosed && name == "value") {
+                            Value::String(self.hash(member))
+                        } else {
+                            self.redact(member)
+                        };
+                        (name.clone(), redacted)
+                    })
+                    .collect();
+                Value::Object(members)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(|i| self.redact(i)).collect()),
+            Value::String(text) => Value::String(self.redact_text(text)),
+            other => other.clone(),
+        }
+    }
+
+    /// Replaces the tokens found in a text (see `har::find_tokens`) with
+    /// their redacted `safe_token`, or with their hash if their claims can't be
+    /// read (e.g. an encrypted token)
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for token in har::find_tokens(text) {
+            let replacement = match jwt::parse(token) {
+                Ok(parsed) if parsed.body.is_object() => {
+                    safe_token(&self.redact(&parsed.header), &self.redact(&parsed.body))
+                }
+                _ => self.hash(&Value::from(token)),
+            };
+            redacted = redacted.replace(token, &replacement);
+        }
+        redacted
+    }
+}
+
+/// Encodes a header and a set of claims as an unsigned compact JWT (with an
+/// empty signature)
+///
+/// The header is kept as it is (including `alg`), so the token describes the
+/// original one but cannot pass a signature verification.
+pub fn safe_token(header: &Value, claims: &Value) -> String {
+    format!(
+        "{}.{}.",
+        get_base64().encode(header.to_string()),
+        get_base64().encode(claims.to_string())
+    )
+}
+
+#[cfg(test)]
+mod test;
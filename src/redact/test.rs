@@ -0,0 +1,99 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn assert_redacts_personal_claims_at_any_depth() {
+    let claims = serde_json::json!({
+        "sub": "42",
+        "Email": "jane@example.com",
+        "address": {"street_address": "1 Main St", "country": "IE"},
+        "act": {"sub": "admin", "name": "Admin"},
+        "members": [{"given_name": "Jane"}],
+        "exp": 1516242622,
+    });
+    let redactor = Redactor::new("salt");
+    let redacted = redactor.redact(&claims);
+    assert_eq!(redacted["sub"], "42");
+    assert_eq!(redacted["exp"], 1516242622);
+    assert_eq!(redacted["act"]["sub"], "admin");
+    for value in [
+        &redacted["Email"],
+        &redacted["address"],
+        &redacted["act"]["name"],
+        &redacted["members"][0]["given_name"],
+    ] {
+        let value = value.as_str().unwrap();
+        assert!(value.starts_with(PREFIX), "{}", value);
+        assert_eq!(value.len(), PREFIX.len() + 12);
+    }
+    assert!(!redacted.to_string().contains("Jane"));
+}
+
+#[test]
+fn assert_hashes_are_stable_per_salt() {
+    let jane = serde_json::json!("jane@example.com");
+    let redactor = Redactor::new("salt");
+    assert_eq!(redactor.hash(&jane), Redactor::new("salt").hash(&jane));
+    assert_ne!(redactor.hash(&jane), Redactor::new("pepper").hash(&jane));
+    assert_ne!(
+        redactor.hash(&jane),
+        redactor.hash(&serde_json::json!("john@example.com"))
+    );
+    // the salt and the value are not simply concatenated
+    assert_ne!(
+        Redactor::new("ab").hash(&serde_json::json!("c")),
+        Redactor::new("a").hash(&serde_json::json!("bc"))
+    );
+
+    let mut redactor = Redactor::new("salt");
+    redactor.claims.push("tenant_name".to_string());
+    let disclosure = serde_json::json!({"name": "tenant_name", "value": "ACME", "salt": "x"});
+    let redacted = redactor.redact(&disclosure);
+    assert_eq!(redacted["value"], redactor.hash(&serde_json::json!("ACME")));
+    assert_eq!(redacted["salt"], "x");
+}
+
+#[test]
+fn assert_redacts_tokens_in_strings() {
+    let redactor = Redactor::new("salt");
+    let inner = safe_token(
+        &serde_json::json!({"alg": "ES256"}),
+        &serde_json::json!({"sub": "42", "email": "jane@example.com"}),
+    );
+    let inner = format!("{}c2lnbmF0dXJl", inner);
+    let claims = serde_json::json!({
+        "inner": inner,
+        "url": format!("https://app.example/cb?id_token={}&state=x", inner),
+        "encrypted": "eyJhbGciOiJSU0EtT0FFUCIsImVuYyI6IkEyNTZHQ00ifQ.a.b.c.d",
+    });
+    let redacted = redactor.redact(&claims);
+    assert!(!redacted.to_string().contains(&inner));
+    assert!(!redacted.to_string().contains("c2lnbmF0dXJl"));
+
+    let nested = crate::jwt::parse(redacted["inner"].as_str().unwrap()).unwrap();
+    assert_eq!(nested.body["sub"], "42");
+    assert_eq!(
+        nested.body["email"],
+        redactor.hash(&Value::from("jane@example.com"))
+    );
+    assert!(nested.signature.is_empty());
+    assert_eq!(
+        redacted["url"],
+        format!(
+            "https://app.example/cb?id_token={}&state=x",
+            redacted["inner"].as_str().unwrap()
+        )
+    );
+    assert!(redacted["encrypted"].as_str().unwrap().starts_with(PREFIX));
+}
+
+#[test]
+fn assert_safe_token_is_unsigned() {
+    let header = serde_json::json!({"alg": "ES256", "typ": "JWT"});
+    let claims = serde_json::json!({"sub": "42"});
+    let token = safe_token(&header, &claims);
+    let parsed = crate::jwt::parse(&token).unwrap();
+    assert_eq!(parsed.header, header);
+    assert_eq!(parsed.body, claims);
+    assert!(parsed.signature.is_empty());
+}
@@ -0,0 +1,433 @@
+//! # SD-JWT
+//!
+//! `sdjwt` parses [Selective Disclosure JWTs](https://datatracker.ietf.org/doc/html/draft-ietf-oauth-selective-disclosure-jwt),
+//! in the form `<issuer-jwt>~<disclosure>~...~<kb-jwt>`.
+//!
+//! Every disclosure is decoded and matched (using the hash algorithm declared
+//! in `_sd_alg`) with the digests found in the `_sd` arrays and in the
+//! `{"...": <digest>}` array items of the issuer JWT, so that the fully
+//! disclosed set of claims can be reconstructed. The optional key binding JWT
+//! is decoded as well and can be checked with `SdJwt::check_key_binding`.
+//!
+//! No signature is verified.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::sdjwt;
+//!
+//! // issuer JWT: {"alg":"none"}.{"iss":"https://issuer.example","_sd":["<digest>"],"_sd_alg":"sha-256"}
+//! // disclosure: ["salt","given_name","John"]
+//! let sd_jwt = sdjwt::parse("eyJhbGciOiJub25lIn0.eyJpc3MiOiJodHRwczovL2lzc3Vlci5leGFtcGxlIiwiX3NkIjpbImVEcVFwZFRYSlhiV2hmLUVzSTd6dzVYNk92WW1GTi1VWlFRTWVzWHdLUHciXSwiX3NkX2FsZyI6InNoYS0yNTYifQ.~WyJzYWx0IiwiZ2l2ZW5fbmFtZSIsIkpvaG4iXQ~").unwrap();
+//! assert_eq!(
+//!     sd_jwt.claims.to_string(),
+//!     "{\"given_name\":\"John\",\"iss\":\"https://issuer.example\"}"
+//! );
+//! assert!(sd_jwt.key_binding.is_none());
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use base64::Engine as _;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::jwt::{self, get_base64, JWTParseError, JWTParsePartError, Token};
+use crate::validation::Check;
+
+/// Represents an error while parsing an SD-JWT
+#[derive(Debug)]
+pub enum SdJwtError {
+    /// Indicates that the string does not contain any `~` separator
+    NotAnSdJwt(),
+    /// Indicates that the issuer-signed JWT could not be parsed
+    IssuerJwt(JWTParsePartError),
+    /// Indicates that the disclosure at the given position could not be decoded
+    InvalidDisclosure(usize, JWTParseError),
+    /// Indicates that the disclosure at the given position is not a valid disclosure array
+    MalformedDisclosure(usize),
+    /// Indicates that `_sd_alg` contains an unsupported hash algorithm
+    UnsupportedAlgorithm(String),
+    /// Indicates that the same digest appears more than once (in disclosures or in the payload)
+    DuplicateDigest(String),
+    /// Indicates that a disclosure would overwrite a claim that is already present
+    ConflictingClaim(String),
+    /// Indicates that the key binding JWT could not be parsed
+    KeyBindingJwt(JWTParsePartError),
+}
+
+impl fmt::Display for SdJwtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            SdJwtError::NotAnSdJwt() => "Not an SD-JWT (missing `~` separator)".to_string(),
+            SdJwtError::IssuerJwt(e) => format!("Invalid issuer JWT: {}", e),
+            SdJwtError::InvalidDisclosure(i, e) => format!("Invalid disclosure #{}: {}", i, e),
+            SdJwtError::MalformedDisclosure(i) => format!(
+                "Invalid disclosure #{}: expected an array of 2 or 3 elements starting with a salt",
+                i
+            ),
+            SdJwtError::UnsupportedAlgorithm(alg) => {
+                format!("Unsupported _sd_alg `{}`", alg)
+            }
+            SdJwtError::DuplicateDigest(digest) => format!("Duplicate digest `{}`", digest),
+            SdJwtError::ConflictingClaim(name) => {
+                format!("Disclosure conflicts with existing claim `{}`", name)
+            }
+            SdJwtError::KeyBindingJwt(e) => format!("Invalid key binding JWT: {}", e),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for SdJwtError {}
+
+/// Represents a decoded disclosure
+#[derive(Debug)]
+pub struct Disclosure {
+    /// the disclosure as it appears in the SD-JWT (base64url encoded)
+    pub encoded: String,
+    /// the base64url encoded digest of the disclosure, computed with `_sd_alg`
+    pub digest: String,
+    /// the salt of the disclosure
+    pub salt: String,
+    /// the name of the claim (`None` for array elements)
+    pub name: Option<String>,
+    /// the disclosed value
+    pub value: Value,
+    /// whether the digest of the disclosure was found in the issuer JWT
+    pub referenced: bool,
+}
+
+impl Disclosure {
+    /// Returns the disclosure as a JSON object
+    pub fn to_json(&self) -> Value {
+        let mut json = serde_json::json!({
+            "digest": self.digest,
+            "salt": self.salt,
+            "value": self.value,
+            "referenced": self.referenced,
+        });
+        if let Some(name) = &self.name {
+            json["name"] = Value::String(name.clone());
+        }
+        json
+    }
+}
+
+/// Represents a parsed SD-JWT
+#[derive(Debug)]
+pub struct SdJwt {
+    /// the issuer-signed JWT, as found in the SD-JWT
+    pub issuer_jwt: Token,
+    /// the disclosures, in the order they appear in the SD-JWT
+    pub disclosures: Vec<Disclosure>,
+    /// the key binding JWT, if any
+    pub key_binding: Option<Token>,
+    /// the fully disclosed set of claims
+    pub claims: Value,
+    /// the hash algorithm used for the digests (from `_sd_alg`, defaults to `sha-256`)
+    pub hash_algorithm: String,
+    /// the part of the SD-JWT covered by the `sd_hash` of the key binding JWT
+    presentation: String,
+}
+
+/// Returns `true` if a string looks like an SD-JWT (a JWT followed by `~`-separated parts)
+pub fn is_sd_jwt(s: &str) -> bool {
+    s.contains('~')
+}
+
+/// Computes the base64url encoded digest of some data with an SD-JWT hash algorithm
+///
+/// Supported algorithms are `sha-256`, `sha-384` and `sha-512`.
+///
+/// # Errors
+///
+/// This function will return `SdJwtError::UnsupportedAlgorithm` for any other algorithm
+pub fn digest(algorithm: &str, data: &[u8]) -> Result<String, SdJwtError> {
+    let hash = match algorithm {
+        "sha-256" => Sha256::digest(data).to_vec(),
+        "sha-384" => Sha384::digest(data).to_vec(),
+        "sha-512" => Sha512::digest(data).to_vec(),
+        other => return Err(SdJwtError::UnsupportedAlgorithm(other.to_string())),
+    };
+    Ok(get_base64().encode(hash))
+}
+
+#[doc(hidden)]
+fn parse_disclosure(
+    index: usize,
+    encoded: &str,
+    algorithm: &str,
+) -> Result<Disclosure, SdJwtError> {
+    let decoded = get_base64()
+        .decode(encoded)
+        .map_err(|e| SdJwtError::InvalidDisclosure(index, e.into()))?;
+    let array: Value = serde_json::from_slice(&decoded)
+        .map_err(|e| SdJwtError::InvalidDisclosure(index, e.into()))?;
+    let (salt, name, value) = match array.as_array().map(|a| a.as_slice()) {
+        Some([Value::String(salt), value]) => (salt.clone(), None, value.clone()),
+        Some([Value::String(salt), Value::String(name), value]) => {
+            (salt.clone(), Some(name.clone()), value.clone())
+        }
+        _ => return Err(SdJwtError::MalformedDisclosure(index)),
+    };
+    Ok(Disclosure {
+        encoded: encoded.to_string(),
+        digest: digest(algorithm, encoded.as_bytes())?,
+        salt,
+        name,
+        value,
+        referenced: false,
+    })
+}
+
+#[doc(hidden)]
+struct Resolver<'a> {
+    by_digest: HashMap<&'a str, usize>,
+    disclosures: &'a [Disclosure],
+    seen: HashSet<String>,
+    used: Vec<usize>,
+}
+
+impl Resolver<'_> {
+    fn mark_seen(&mut self, digest: &str) -> Result<(), SdJwtError> {
+        if !self.seen.insert(digest.to_string()) {
+            return Err(SdJwtError::DuplicateDigest(digest.to_string()));
+        }
+        Ok(())
+    }
+
+    fn resolve(&mut self, value: &Value) -> Result<Value, SdJwtError> {
+        let disclosures = self.disclosures;
+        match value {
+            Value::Object(map) => {
+                let mut out = Map::new();
+                for (key, item) in map {
+                    if key != "_sd" {
+                        out.insert(key.clone(), self.resolve(item)?);
+                    }
+                }
+                let digests = map.get("_sd").and_then(|d| d.as_array());
+                for digest in digests.into_iter().flatten().filter_map(|d| d.as_str()) {
+                    self.mark_seen(digest)?;
+                    let index = match self.by_digest.get(digest) {
+                        Some(index) => *index,
+                        // decoy digest or undisclosed claim
+                        None => continue,
+                    };
+                    let disclosure = &disclosures[index];
+                    let name = match &disclosure.name {
+                        Some(name) if name != "_sd" && name != "..." => name.clone(),
+                        _ => return Err(SdJwtError::MalformedDisclosure(index)),
+                    };
+                    if out.contains_key(&name) {
+                        return Err(SdJwtError::ConflictingClaim(name));
+                    }
+                    self.used.push(index);
+                    let value = self.resolve(&disclosure.value)?;
+                    out.insert(name, value);
+                }
+                Ok(Value::Object(out))
+            }
+            Value::Array(items) => {
+                let mut out = Vec::new();
+                for item in items {
+                    let digest = item
+                        .as_object()
+                        .filter(|o| o.len() == 1)
+                        .and_then(|o| o.get("..."))
+                        .and_then(|d| d.as_str());
+                    match digest {
+                        Some(digest) => {
+                            self.mark_seen(digest)?;
+                            if let Some(index) = self.by_digest.get(digest).copied() {
+                                if disclosures[index].name.is_some() {
+                                    return Err(SdJwtError::MalformedDisclosure(index));
+                                }
+                                self.used.push(index);
+                                out.push(self.resolve(&disclosures[index].value)?);
+                            }
+                        }
+                        None => out.push(self.resolve(item)?),
+                    }
+                }
+                Ok(Value::Array(out))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+/// Parses an SD-JWT from a string and reconstructs the disclosed claims
+///
+/// # Errors
+///
+/// This function will return an `SdJwtError` if any of the parts cannot be
+/// decoded, if the hash algorithm is not supported or if the disclosures are
+/// inconsistent with the issuer JWT (duplicate digests or conflicting claims)
+pub fn parse<T: AsRef<str>>(sd_jwt: T) -> Result<SdJwt, SdJwtError> {
+    let sd_jwt = sd_jwt.as_ref().trim();
+    let last_tilde = sd_jwt.rfind('~').ok_or(SdJwtError::NotAnSdJwt())?;
+    let presentation = &sd_jwt[..=last_tilde];
+    let kb_jwt = &sd_jwt[last_tilde + 1..];
+
+    let mut parts = presentation[..last_tilde].split('~');
+    let issuer_jwt = jwt::parse(parts.next().unwrap_or_default()).map_err(SdJwtError::IssuerJwt)?;
+    let hash_algorithm = issuer_jwt
+        .body
+        .get("_sd_alg")
+        .and_then(|a| a.as_str())
+        .unwrap_or("sha-256")
+        .to_string();
+
+    let mut disclosures = Vec::new();
+    for (i, encoded) in parts.filter(|p| !p.is_empty()).enumerate() {
+        disclosures.push(parse_disclosure(i, encoded, &hash_algorithm)?);
+    }
+
+    let mut by_digest = HashMap::new();
+    for (i, disclosure) in disclosures.iter().enumerate() {
+        if by_digest.insert(disclosure.digest.as_str(), i).is_some() {
+            return Err(SdJwtError::DuplicateDigest(disclosure.digest.clone()));
+        }
+    }
+    let mut resolver = Resolver {
+        by_digest,
+        disclosures: &disclosures,
+        seen: HashSet::new(),
+        used: Vec::new(),
+    };
+    let mut claims = resolver.resolve(&issuer_jwt.body)?;
+    if let Value::Object(map) = &mut claims {
+        map.remove("_sd_alg");
+    }
+    let used = resolver.used;
+    for index in used {
+        disclosures[index].referenced = true;
+    }
+
+    let key_binding = if kb_jwt.is_empty() {
+        None
+    } else {
+        Some(jwt::parse(kb_jwt).map_err(SdJwtError::KeyBindingJwt)?)
+    };
+
+    Ok(SdJwt {
+        issuer_jwt,
+        disclosures,
+        key_binding,
+        claims,
+        hash_algorithm,
+        presentation: presentation.to_string(),
+    })
+}
+
+impl SdJwt {
+    /// Checks the key binding JWT, if present
+    ///
+    /// The checks cover the `typ` header (`kb+jwt`), the `sd_hash` claim (which must
+    /// match the digest of the issuer JWT and of the disclosures), the `iat`
+    /// claim (which must not be in the future compared to `now`) and the `nonce`
+    /// and `aud` claims (which must be present and, if given, equal to the
+    /// expected values: without an expected value, the check only tells that
+    /// the claim is present). The signature is not verified.
+    ///
+    /// Returns an empty list if there is no key binding JWT.
+    pub fn check_key_binding(
+        &self,
+        expected_nonce: Option<&str>,
+        expected_aud: Option<&str>,
+        now: u64,
+    ) -> Vec<Check> {
+        let kb = match &self.key_binding {
+            Some(kb) => kb,
+            None => return Vec::new(),
+        };
+        let mut checks = Vec::new();
+
+        match kb.header.get("typ").and_then(|t| t.as_str()) {
+            Some("kb+jwt") => checks.push(Check::pass("typ", "typ is kb+jwt")),
+            other => checks.push(Check::fail(
+                "typ",
+                format!("expected typ kb+jwt, found {:?}", other.unwrap_or_default()),
+            )),
+        }
+
+        match digest(&self.hash_algorithm, self.presentation.as_bytes()) {
+            Ok(expected) => match kb.body.get("sd_hash").and_then(|h| h.as_str()) {
+                Some(sd_hash) if sd_hash == expected => {
+                    checks.push(Check::pass("sd_hash", "sd_hash matches the presentation"))
+                }
+                Some(sd_hash) => checks.push(Check::fail(
+                    "sd_hash",
+                    format!("sd_hash is {}, expected {}", sd_hash, expected),
+                )),
+                None => checks.push(Check::fail("sd_hash", "sd_hash claim is missing")),
+            },
+            Err(e) => checks.push(Check::fail("sd_hash", e.to_string())),
+        }
+
+        match kb.body.get("iat").and_then(|i| i.as_f64()) {
+            Some(iat) if iat <= now as f64 => {
+                checks.push(Check::pass("iat", "iat is not in the future"))
+            }
+            Some(_) => checks.push(Check::fail("iat", "iat is in the future")),
+            None => checks.push(Check::fail("iat", "iat claim is missing")),
+        }
+
+        for (name, expected) in [("nonce", expected_nonce), ("aud", expected_aud)] {
+            let check = match (kb.body.get(name).and_then(|v| v.as_str()), expected) {
+                (None, _) => Check::fail(name, format!("{} claim is missing", name)),
+                (Some(actual), Some(expected)) if actual != expected => Check::fail(
+                    name,
+                    format!("{} is {}, expected {}", name, actual, expected),
+                ),
+                (Some(actual), Some(_)) => Check::pass(name, format!("{} is {}", name, actual)),
+                (Some(actual), None) => Check::pass(
+                    name,
+                    format!(
+                        "{} is present ({}), but not compared with an expected value",
+                        name, actual
+                    ),
+                ),
+            };
+            checks.push(check);
+        }
+
+        checks
+    }
+
+    /// Returns the SD-JWT as a JSON object with the `header` and the disclosed
+    /// `claims` of the issuer JWT, the `disclosures` and the `key_binding` JWT (if any)
+    ///
+    /// The key binding JWT comes with its checks (see `check_key_binding`).
+    pub fn to_json(
+        &self,
+        expected_nonce: Option<&str>,
+        expected_aud: Option<&str>,
+        now: u64,
+    ) -> Value {
+        let mut json = serde_json::json!({
+            "header": self.issuer_jwt.header,
+            "claims": self.claims,
+            "disclosures": self.disclosures.iter().map(|d| d.to_json()).collect::<Vec<_>>(),
+        });
+        if let Some(kb) = &self.key_binding {
+            json["key_binding"] = serde_json::json!({
+                "header": kb.header,
+                "claims": kb.body,
+                "checks": self
+                    .check_key_binding(expected_nonce, expected_aud, now)
+                    .iter()
+                    .map(|c| c.to_json())
+                    .collect::<Vec<_>>(),
+            });
+        }
+        json
+    }
+}
+
+#[cfg(test)]
+mod test;
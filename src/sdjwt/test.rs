@@ -0,0 +1,139 @@
+#[cfg(test)]
+use super::*;
+
+// Issuer JWT with payload:
+// {"iss":"https://issuer.example","_sd":[<given_name>,<family_name>,<decoy>],"_sd_alg":"sha-256",
+//  "nationalities":[{"...":<FR>},"IT"],"address":{"_sd":[<street_address>],"country":"FR"}}
+// followed by the disclosures of given_name, FR and street_address (family_name is not disclosed)
+#[doc(hidden)]
+const PRESENTATION: &str = "eyJhbGciOiJFUzI1NiIsInR5cCI6ImRjK3NkLWp3dCJ9.eyJpc3MiOiJodHRwczovL2lzc3Vlci5leGFtcGxlIiwiX3NkIjpbIjRjRHVaVVJTbHhWdEFJcDRfOEt5T2pTZTNnZ3dtYW9XV1hpelpJRFpjUTAiLCJSQzB4S3AyQzZfNVZlZE9RSW9DbllYZVJpbGl3T0FLVjNaUmZoRGhCZGVvIiwidmV1Ym9pcjQtblBsbi1mRTA4U0s0UlpXRjkxMnh5QjNQTjlzdkRPcEhkYyJdLCJfc2RfYWxnIjoic2hhLTI1NiIsIm5hdGlvbmFsaXRpZXMiOlt7Ii4uLiI6Ik9wUHIzU01RSzJxOXpUTDN0TEhKT21HTktPNGpOTksxRHJ5NFhNVHBrOGMifSwiSVQiXSwiYWRkcmVzcyI6eyJfc2QiOlsicGdtRjNqMENVQXJiTVlWTWJ6STRWYmpvVk5RYmloUDFleG1qMTlJcTd2dyJdLCJjb3VudHJ5IjoiRlIifX0.c2ln~WyJzMSIsImdpdmVuX25hbWUiLCJKb2huIl0~WyJzMyIsIkZSIl0~WyJzNCIsInN0cmVldF9hZGRyZXNzIiwiTWFpbiBTdCJd~";
+
+// {"alg":"ES256","typ":"kb+jwt"}.{"nonce":"1234567890","aud":"https://verifier.example","iat":1700000000,"sd_hash":<sd_hash of PRESENTATION>}
+#[doc(hidden)]
+const KB_JWT: &str = "eyJhbGciOiJFUzI1NiIsInR5cCI6ImtiK2p3dCJ9.eyJub25jZSI6IjEyMzQ1Njc4OTAiLCJhdWQiOiJodHRwczovL3ZlcmlmaWVyLmV4YW1wbGUiLCJpYXQiOjE3MDAwMDAwMDAsInNkX2hhc2giOiJ5a093S3Y5NXhmWFRHbl94RzZVT0t0djNlYXB2T1ctNjBMSnlzTUtKUWRVIn0.c2ln";
+
+#[test]
+fn assert_parse_reconstructs_disclosed_claims() {
+    let sd_jwt = parse(PRESENTATION).unwrap();
+    assert_eq!(
+        sd_jwt.claims,
+        serde_json::json!({
+            "iss": "https://issuer.example",
+            "given_name": "John",
+            "nationalities": ["FR", "IT"],
+            "address": {"country": "FR", "street_address": "Main St"}
+        })
+    );
+    assert_eq!(sd_jwt.hash_algorithm, "sha-256");
+    assert_eq!(sd_jwt.disclosures.len(), 3);
+    assert!(sd_jwt.disclosures.iter().all(|d| d.referenced));
+    assert_eq!(sd_jwt.disclosures[1].name, None);
+    assert_eq!(sd_jwt.disclosures[1].value, "FR");
+    assert!(sd_jwt.key_binding.is_none());
+    assert!(sd_jwt.check_key_binding(None, None, 0).is_empty());
+}
+
+#[test]
+fn assert_parse_issuer_jwt_only() {
+    let issuer_jwt = PRESENTATION.split('~').next().unwrap();
+    let sd_jwt = parse(format!("{}~", issuer_jwt)).unwrap();
+    assert_eq!(
+        sd_jwt.claims,
+        serde_json::json!({
+            "iss": "https://issuer.example",
+            "nationalities": ["IT"],
+            "address": {"country": "FR"}
+        })
+    );
+}
+
+#[test]
+fn assert_unreferenced_disclosures_are_reported() {
+    // ["s9","extra","value"] is not referenced by the issuer JWT
+    let sd_jwt = parse(format!("{}WyJzOSIsImV4dHJhIiwidmFsdWUiXQ~", PRESENTATION)).unwrap();
+    assert!(!sd_jwt.disclosures[3].referenced);
+    assert!(sd_jwt.claims.get("extra").is_none());
+}
+
+#[test]
+fn assert_parse_fails_with_duplicate_disclosures() {
+    let err = parse(format!(
+        "{}WyJzMSIsImdpdmVuX25hbWUiLCJKb2huIl0~",
+        PRESENTATION
+    ))
+    .unwrap_err();
+    assert!(err.to_string().starts_with("Duplicate digest"));
+}
+
+#[test]
+fn assert_parse_fails_with_malformed_disclosure() {
+    // ["only-one-element"]
+    let issuer_jwt = PRESENTATION.split('~').next().unwrap();
+    let err = parse(format!("{}~WyJvbmx5LW9uZS1lbGVtZW50Il0~", issuer_jwt)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid disclosure #0: expected an array of 2 or 3 elements starting with a salt"
+    );
+    let err = parse(format!("{}~not base64~", issuer_jwt)).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Invalid disclosure #0: Base64 error"));
+}
+
+#[test]
+fn assert_parse_fails_without_separator() {
+    assert_eq!(
+        parse("a.b.c").unwrap_err().to_string(),
+        "Not an SD-JWT (missing `~` separator)"
+    );
+}
+
+#[test]
+fn assert_key_binding_checks_pass() {
+    let sd_jwt = parse(format!("{}{}", PRESENTATION, KB_JWT)).unwrap();
+    let kb = sd_jwt.key_binding.as_ref().unwrap();
+    assert_eq!(kb.header["typ"], "kb+jwt");
+    let checks = sd_jwt.check_key_binding(
+        Some("1234567890"),
+        Some("https://verifier.example"),
+        1700000001,
+    );
+    assert_eq!(checks.len(), 5);
+    assert!(crate::validation::all_passed(&checks), "{:?}", checks);
+
+    // without expected values, nonce and aud are only present
+    let checks = sd_jwt.check_key_binding(None, None, 1700000001);
+    assert!(crate::validation::all_passed(&checks), "{:?}", checks);
+    assert_eq!(
+        checks[3].detail,
+        "nonce is present (1234567890), but not compared with an expected value"
+    );
+}
+
+#[test]
+fn assert_key_binding_checks_fail() {
+    // drop the last disclosure: the sd_hash no longer matches
+    let without_last = PRESENTATION.trim_end_matches('~');
+    let without_last = &without_last[..=without_last.rfind('~').unwrap()];
+    let sd_jwt = parse(format!("{}{}", without_last, KB_JWT)).unwrap();
+    let checks = sd_jwt.check_key_binding(Some("other"), None, 1600000000);
+    let failed: Vec<&str> = checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(failed, vec!["sd_hash", "iat", "nonce"]);
+}
+
+#[test]
+fn assert_digest_algorithms() {
+    assert_eq!(
+        digest("sha-256", b"WyJzMSIsImdpdmVuX25hbWUiLCJKb2huIl0").unwrap(),
+        "4cDuZURSlxVtAIp4_8KyOjSe3ggwmaoWWXizZIDZcQ0"
+    );
+    assert_eq!(digest("sha-512", b"").unwrap().len(), 86);
+    assert_eq!(
+        digest("md5", b"").unwrap_err().to_string(),
+        "Unsupported _sd_alg `md5`"
+    );
+}
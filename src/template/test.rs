@@ -0,0 +1,97 @@
+#[cfg(test)]
+use super::*;
+
+#[doc(hidden)]
+fn context() -> Value {
+    serde_json::json!({
+        "header": {"alg": "RS256", "kid": "abc"},
+        "claims": {
+            "sub": "alice",
+            "exp": 1516242622,
+            "aud": ["api", "web"],
+            "cognito:groups": ["admin"],
+            "email": null
+        }
+    })
+}
+
+#[test]
+fn assert_render_paths_and_text() {
+    let template = parse("{{claims.sub}} signed with {{ header.alg }}").unwrap();
+    assert_eq!(
+        template.render(&context(), 0).unwrap(),
+        "alice signed with RS256"
+    );
+}
+
+#[test]
+fn assert_render_array_index_and_bracket_keys() {
+    let template = parse("{{claims.aud.1}} {{claims[\"cognito:groups\"] | join}}").unwrap();
+    assert_eq!(template.render(&context(), 0).unwrap(), "web admin");
+}
+
+#[test]
+fn assert_render_missing_values_and_defaults() {
+    let template = parse(
+        "[{{claims.nbf}}] {{claims.email | default:\"n/a\"}} {{claims.name | default:anonymous}}",
+    )
+    .unwrap();
+    assert_eq!(template.render(&context(), 0).unwrap(), "[] n/a anonymous");
+}
+
+#[test]
+fn assert_render_time_filters() {
+    let template =
+        parse("{{claims.exp | date}} {{claims.exp | date:'%H:%M'}} {{claims.exp | relative}}")
+            .unwrap();
+    assert_eq!(
+        template.render(&context(), 1516239022).unwrap(),
+        "2018-01-18T02:30:22Z 02:30 in 1 hour"
+    );
+}
+
+#[test]
+fn assert_render_text_filters() {
+    let template =
+        parse("{{claims.sub | upper}} {{claims.aud | json}} {{claims.aud | join:'+'}}").unwrap();
+    assert_eq!(
+        template.render(&context(), 0).unwrap(),
+        "ALICE [\"api\",\"web\"] api+web"
+    );
+}
+
+#[test]
+fn assert_render_literals() {
+    let template = parse("{{ \"{{\" }}claims.sub{{ '}}' }}").unwrap();
+    assert_eq!(template.render(&context(), 0).unwrap(), "{{claims.sub}}");
+}
+
+#[test]
+fn assert_parse_fails_with_unclosed_expression() {
+    let err = parse("hello {{claims.sub").unwrap_err();
+    assert_eq!(err.to_string(), "Unclosed expression starting at offset 6");
+}
+
+#[test]
+fn assert_parse_fails_with_unknown_filter() {
+    let err = parse("{{claims.sub | shout}}").unwrap_err();
+    assert_eq!(err.to_string(), "Unknown filter `shout`");
+}
+
+#[test]
+fn assert_parse_fails_with_invalid_expression() {
+    let err = parse("{{payload.sub}}").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid expression `payload.sub`");
+    assert!(parse("{{claims.sub upper}}").is_err());
+    assert!(parse("{{}}").is_err());
+}
+
+#[test]
+fn assert_render_fails_with_invalid_filter_input() {
+    let template = parse("{{claims.sub | date}}").unwrap();
+    let err = template.render(&context(), 0).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Filter `date` cannot be applied to \"alice\""
+    );
+}
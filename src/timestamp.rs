@@ -0,0 +1,304 @@
+//! # Timestamp
+//!
+//! `timestamp` is a collection of utilities to deal with the NumericDate values
+//! (seconds since the Unix epoch) used by time claims such as `exp`, `nbf` and
+//! `iat`.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::timestamp;
+//!
+//! assert_eq!(timestamp::format_iso8601(1516239022), "2018-01-18T01:30:22Z");
+//! assert_eq!(timestamp::format_relative(1516239022, 1516242622), "1 hour ago");
+//! assert_eq!(timestamp::format_relative(1516239022 + 120, 1516239022), "in 2 minutes");
+//! ```
+
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The claims placed on a `timeline`, in the order used for equal timestamps
+pub const TIME_CLAIMS: [&str; 4] = ["iat", "auth_time", "nbf", "exp"];
+
+/// Returns the current time as seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Converts a number of days since the Unix epoch into a (year, month, day) civil date
+#[doc(hidden)]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's `civil_from_days` algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a (year, month, day) civil date into a number of days since the Unix epoch
+///
+/// Returns `None` if the number of days does not fit in an `i64`.
+#[doc(hidden)]
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    // Howard Hinnant's `days_from_civil` algorithm
+    let year = year.checked_sub(i64::from(month <= 2))?;
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era.checked_mul(146_097)?.checked_add(doe - 719_468)
+}
+
+/// Returns the number of days of a month (`1` to `12`) in the proleptic Gregorian calendar
+#[doc(hidden)]
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year.rem_euclid(4) == 0
+            && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0) =>
+        {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses an ISO 8601 date and time (e.g. `2018-01-18T01:30:22.123+01:00`)
+/// into milliseconds since the Unix epoch
+///
+/// The fraction of second and the offset are optional, a date without offset
+/// is read as UTC. Returns `None` if the string is not in this form.
+pub fn parse_iso8601_millis(s: &str) -> Option<i64> {
+    let number = |part: &str| -> Option<i64> {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        part.parse().ok()
+    };
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year = number(date_parts.next()?)?;
+    let month = number(date_parts.next()?)?;
+    let day = number(date_parts.next()?)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&time[..i], &time[i..]),
+        None => (time, ""),
+    };
+    let offset_minutes = match offset {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let offset = &offset[1..];
+            let (hours, minutes) = offset
+                .split_once(':')
+                .unwrap_or((offset.get(..2)?, offset.get(2..)?));
+            let (hours, minutes) = (number(hours)?, number(minutes)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 60 + minutes)
+        }
+    };
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time_parts = time.splitn(3, ':');
+    let hours = number(time_parts.next()?)?;
+    let minutes = number(time_parts.next()?)?;
+    let seconds = number(time_parts.next()?)?;
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    let millis = if fraction.is_empty() {
+        0
+    } else {
+        // only the milliseconds are kept
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let digits = &fraction[..fraction.len().min(3)];
+        number(digits)? * 10_i64.pow(3 - digits.len() as u32)
+    };
+
+    // the year can be arbitrarily large
+    let days = days_from_civil(year, month as u32, day as u32)?;
+    let secs = days
+        .checked_mul(86_400)?
+        .checked_add(hours * 3600 + minutes * 60 + seconds - offset_minutes * 60)?;
+    secs.checked_mul(1000)?.checked_add(millis)
+}
+
+/// Formats a timestamp using a small subset of the `strftime` directives
+///
+/// Supported directives are `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` and `%%`.
+/// The time is always expressed in UTC. Any other character is copied as is.
+pub fn format(secs: i64, pattern: &str) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    let mut out = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&format!("{:04}", year)),
+            Some('m') => out.push_str(&format!("{:02}", month)),
+            Some('d') => out.push_str(&format!("{:02}", day)),
+            Some('H') => out.push_str(&format!("{:02}", time / 3600)),
+            Some('M') => out.push_str(&format!("{:02}", time % 3600 / 60)),
+            Some('S') => out.push_str(&format!("{:02}", time % 60)),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Formats a timestamp as an ISO 8601 date in UTC (e.g. `2018-01-18T01:30:22Z`)
+pub fn format_iso8601(secs: i64) -> String {
+    format(secs, "%Y-%m-%dT%H:%M:%SZ")
+}
+
+/// Formats a number of seconds as a coarse human readable duration (e.g. `3 days`)
+pub fn format_duration(secs: u64) -> String {
+    const UNITS: [(&str, u64); 5] = [
+        ("year", 365 * 86_400),
+        ("day", 86_400),
+        ("hour", 3600),
+        ("minute", 60),
+        ("second", 1),
+    ];
+    let (unit, size) = UNITS
+        .iter()
+        .find(|(_, size)| secs >= *size)
+        .copied()
+        .unwrap_or(UNITS[4]);
+    let amount = secs / size;
+    if amount == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+/// Parses a duration made of numbers followed by a unit (`s`, `m`, `h`, `d` or
+/// `w`), like `90s`, `15m` or `1h30m`, into a number of seconds
+///
+/// A number without unit is a number of seconds. Returns `None` if the string
+/// is not in this form.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = s.parse() {
+        return Some(secs);
+    }
+    let mut total: u64 = 0;
+    let mut amount: Option<u64> = None;
+    for c in s.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            amount = Some(
+                amount
+                    .unwrap_or(0)
+                    .checked_mul(10)?
+                    .checked_add(digit.into())?,
+            );
+            continue;
+        }
+        let unit: u64 = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            'w' => 7 * 86_400,
+            _ => return None,
+        };
+        total = total.checked_add(amount.take()?.checked_mul(unit)?)?;
+    }
+    // a trailing number must have a unit
+    match amount {
+        Some(_) => None,
+        None => Some(total),
+    }
+}
+
+/// Describes a timestamp relatively to `now` (e.g. `in 2 hours` or `3 days ago`)
+pub fn format_relative(secs: i64, now: i64) -> String {
+    // claims are untrusted: the difference of two i64 may not fit in an i64
+    let delta = i128::from(secs) - i128::from(now);
+    let duration = u64::try_from(delta.unsigned_abs()).unwrap_or(u64::MAX);
+    if delta == 0 {
+        "now".to_string()
+    } else if delta > 0 {
+        format!("in {}", format_duration(duration))
+    } else {
+        format!("{} ago", format_duration(duration))
+    }
+}
+
+/// A time claim of a token, placed on a timeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// the name of the claim (e.g. `exp`)
+    pub claim: String,
+    /// the value of the claim, in seconds since the Unix epoch
+    pub secs: i64,
+}
+
+impl Event {
+    /// Returns the event as a JSON object with `claim`, `timestamp`, `date`
+    /// (ISO 8601) and `relative` (to `now`) fields
+    pub fn to_json(&self, now: u64) -> Value {
+        serde_json::json!({
+            "claim": self.claim,
+            "timestamp": self.secs,
+            "date": format_iso8601(self.secs),
+            "relative": format_relative(self.secs, now as i64),
+        })
+    }
+}
+
+/// Returns the seconds of a NumericDate, which may have a fractional part
+/// (truncated, and saturated to the `i64` range)
+pub fn numeric_date(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_f64().map(|f| f as i64))
+}
+
+/// Returns the numeric time claims (see `TIME_CLAIMS`) of a token, sorted by time
+pub fn timeline(claims: &Value) -> Vec<Event> {
+    let mut events: Vec<Event> = TIME_CLAIMS
+        .iter()
+        .filter_map(|&claim| {
+            let secs = numeric_date(claims.get(claim)?)?;
+            Some(Event {
+                claim: claim.to_string(),
+                secs,
+            })
+        })
+        .collect();
+    events.sort_by_key(|event| event.secs);
+    events
+}
+
+#[cfg(test)]
+mod test;
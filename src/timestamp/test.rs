@@ -0,0 +1,134 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn assert_format_iso8601() {
+    assert_eq!(format_iso8601(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_iso8601(1516239022), "2018-01-18T01:30:22Z");
+    assert_eq!(format_iso8601(951782400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_iso8601(-1), "1969-12-31T23:59:59Z");
+}
+
+#[test]
+fn assert_format_with_pattern() {
+    assert_eq!(format(1516239022, "%d/%m/%Y %H:%M"), "18/01/2018 01:30");
+    assert_eq!(format(1516239022, "100%% %Q"), "100% %Q");
+}
+
+#[test]
+fn assert_format_duration() {
+    assert_eq!(format_duration(0), "0 seconds");
+    assert_eq!(format_duration(1), "1 second");
+    assert_eq!(format_duration(59), "59 seconds");
+    assert_eq!(format_duration(3600), "1 hour");
+    assert_eq!(format_duration(3 * 86_400 + 5), "3 days");
+    assert_eq!(format_duration(800 * 86_400), "2 years");
+}
+
+#[test]
+fn assert_format_relative() {
+    assert_eq!(format_relative(100, 100), "now");
+    assert_eq!(format_relative(160, 100), "in 1 minute");
+    assert_eq!(format_relative(100, 7300), "2 hours ago");
+    assert_eq!(
+        format_relative(i64::MIN, 1516239022),
+        "292471208725 years ago"
+    );
+    assert_eq!(format_relative(i64::MAX, -1), "in 292471208677 years");
+    assert_eq!(
+        format_relative(i64::MIN, i64::MAX),
+        "584942417355 years ago"
+    );
+}
+
+#[test]
+fn assert_timeline() {
+    let claims = serde_json::json!({
+        "exp": 1516242622,
+        "nbf": 1516239022,
+        "iat": 1516239022,
+        "auth_time": "yesterday",
+        "sub": "1234567890",
+    });
+    let events = timeline(&claims);
+    let names: Vec<&str> = events.iter().map(|e| e.claim.as_str()).collect();
+    assert_eq!(names, vec!["iat", "nbf", "exp"]);
+    assert_eq!(
+        events[2].to_json(1516239022),
+        serde_json::json!({
+            "claim": "exp",
+            "timestamp": 1516242622,
+            "date": "2018-01-18T02:30:22Z",
+            "relative": "in 1 hour",
+        })
+    );
+    assert!(timeline(&serde_json::json!("not an object")).is_empty());
+}
+
+#[test]
+fn assert_parse_iso8601_millis() {
+    assert_eq!(
+        parse_iso8601_millis("2018-01-18T01:30:22Z"),
+        Some(1516239022000)
+    );
+    assert_eq!(
+        parse_iso8601_millis("2018-01-18T02:30:22.5+01:00"),
+        Some(1516239022500)
+    );
+    assert_eq!(
+        parse_iso8601_millis("2018-01-17T23:30:22.123456-0200"),
+        Some(1516239022123)
+    );
+    assert_eq!(parse_iso8601_millis("1969-12-31T23:59:59Z"), Some(-1000));
+    assert_eq!(parse_iso8601_millis("2018-01-18"), None);
+    assert_eq!(parse_iso8601_millis("2018-13-18T01:30:22Z"), None);
+    // the day must exist in the month
+    assert_eq!(
+        parse_iso8601_millis("2024-02-29T00:00:00Z"),
+        Some(1709164800000)
+    );
+    assert_eq!(
+        parse_iso8601_millis("2000-02-29T00:00:00Z"),
+        Some(951782400000)
+    );
+    assert_eq!(parse_iso8601_millis("2023-02-29T00:00:00Z"), None);
+    assert_eq!(parse_iso8601_millis("1900-02-29T00:00:00Z"), None);
+    assert_eq!(parse_iso8601_millis("2018-04-31T00:00:00Z"), None);
+    assert_eq!(
+        parse_iso8601_millis("2018-04-30T00:00:00Z"),
+        Some(1525046400000)
+    );
+    assert_eq!(parse_iso8601_millis("2018-01-32T00:00:00Z"), None);
+    assert_eq!(parse_iso8601_millis("2018-01-00T00:00:00Z"), None);
+    assert_eq!(parse_iso8601_millis("yesterday at noon"), None);
+    // untrusted input: no panic on non-ASCII fractions or huge numbers
+    assert_eq!(parse_iso8601_millis("2018-01-18T01:30:22.12é"), None);
+    assert_eq!(parse_iso8601_millis("2018-01-18T01:30:22.é"), None);
+    assert_eq!(
+        parse_iso8601_millis("9223372036854775807-01-18T01:30:22Z"),
+        None
+    );
+    assert_eq!(parse_iso8601_millis("292278995-01-01T00:00:00Z"), None);
+    assert_eq!(
+        parse_iso8601_millis("2018-01-18T01:30:22+9223372036854775807:00"),
+        None
+    );
+    assert_eq!(
+        parse_iso8601_millis("2018-01-18T01:30:22.12345678901234567890123Z"),
+        Some(1516239022123)
+    );
+}
+
+#[test]
+fn assert_parse_duration() {
+    assert_eq!(parse_duration("90"), Some(90));
+    assert_eq!(parse_duration("90s"), Some(90));
+    assert_eq!(parse_duration("15m"), Some(900));
+    assert_eq!(parse_duration("1h30m"), Some(5400));
+    assert_eq!(parse_duration("2d"), Some(172_800));
+    assert_eq!(parse_duration("1w"), Some(604_800));
+    assert_eq!(parse_duration("1h30"), None);
+    assert_eq!(parse_duration("h"), None);
+    assert_eq!(parse_duration("-5m"), None);
+    assert_eq!(parse_duration(""), None);
+}
@@ -0,0 +1,61 @@
+//! # Validation
+//!
+//! `validation` contains the types shared by the modules that check tokens
+//! against a set of rules (e.g. the key binding of an SD-JWT).
+//!
+//! Every rule produces a `Check`, so that the whole list of passed and failed
+//! rules can be shown to the user, rather than just the first failure.
+
+use std::fmt;
+
+/// Represents the outcome of a single validation rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// a short identifier of the rule (e.g. `sd_hash`)
+    pub name: String,
+    /// whether the token satisfies the rule
+    pub passed: bool,
+    /// a human readable description of the outcome
+    pub detail: String,
+}
+
+impl Check {
+    /// Creates a passed check
+    pub fn pass<N: Into<String>, D: Into<String>>(name: N, detail: D) -> Self {
+        Self {
+            name: name.into(),
+            passed: true,
+            detail: detail.into(),
+        }
+    }
+
+    /// Creates a failed check
+    pub fn fail<N: Into<String>, D: Into<String>>(name: N, detail: D) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            detail: detail.into(),
+        }
+    }
+
+    /// Returns the check as a JSON object with `name`, `passed` and `detail` fields
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "passed": self.passed,
+            "detail": self.detail,
+        })
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.passed { "PASS" } else { "FAIL" };
+        write!(f, "[{}] {}: {}", status, self.name, self.detail)
+    }
+}
+
+/// Returns `true` if all the given checks passed
+pub fn all_passed(checks: &[Check]) -> bool {
+    checks.iter().all(|c| c.passed)
+}
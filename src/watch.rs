@@ -0,0 +1,212 @@
+//! # Watch
+//!
+//! `watch` follows a token that is rotated in place, like a Kubernetes projected
+//! service account token or the OIDC token file of a CI job.
+//!
+//! A `Watcher` is given the content of the token file every time it is read and
+//! reports what happened: a first token, a rotation (with the claims that
+//! changed) or an unreadable token. `countdown` describes the time left before
+//! `exp` and `elapsed_fraction` how much of the lifetime of the token is used,
+//! to detect tokens that stopped being refreshed.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::watch;
+//!
+//! let mut watcher = watch::Watcher::new();
+//! // {"alg":"none"}.{"sub":"a","iat":1516239022,"exp":1516242622}
+//! let event = watcher.update("eyJhbGciOiJub25lIn0.eyJzdWIiOiJhIiwiaWF0IjoxNTE2MjM5MDIyLCJleHAiOjE1MTYyNDI2MjJ9.");
+//! assert!(matches!(event, Some(watch::Event::Loaded)));
+//!
+//! let claims = watcher.claims().unwrap();
+//! assert_eq!(watch::elapsed_fraction(claims, 1516240822), Some(0.5));
+//! assert_eq!(
+//!     watch::countdown(claims, 1516240822),
+//!     "expires in 00:30:00 (50% of the lifetime elapsed)"
+//! );
+//! ```
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::jwt;
+use crate::timestamp;
+
+/// The exit code of `jwtinfo watch` when the token is not refreshed in time
+/// (after the codes of `expiry`, away from the usage errors of `2`)
+pub const EXIT_STALE: i32 = 13;
+
+/// A claim that differs between two versions of a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// the name of the claim
+    pub claim: String,
+    /// the previous value (`None` for an added claim)
+    pub old: Option<Value>,
+    /// the new value (`None` for a removed claim)
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "~ {}: {} -> {}", self.claim, old, new),
+            (None, Some(new)) => write!(f, "+ {}: {}", self.claim, new),
+            (Some(old), None) => write!(f, "- {}: {}", self.claim, old),
+            (None, None) => write!(f, "  {}", self.claim),
+        }
+    }
+}
+
+/// Returns the claims that differ between two claim sets, sorted by name
+///
+/// Claims sets that are not JSON objects are compared as a whole, under the
+/// empty name.
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    let (old_claims, new_claims) = match (old.as_object(), new.as_object()) {
+        (Some(old), Some(new)) => (old, new),
+        _ if old == new => return Vec::new(),
+        _ => {
+            return vec![Change {
+                claim: String::new(),
+                old: Some(old.clone()),
+                new: Some(new.clone()),
+            }]
+        }
+    };
+    let mut names: Vec<&String> = old_claims.keys().chain(new_claims.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| old_claims.get(*name) != new_claims.get(*name))
+        .map(|name| Change {
+            claim: name.clone(),
+            old: old_claims.get(name).cloned(),
+            new: new_claims.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Something that happened to the watched token
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// the first valid token was read
+    Loaded,
+    /// the token was replaced, with the claims that changed
+    Rotated(Vec<Change>),
+    /// the content is not a valid token (the previous token, if any, is kept)
+    Invalid(String),
+}
+
+/// Follows the successive contents of a token file
+#[derive(Debug, Default)]
+pub struct Watcher {
+    #[doc(hidden)]
+    content: Option<String>,
+    #[doc(hidden)]
+    token: Option<String>,
+    #[doc(hidden)]
+    claims: Option<Value>,
+}
+
+impl Watcher {
+    /// Creates a watcher that has not read any token yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the current content of the token file, returning `None` if it
+    /// did not change since the last call
+    ///
+    /// Empty contents (e.g. a file being rewritten) are ignored.
+    pub fn update(&mut self, content: &str) -> Option<Event> {
+        let content = content.trim();
+        if content.is_empty() || self.content.as_deref() == Some(content) {
+            return None;
+        }
+        self.content = Some(content.to_string());
+        let token = match jwt::parse(content) {
+            Ok(token) => token,
+            Err(e) => return Some(Event::Invalid(e.to_string())),
+        };
+        if self.token.as_deref() == Some(content) {
+            // back to the current token after an invalid content
+            return None;
+        }
+        self.token = Some(content.to_string());
+        let event = match &self.claims {
+            None => Event::Loaded,
+            Some(claims) => Event::Rotated(diff(claims, &token.body)),
+        };
+        self.claims = Some(token.body);
+        Some(event)
+    }
+
+    /// Returns the claims of the current token
+    pub fn claims(&self) -> Option<&Value> {
+        self.claims.as_ref()
+    }
+}
+
+/// Returns the seconds of a time claim (see `timestamp::numeric_date`), or
+/// `None` if the claims don't have it as a number
+pub fn time_claim(claims: &Value, name: &str) -> Option<i64> {
+    timestamp::numeric_date(claims.get(name)?)
+}
+
+/// Returns the part of the lifetime of a token that has elapsed at `now`
+/// (`0.0` when issued, `1.0` when expired)
+///
+/// The lifetime goes from `nbf` (or `iat`) to `exp`. Returns `None` if the
+/// token has no `exp` or no start.
+pub fn elapsed_fraction(claims: &Value, now: u64) -> Option<f64> {
+    let exp = time_claim(claims, "exp")?;
+    let start = time_claim(claims, "nbf").or_else(|| time_claim(claims, "iat"))?;
+    if exp <= start {
+        return Some(1.0);
+    }
+    // in floating point: the differences of extreme claims overflow an i64
+    Some((now as f64 - start as f64) / (exp as f64 - start as f64))
+}
+
+/// Formats a number of seconds as a countdown (e.g. `00:42:10` or `2d 01:00:00`)
+pub fn format_countdown(secs: u64) -> String {
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    );
+    match secs / 86_400 {
+        0 => clock,
+        days => format!("{}d {}", days, clock),
+    }
+}
+
+/// Describes the time left before the token expires
+pub fn countdown(claims: &Value, now: u64) -> String {
+    let exp = match time_claim(claims, "exp") {
+        Some(exp) => exp,
+        None => return "never expires (no exp)".to_string(),
+    };
+    let left = exp.saturating_sub(now as i64);
+    let status = if left > 0 {
+        format!("expires in {}", format_countdown(left as u64))
+    } else {
+        format!("expired {} ago", format_countdown(left.unsigned_abs()))
+    };
+    match elapsed_fraction(claims, now) {
+        Some(fraction) if left > 0 => format!(
+            "{} ({:.0}% of the lifetime elapsed)",
+            status,
+            fraction.max(0.0) * 100.0
+        ),
+        _ => status,
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,111 @@
+#[cfg(test)]
+use super::*;
+
+#[cfg(test)]
+use base64::Engine as _;
+
+#[doc(hidden)]
+const NOW: u64 = 1516239022;
+
+/// Encodes an unsigned token with the given claims
+#[doc(hidden)]
+fn token(claims: Value) -> String {
+    format!(
+        "eyJhbGciOiJub25lIn0.{}.",
+        jwt::get_base64().encode(claims.to_string())
+    )
+}
+
+#[test]
+fn assert_watcher_reports_rotations() {
+    let mut watcher = Watcher::new();
+    assert_eq!(watcher.claims(), None);
+    let first = token(serde_json::json!({"sub": "a", "iat": NOW, "exp": NOW + 600}));
+    assert_eq!(watcher.update(&first), Some(Event::Loaded));
+    // unchanged, even with a trailing new line
+    assert_eq!(watcher.update(&format!("{}\n", first)), None);
+    assert_eq!(watcher.update(""), None);
+
+    // a file being written is reported, and the current token is kept
+    assert!(matches!(
+        watcher.update("eyJhbGciOiJub25lIn0"),
+        Some(Event::Invalid(_))
+    ));
+    assert_eq!(watcher.claims().unwrap()["sub"], "a");
+    assert_eq!(watcher.update(&first), None);
+
+    let second =
+        token(serde_json::json!({"sub": "a", "iat": NOW + 300, "exp": NOW + 900, "jti": "2"}));
+    match watcher.update(&second) {
+        Some(Event::Rotated(changes)) => {
+            let changes: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+            assert_eq!(
+                changes,
+                vec![
+                    "~ exp: 1516239622 -> 1516239922",
+                    "~ iat: 1516239022 -> 1516239322",
+                    "+ jti: \"2\"",
+                ]
+            );
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+fn assert_diff() {
+    let old = serde_json::json!({"sub": "a", "aud": ["x"]});
+    let new = serde_json::json!({"sub": "a", "aud": ["x", "y"]});
+    assert_eq!(
+        diff(&old, &new),
+        vec![Change {
+            claim: "aud".to_string(),
+            old: Some(serde_json::json!(["x"])),
+            new: Some(serde_json::json!(["x", "y"])),
+        }]
+    );
+    assert_eq!(
+        diff(&new, &serde_json::json!({}))[1].to_string(),
+        "- sub: \"a\""
+    );
+    assert!(diff(&old, &old).is_empty());
+    assert_eq!(diff(&Value::from("a"), &old).len(), 1);
+}
+
+#[test]
+fn assert_lifetime() {
+    let claims = serde_json::json!({"iat": NOW, "nbf": NOW + 100, "exp": NOW + 1100});
+    assert_eq!(elapsed_fraction(&claims, NOW + 100), Some(0.0));
+    assert_eq!(elapsed_fraction(&claims, NOW + 900), Some(0.8));
+    assert_eq!(elapsed_fraction(&claims, NOW + 1100), Some(1.0));
+    assert_eq!(
+        elapsed_fraction(&serde_json::json!({"exp": NOW}), NOW),
+        None
+    );
+
+    assert_eq!(format_countdown(59), "00:00:59");
+    assert_eq!(format_countdown(2 * 86_400 + 3661), "2d 01:01:01");
+    assert_eq!(
+        countdown(&claims, NOW + 900),
+        "expires in 00:03:20 (80% of the lifetime elapsed)"
+    );
+    assert_eq!(countdown(&claims, NOW + 1105), "expired 00:00:05 ago");
+    assert_eq!(
+        countdown(&serde_json::json!({"exp": NOW + 60}), NOW),
+        "expires in 00:01:00"
+    );
+    assert_eq!(
+        countdown(&serde_json::json!({}), NOW),
+        "never expires (no exp)"
+    );
+
+    // extreme claims do not overflow
+    let claims = serde_json::json!({"iat": i64::MIN, "exp": i64::MAX});
+    let fraction = elapsed_fraction(&claims, NOW).unwrap();
+    assert!((fraction - 0.5).abs() < 1e-6, "{}", fraction);
+    assert!(countdown(&claims, NOW).starts_with("expires in 106751991149751d 13:59:45 (50% "));
+    assert_eq!(
+        countdown(&serde_json::json!({"exp": i64::MIN}), NOW),
+        "expired 106751991167300d 15:30:08 ago"
+    );
+}
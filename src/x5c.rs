@@ -0,0 +1,508 @@
+//! # X5C
+//!
+//! `x5c` decodes the certificate chain found in the `x5c` header parameter of
+//! a JWS and validates it.
+//!
+//! Every certificate is decoded into its subject, issuer, subject alternative
+//! names, validity period and key type. The validation builds the chain from
+//! the leaf to a trusted CA (read from a PEM bundle), checks the validity of
+//! every certificate at a given time, verifies the signature of the token with
+//! the key of the leaf certificate and compares the `x5t` and `x5t#S256`
+//! thumbprints with the leaf. Every rule produces a `validation::Check`.
+//!
+//! Supported certificate signatures are RSA PKCS#1 v1.5 (SHA-256, SHA-384 and
+//! SHA-512), ECDSA (P-256 with SHA-256 and P-384 with SHA-384) and Ed25519.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use jwtinfo::x5c;
+//!
+//! let header = serde_json::json!({"alg": "RS256"});
+//! assert!(x5c::parse_chain(&header).unwrap().is_empty());
+//!
+//! let header = serde_json::json!({"alg": "RS256", "x5c": ["not a certificate"]});
+//! assert_eq!(
+//!     x5c::parse_chain(&header).unwrap_err().to_string(),
+//!     "Invalid certificate 0 in x5c: not base64"
+//! );
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use x509_parser::extensions::GeneralName;
+use x509_parser::pem::Pem;
+use x509_parser::prelude::{FromDer, X509Certificate};
+use x509_parser::public_key::PublicKey;
+
+use crate::jwk;
+use crate::jwt::get_base64;
+use crate::timestamp;
+use crate::validation::Check;
+
+/// Represents an error while decoding certificates
+#[derive(Debug)]
+pub enum X5cError {
+    /// Indicates that the `x5c` header parameter is not an array of strings
+    InvalidHeader(),
+    /// Indicates that a certificate of the chain cannot be decoded
+    InvalidCertificate(usize, String),
+    /// Indicates that the CA bundle cannot be decoded
+    InvalidCaBundle(String),
+}
+
+impl fmt::Display for X5cError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            X5cError::InvalidHeader() => "x5c must be an array of strings".to_string(),
+            X5cError::InvalidCertificate(index, e) => {
+                format!("Invalid certificate {} in x5c: {}", index, e)
+            }
+            X5cError::InvalidCaBundle(e) => format!("Invalid CA bundle: {}", e),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for X5cError {}
+
+/// Represents a decoded X.509 certificate
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    /// the distinguished name of the subject
+    pub subject: String,
+    /// the distinguished name of the issuer
+    pub issuer: String,
+    /// the serial number, as colon separated hex
+    pub serial: String,
+    /// the subject alternative names (DNS names, e-mails, URIs and IP addresses)
+    pub sans: Vec<String>,
+    /// the start of the validity period, in seconds since the Unix epoch
+    pub not_before: i64,
+    /// the end of the validity period, in seconds since the Unix epoch
+    pub not_after: i64,
+    /// a description of the key (e.g. `RSA 2048`, `EC P-256` or `Ed25519`)
+    pub key_type: String,
+    /// whether the certificate belongs to a CA (basic constraints)
+    pub is_ca: bool,
+    /// the public key as a JWK, if the key type is supported
+    pub jwk: Option<Value>,
+    /// the DER encoding of the whole certificate
+    der: Vec<u8>,
+    /// the DER encoding of the signed part of the certificate
+    tbs: Vec<u8>,
+    /// the OID of the signature algorithm
+    signature_algorithm: String,
+    /// the signature of the issuer
+    signature: Vec<u8>,
+}
+
+impl Certificate {
+    /// Decodes a DER encoded certificate
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error message of the parser if the
+    /// certificate cannot be decoded
+    pub fn from_der(der: &[u8]) -> Result<Self, String> {
+        let (_, cert) = X509Certificate::from_der(der).map_err(|e| e.to_string())?;
+        let sans = match cert.subject_alternative_name() {
+            Ok(Some(extension)) => extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(general_name)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let (key_type, jwk) = public_key(&cert);
+        Ok(Self {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            serial: cert.raw_serial_as_string(),
+            sans,
+            not_before: cert.validity().not_before.timestamp(),
+            not_after: cert.validity().not_after.timestamp(),
+            key_type,
+            is_ca: cert.is_ca(),
+            jwk,
+            der: der.to_vec(),
+            tbs: cert.tbs_certificate.as_ref().to_vec(),
+            signature_algorithm: cert.signature_algorithm.algorithm.to_id_string(),
+            signature: cert.signature_value.data.to_vec(),
+        })
+    }
+
+    /// Returns the SHA-1 thumbprint of the certificate, base64url encoded (`x5t`)
+    pub fn sha1_thumbprint(&self) -> String {
+        get_base64().encode(Sha1::digest(&self.der))
+    }
+
+    /// Returns the SHA-256 thumbprint of the certificate, base64url encoded (`x5t#S256`)
+    pub fn sha256_thumbprint(&self) -> String {
+        get_base64().encode(Sha256::digest(&self.der))
+    }
+
+    /// Returns `true` if `now` is within the validity period of the certificate
+    pub fn is_valid_at(&self, now: u64) -> bool {
+        self.not_before <= now as i64 && now as i64 <= self.not_after
+    }
+
+    /// Verifies that the certificate is signed by the key of `issuer`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a message if the signature algorithm or the
+    /// key of the issuer is not supported, or if the signature is not valid
+    pub fn verify_signed_by(&self, issuer: &Certificate) -> Result<(), String> {
+        let key = issuer
+            .jwk
+            .as_ref()
+            .ok_or_else(|| format!("unsupported issuer key ({})", issuer.key_type))?;
+        let (alg, signature) = match self.signature_algorithm.as_str() {
+            "1.2.840.113549.1.1.11" => ("RS256", self.signature.clone()),
+            "1.2.840.113549.1.1.12" => ("RS384", self.signature.clone()),
+            "1.2.840.113549.1.1.13" => ("RS512", self.signature.clone()),
+            "1.2.840.10045.4.3.2" => (
+                "ES256",
+                p256::ecdsa::Signature::from_der(&self.signature)
+                    .map_err(|e| e.to_string())?
+                    .to_vec(),
+            ),
+            "1.2.840.10045.4.3.3" => (
+                "ES384",
+                p384::ecdsa::Signature::from_der(&self.signature)
+                    .map_err(|e| e.to_string())?
+                    .to_vec(),
+            ),
+            "1.3.101.112" => ("EdDSA", self.signature.clone()),
+            other => return Err(format!("unsupported signature algorithm {}", other)),
+        };
+        jwk::verify(key, alg, &self.tbs, &signature).map_err(|e| e.to_string())
+    }
+
+    /// Returns the certificate as a JSON object
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "subject": self.subject,
+            "issuer": self.issuer,
+            "serial": self.serial,
+            "sans": self.sans,
+            "not_before": timestamp::format_iso8601(self.not_before),
+            "not_after": timestamp::format_iso8601(self.not_after),
+            "key_type": self.key_type,
+            "is_ca": self.is_ca,
+        })
+    }
+}
+
+/// Formats a subject alternative name (`None` for the types that are not shown)
+#[doc(hidden)]
+fn general_name(name: &GeneralName) -> Option<String> {
+    match name {
+        GeneralName::DNSName(dns) => Some(format!("DNS:{}", dns)),
+        GeneralName::RFC822Name(email) => Some(format!("email:{}", email)),
+        GeneralName::URI(uri) => Some(format!("URI:{}", uri)),
+        GeneralName::IPAddress(ip) => match ip.len() {
+            4 => Some(format!(
+                "IP:{}",
+                std::net::Ipv4Addr::from([ip[0], ip[1], ip[2], ip[3]])
+            )),
+            16 => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(ip);
+                Some(format!("IP:{}", std::net::Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Describes the public key of a certificate and converts it to a JWK (if supported)
+#[doc(hidden)]
+fn public_key(cert: &X509Certificate) -> (String, Option<Value>) {
+    let spki = cert.public_key();
+    let curve = spki
+        .algorithm
+        .parameters
+        .as_ref()
+        .and_then(|p| p.as_oid().ok())
+        .map(|oid| oid.to_id_string());
+    match (
+        spki.algorithm.algorithm.to_id_string().as_str(),
+        spki.parsed(),
+    ) {
+        ("1.3.101.112", _) => (
+            "Ed25519".to_string(),
+            Some(serde_json::json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": get_base64().encode(&spki.subject_public_key.data),
+            })),
+        ),
+        (_, Ok(PublicKey::RSA(rsa))) => {
+            let modulus = strip_leading_zeros(rsa.modulus);
+            (
+                format!("RSA {}", modulus.len() * 8),
+                Some(serde_json::json!({
+                    "kty": "RSA",
+                    "n": get_base64().encode(modulus),
+                    "e": get_base64().encode(strip_leading_zeros(rsa.exponent)),
+                })),
+            )
+        }
+        (_, Ok(PublicKey::EC(point))) => {
+            let crv = match curve.as_deref() {
+                Some("1.2.840.10045.3.1.7") => "P-256",
+                Some("1.3.132.0.34") => "P-384",
+                Some("1.3.132.0.35") => "P-521",
+                _ => "unknown curve",
+            };
+            let data = point.data();
+            let jwk = if (crv == "P-256" || crv == "P-384") && data.first() == Some(&0x04) {
+                let (x, y) = data[1..].split_at((data.len() - 1) / 2);
+                Some(serde_json::json!({
+                    "kty": "EC",
+                    "crv": crv,
+                    "x": get_base64().encode(x),
+                    "y": get_base64().encode(y),
+                }))
+            } else {
+                None
+            };
+            (format!("EC {}", crv), jwk)
+        }
+        (oid, _) => (format!("unsupported ({})", oid), None),
+    }
+}
+
+#[doc(hidden)]
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// Decodes the certificates of the `x5c` header parameter, leaf first
+///
+/// Returns an empty `Vec` if the header has no `x5c`.
+///
+/// # Errors
+///
+/// This function will return a `X5cError` if `x5c` is not an array of base64
+/// encoded DER certificates
+pub fn parse_chain(header: &Value) -> Result<Vec<Certificate>, X5cError> {
+    let items = match header.get("x5c") {
+        None => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err(X5cError::InvalidHeader()),
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let encoded = item.as_str().ok_or(X5cError::InvalidHeader())?;
+            // x5c uses standard base64 (with padding), not base64url
+            let der = STANDARD
+                .decode(encoded)
+                .map_err(|_| X5cError::InvalidCertificate(index, "not base64".to_string()))?;
+            Certificate::from_der(&der).map_err(|e| X5cError::InvalidCertificate(index, e))
+        })
+        .collect()
+}
+
+/// Decodes all the certificates of a PEM bundle (e.g. a CA file)
+///
+/// # Errors
+///
+/// This function will return a `X5cError` if a PEM block or a certificate
+/// cannot be decoded, or if the bundle contains no certificate
+pub fn parse_pem_bundle(pem: &str) -> Result<Vec<Certificate>, X5cError> {
+    let mut certificates = Vec::new();
+    for block in Pem::iter_from_buffer(pem.as_bytes()) {
+        let block = block.map_err(|e| X5cError::InvalidCaBundle(e.to_string()))?;
+        if block.label != "CERTIFICATE" {
+            continue;
+        }
+        certificates
+            .push(Certificate::from_der(&block.contents).map_err(X5cError::InvalidCaBundle)?);
+    }
+    if certificates.is_empty() {
+        return Err(X5cError::InvalidCaBundle(
+            "no certificate found".to_string(),
+        ));
+    }
+    Ok(certificates)
+}
+
+/// Validates the certificate chain of a JWS (e.g. a JWT)
+///
+/// `chain` is the decoded `x5c` (leaf first), `trusted` the CA certificates
+/// and `at` the time (in seconds since the Unix epoch) the certificates must be
+/// valid at. The checks cover the validity of every certificate, the links of
+/// the chain (issuer names, CA basic constraints and signatures), the trust
+/// anchor (any trusted CA named as the issuer of the last certificate, valid at
+/// `at`, with the CA basic constraint and a valid signature), the signature of the token with the leaf key and the `x5t` and
+/// `x5t#S256` thumbprints (if present in the header).
+pub fn validate(
+    token: &str,
+    header: &Value,
+    chain: &[Certificate],
+    trusted: &[Certificate],
+    at: u64,
+) -> Vec<Check> {
+    let leaf = match chain.first() {
+        Some(leaf) => leaf,
+        None => return vec![Check::fail("x5c", "x5c header is missing or empty")],
+    };
+    let mut checks = vec![Check::pass(
+        "x5c",
+        format!("{} certificate(s), leaf is {}", chain.len(), leaf.subject),
+    )];
+
+    let when = timestamp::format_iso8601(at as i64);
+    for (index, cert) in chain.iter().enumerate() {
+        let name = format!("validity[{}]", index);
+        if cert.is_valid_at(at) {
+            checks.push(Check::pass(name, format!("valid at {}", when)));
+        } else {
+            checks.push(Check::fail(
+                name,
+                format!(
+                    "not valid at {} (valid from {} to {})",
+                    when,
+                    timestamp::format_iso8601(cert.not_before),
+                    timestamp::format_iso8601(cert.not_after)
+                ),
+            ));
+        }
+    }
+
+    for (index, pair) in chain.windows(2).enumerate() {
+        checks.push(check_link(&format!("chain[{}]", index), &pair[0], &pair[1]));
+    }
+
+    let last = chain.last().unwrap_or(leaf);
+    let anchor = if trusted.iter().any(|ca| ca.der == last.der) {
+        Check::pass("trust", format!("{} is a trusted CA", last.subject))
+    } else {
+        // several CAs can share a name (e.g. a root and its rotated successor)
+        let candidates: Vec<Check> = trusted
+            .iter()
+            .filter(|ca| ca.subject == last.issuer)
+            .map(|ca| check_anchor(last, ca, at))
+            .collect();
+        match candidates.iter().position(|check| check.passed) {
+            Some(index) => candidates[index].clone(),
+            None if candidates.is_empty() => Check::fail(
+                "trust",
+                format!("no trusted CA found for issuer {}", last.issuer),
+            ),
+            None if candidates.len() == 1 => candidates[0].clone(),
+            None => Check::fail(
+                "trust",
+                format!(
+                    "none of the {} trusted CAs named {} is valid: {}",
+                    candidates.len(),
+                    last.issuer,
+                    candidates
+                        .iter()
+                        .map(|check| check.detail.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+            ),
+        }
+    };
+    checks.push(anchor);
+
+    checks.push(match &leaf.jwk {
+        Some(key) => match jwk::verify_jws(token, key) {
+            Ok(()) => Check::pass("signature", "signature is valid for the leaf key"),
+            Err(e) => Check::fail("signature", e.to_string()),
+        },
+        None => Check::fail(
+            "signature",
+            format!("unsupported leaf key ({})", leaf.key_type),
+        ),
+    });
+
+    for (name, actual) in [
+        ("x5t", leaf.sha1_thumbprint()),
+        ("x5t#S256", leaf.sha256_thumbprint()),
+    ] {
+        if let Some(expected) = header.get(name) {
+            checks.push(if expected.as_str() == Some(actual.as_str()) {
+                Check::pass(name, format!("{} matches the leaf certificate", name))
+            } else {
+                Check::fail(
+                    name,
+                    format!(
+                        "{} is {}, the leaf certificate thumbprint is {}",
+                        name, expected, actual
+                    ),
+                )
+            });
+        }
+    }
+
+    checks
+}
+
+/// Checks that `cert` is issued by the trusted CA `ca`, which must itself be
+/// valid at `at`
+#[doc(hidden)]
+fn check_anchor(cert: &Certificate, ca: &Certificate, at: u64) -> Check {
+    if !ca.is_valid_at(at) {
+        return Check::fail(
+            "trust",
+            format!(
+                "the trusted CA {} is not valid at {} (valid from {} to {})",
+                ca.subject,
+                timestamp::format_iso8601(at as i64),
+                timestamp::format_iso8601(ca.not_before),
+                timestamp::format_iso8601(ca.not_after)
+            ),
+        );
+    }
+    check_link("trust", cert, ca)
+}
+
+/// Checks that `cert` is issued by `issuer`
+#[doc(hidden)]
+fn check_link(name: &str, cert: &Certificate, issuer: &Certificate) -> Check {
+    if cert.issuer != issuer.subject {
+        return Check::fail(
+            name,
+            format!(
+                "{} is issued by {}, not by {}",
+                cert.subject, cert.issuer, issuer.subject
+            ),
+        );
+    }
+    if !issuer.is_ca {
+        return Check::fail(name, format!("{} is not a CA", issuer.subject));
+    }
+    match cert.verify_signed_by(issuer) {
+        Ok(()) => Check::pass(
+            name,
+            format!("{} is signed by {}", cert.subject, issuer.subject),
+        ),
+        Err(e) => Check::fail(
+            name,
+            format!(
+                "{} is not signed by {}: {}",
+                cert.subject, issuer.subject, e
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test;
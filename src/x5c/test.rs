@@ -0,0 +1,191 @@
+#[cfg(test)]
+use super::*;
+
+// a JWT signed (ES256) by a P-256 leaf certificate, issued by a P-384
+// intermediate CA (ecdsa-with-SHA384), issued by an RSA root CA
+// (sha256WithRSAEncryption); iat is 1900000000
+#[doc(hidden)]
+const TEST_TOKEN: &str = "eyJhbGciOiJFUzI1NiIsInR5cCI6IkpXVCIsIng1YyI6WyJNSUlDQ2pDQ0FZK2dBd0lCQWdJVUd4L0xqaWJzVVoxU0ZzOUNpR2V2Sm0ydUFaTXdDZ1lJS29aSXpqMEVBd013TVRFZE1Cc0dBMVVFQXd3VVZHVnpkQ0JKYm5SbGNtMWxaR2xoZEdVZ1EwRXhFREFPQmdOVkJBb01CMnAzZEdsdVptOHdJQmNOTWpZeE1ERTRNak0xTnpVeFdoZ1BNakV5TWpBNE1UWXlNelUzTlRGYU1DOHhHekFaQmdOVkJBTU1Fbk5wWjI1bGNpNWxlR0Z0Y0d4bExtTnZiVEVRTUE0R0ExVUVDZ3dIVUdGeWRHNWxjakJaTUJNR0J5cUdTTTQ5QWdFR0NDcUdTTTQ5QXdFSEEwSUFCRTdobytKRVpjZWxqb0NuczhXUldRMTdidmdVM1ZZdDY3eGdSZkVtd1VhVklSc09obDdwUnlNMWltc3Z0UWd4K29leGhyeS9jcFkzZktCNk5TU3ozYmVqZ1lRd2dZRXdDUVlEVlIwVEJBSXdBREEwQmdOVkhSRUVMVEFyZ2hKemFXZHVaWEl1WlhoaGJYQnNaUzVqYjIyQkQzQnJhVUJsZUdGdGNHeGxMbU52YlljRXdBQUNBVEFkQmdOVkhRNEVGZ1FVditFdVFvYzlyZVFnTkp1amtHUGJ0Z05lT2xJd0h3WURWUjBqQkJnd0ZvQVUxWkxReW9Mek9NeWNnK3A5ajVNYklDT2pTaEF3Q2dZSUtvWkl6ajBFQXdNRGFRQXdaZ0l4QUxkb0NjQUZaTTNOdlphUzdRdnhnWklnVzViQzk4ZnNLNzU5UHRudXFad0hURllFcVRBL2ZyVjFqWHBvN3NkOXFRSXhBUHcyR0JEeDlCQlFDYUJMM1Z2RW01RFpHVTNyeDdiQXE4RTIvK1BadVd0QzIrTzNUVUlucUJNaGRIYVp0QjlVeEE9PSIsIk1JSUNuekNDQVllZ0F3SUJBZ0lVUEZSNy9UUTR2YStiaDdDUDQxL2k5TEpCWnJvd0RRWUpLb1pJaHZjTkFRRUxCUUF3S1RFVk1CTUdBMVVFQXd3TVZHVnpkQ0JTYjI5MElFTkJNUkF3RGdZRFZRUUtEQWRxZDNScGJtWnZNQ0FYRFRJMk1UQXhPREl6TlRjMU1Wb1lEekl4TWpVd05URXlNak0xTnpVeFdqQXhNUjB3R3dZRFZRUUREQlJVWlhOMElFbHVkR1Z5YldWa2FXRjBaU0JEUVRFUU1BNEdBMVVFQ2d3SGFuZDBhVzVtYnpCMk1CQUdCeXFHU000OUFnRUdCU3VCQkFBaUEySUFCQkZxVm5tYnprbmEyckdNQjlrZkN6QVFkQWE5M1VlMllmblRzbWJoMmkzTHJVbS80UWM0Sk9HNjdpU0dkM0pCRlFsVENVRDI4QU5ZMDlvT3VXZTJ0TC95VlFsK0huRXNWWWxyOUg3Zit0Y1ZGTS9LaUZVd2szT0ZpNmtIYm9IRElxTmpNR0V3RHdZRFZSMFRBUUgvQkFVd0F3RUIvekFPQmdOVkhROEJBZjhFQkFNQ0FnUXdIUVlEVlIwT0JCWUVGTldTME1xQzh6ak1uSVBxZlkrVEd5QWpvMG9RTUI4R0ExVWRJd1FZTUJhQUZOOGhhTXFnZjNBL0ZZbFAxdHdNcFdxT1RzZnpNQTBHQ1NxR1NJYjNEUUVCQ3dVQUE0SUJBUUIwakUyMlNkVGIrc0ZmU1Z4RW9KNzhETTl5Mnk0T3MzTmpmbGFjbDlLV1lnSjYyVzB4c0ZqVSs0aXRmLytud1VhOWZtYWhSQnVNT3FJclkvbEtDcWUvTWJxN2tzeWNRSjhlVkhVdDlZQmZabWZnQXo4RlUvdTlaN2tzL0lFbEI5YjNWMTdLbjNZQ0J4dGI2UHMzQUg0Ym1LOXRIWUt4SzluNXMwRVNmdW04UlQrTlBZSDdWZEVkV0o1b1MxaDRLTVM4b0Fob3RGbGNpU0lOTGdvVHhnWmxDVEhPZ3hMUHc5QWVCbWVxZkVpSjFabWIxdUxMYWNkQXpRZEY4Q1N0bjVNRys1enpCY0x6T2UvU3BPUFRCaW95L3l5OFIwd2xqeUpPSjVJbzBTSE42eHpPbHFIaFZKVWQra2w5eFM3N2U4eWVTaDZZU3JERWdWYmEzMnVtajZsSiJdLCJ4NXQjUzI1NiI6InBJOW5hY3QxU2d4emRDRnB5RDVLdE5Sc0xtVndfS2Z2SURFaFdiRnR2aGciLCJ4NXQiOiJFWVh0bm1TVjBGWTB2VmRKTjgzakVvOGd2LVkifQ.eyJpc3MiOiJodHRwczovL3BhcnRuZXIuZXhhbXBsZS5jb20iLCJzdWIiOiJpbnZvaWNlLXNlcnZpY2UiLCJpYXQiOjE5MDAwMDAwMDAsImV4cCI6NDEwMjQ0NDgwMH0.FEerPJ3Wn_dzRxu00L6CvZEmeQlLXZk2DgDeqW8ggqPJnPswfzi26xYllIOaMMgJv5yN2bVoq2kfCARidFX7Rw";
+
+#[doc(hidden)]
+const ROOT_CA: &str = "-----BEGIN CERTIFICATE-----\nMIIDRTCCAi2gAwIBAgIUB+4nW4JNu35ilA3zddu/o4jsW4EwDQYJKoZIhvcNAQEL\nBQAwKTEVMBMGA1UEAwwMVGVzdCBSb290IENBMRAwDgYDVQQKDAdqd3RpbmZvMCAX\nDTI2MTAxODIzNTc1MVoYDzIxMjYwOTI0MjM1NzUxWjApMRUwEwYDVQQDDAxUZXN0\nIFJvb3QgQ0ExEDAOBgNVBAoMB2p3dGluZm8wggEiMA0GCSqGSIb3DQEBAQUAA4IB\nDwAwggEKAoIBAQDiIgCnsvf2SHHuCCXY9VdjQxNnazEu6ZcNaDmkS0zxzcc1zrLw\nMvfW0JM0biMaAK/MmFc4LImW9DsZmDbV+hEIbi9gakI0dpVW4agpegKbrpxgWPfw\nrXLbn7rv0kFJWBSZWR9Hq6jctP9JIORpJqY+buEzEcOrOtvUdjVT2IltJ16lHEiU\nHoS+cly+O34eGwK10UlhrS2iJ52vPMPCIBi+B52gem/VE4Sc6vsQ2iY5we8GsytJ\nh6w+fr0cIJpcvvCi00JYiFovyyJ62kmzey6w0swSX9QNem/Jwsqsr2BjBUFoUIIh\noDKSLgbH2qsa3extCtOkbmtot+huNkCymYeDAgMBAAGjYzBhMB0GA1UdDgQWBBTf\nIWjKoH9wPxWJT9bcDKVqjk7H8zAfBgNVHSMEGDAWgBTfIWjKoH9wPxWJT9bcDKVq\njk7H8zAPBgNVHRMBAf8EBTADAQH/MA4GA1UdDwEB/wQEAwICBDANBgkqhkiG9w0B\nAQsFAAOCAQEAIP5y9UNKR2ShjpKGos0sRDwiz9yO2zlxxQZ8uW4pGyv8pKhdDVrl\nZAz8+qTtoDGAOYW49SuQAw1s9isYTJVZA+AilHGTP1OFtXT/uI7ZDdScV1klPjWE\nI5K+PS82kJbahKHInEhrsHg3s+6/taWlkcH25fIDC3k5gdudmOI2m1BCx6Z21Bz2\npL6TZJDRxPColwPNgSHBLSONL/erl9WtRjy6s7fEqL0l9UUIjr4VKEuk308UHek0\ncaLEMXaYDAT2b65oogs0vBOmEdbmRbOtBniBwYsWHoVqEogUF6ajECuGunWjI4hb\nzDRXRWNG4s92e0F/LYS/fDtWIx1G5K+SoA==\n-----END CERTIFICATE-----\n";
+
+#[doc(hidden)]
+const IAT: u64 = 1900000000;
+
+#[doc(hidden)]
+fn header() -> Value {
+    crate::jwt::parse(TEST_TOKEN).unwrap().header
+}
+
+#[doc(hidden)]
+fn failed(checks: &[Check]) -> Vec<String> {
+    checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.to_string())
+        .collect()
+}
+
+#[test]
+fn assert_parse_chain() {
+    let chain = parse_chain(&header()).unwrap();
+    assert_eq!(chain.len(), 2);
+    let leaf = &chain[0];
+    assert_eq!(leaf.subject, "CN=signer.example.com, O=Partner");
+    assert_eq!(leaf.issuer, "CN=Test Intermediate CA, O=jwtinfo");
+    assert_eq!(
+        leaf.sans,
+        [
+            "DNS:signer.example.com",
+            "email:pki@example.com",
+            "IP:192.0.2.1"
+        ]
+    );
+    assert_eq!(leaf.key_type, "EC P-256");
+    assert!(!leaf.is_ca);
+    assert!(leaf.is_valid_at(IAT));
+    assert!(!leaf.is_valid_at(0));
+    assert_eq!(chain[1].key_type, "EC P-384");
+    assert!(chain[1].is_ca);
+    assert_eq!(leaf.to_json()["sans"][0], "DNS:signer.example.com");
+}
+
+#[test]
+fn assert_parse_chain_invalid() {
+    assert!(parse_chain(&serde_json::json!({})).unwrap().is_empty());
+    assert_eq!(
+        parse_chain(&serde_json::json!({"x5c": "abc"}))
+            .unwrap_err()
+            .to_string(),
+        "x5c must be an array of strings"
+    );
+    assert!(matches!(
+        parse_chain(&serde_json::json!({"x5c": ["AAAA"]})),
+        Err(X5cError::InvalidCertificate(0, _))
+    ));
+}
+
+#[test]
+fn assert_parse_pem_bundle() {
+    let roots = parse_pem_bundle(ROOT_CA).unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].subject, "CN=Test Root CA, O=jwtinfo");
+    assert_eq!(roots[0].key_type, "RSA 2048");
+    assert!(parse_pem_bundle("no certificates here").is_err());
+}
+
+#[test]
+fn assert_validate() {
+    let header = header();
+    let chain = parse_chain(&header).unwrap();
+    let roots = parse_pem_bundle(ROOT_CA).unwrap();
+    let checks = validate(TEST_TOKEN, &header, &chain, &roots, IAT);
+    assert_eq!(failed(&checks), Vec::<String>::new());
+    assert_eq!(
+        checks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        [
+            "x5c",
+            "validity[0]",
+            "validity[1]",
+            "chain[0]",
+            "trust",
+            "signature",
+            "x5t",
+            "x5t#S256"
+        ]
+    );
+}
+
+#[test]
+fn assert_validate_untrusted_and_expired() {
+    let header = header();
+    let chain = parse_chain(&header).unwrap();
+    // the leaf is not a CA, so it cannot anchor the chain
+    let checks = validate(TEST_TOKEN, &header, &chain, &chain[..1], 0);
+    let failures = failed(&checks);
+    assert_eq!(failures.len(), 3);
+    assert!(failures[0]
+        .starts_with("[FAIL] validity[0]: not valid at 1970-01-01T00:00:00Z (valid from 2026-"));
+    assert!(failures[1].starts_with("[FAIL] validity[1]: not valid at 1970-01-01T00:00:00Z"));
+    assert_eq!(
+        failures[2],
+        "[FAIL] trust: no trusted CA found for issuer CN=Test Root CA, O=jwtinfo"
+    );
+}
+
+#[test]
+fn assert_validate_trust_anchor() {
+    let header = header();
+    let chain = parse_chain(&header).unwrap();
+    let root = parse_pem_bundle(ROOT_CA).unwrap().remove(0);
+
+    // a CA with the same name but another key, like a rotated root
+    let mut rotated = chain[0].clone();
+    rotated.subject = root.subject.clone();
+    rotated.is_ca = true;
+    let checks = validate(TEST_TOKEN, &header, &chain, &[rotated.clone()], IAT);
+    assert!(failed(&checks)[0].starts_with(
+        "[FAIL] trust: CN=Test Intermediate CA, O=jwtinfo is not signed by CN=Test Root CA, O=jwtinfo"
+    ));
+    let checks = validate(
+        TEST_TOKEN,
+        &header,
+        &chain,
+        &[rotated.clone(), root.clone()],
+        IAT,
+    );
+    assert_eq!(failed(&checks), Vec::<String>::new());
+
+    // an expired root cannot anchor the chain
+    let mut expired = root.clone();
+    expired.not_after = IAT as i64 - 1;
+    let checks = validate(TEST_TOKEN, &header, &chain, &[expired.clone()], IAT);
+    assert_eq!(
+        failed(&checks),
+        ["[FAIL] trust: the trusted CA CN=Test Root CA, O=jwtinfo is not valid at 2030-03-17T17:46:40Z (valid from 2026-10-18T23:57:51Z to 2030-03-17T17:46:39Z)"]
+    );
+    let checks = validate(TEST_TOKEN, &header, &chain, &[expired, rotated], IAT);
+    assert!(failed(&checks)[0]
+        .starts_with("[FAIL] trust: none of the 2 trusted CAs named CN=Test Root CA, O=jwtinfo is valid: the trusted CA"));
+
+    // neither can a certificate without the CA basic constraint
+    let mut not_ca = root;
+    not_ca.is_ca = false;
+    let checks = validate(TEST_TOKEN, &header, &chain, &[not_ca], IAT);
+    assert_eq!(
+        failed(&checks),
+        ["[FAIL] trust: CN=Test Root CA, O=jwtinfo is not a CA"]
+    );
+}
+
+#[test]
+fn assert_validate_tampered() {
+    let mut header = header();
+    header["x5t"] = Value::from("AAAA");
+    let mut parts = TEST_TOKEN.split('.');
+    let tampered = format!(
+        "{}.{}.{}",
+        parts.next().unwrap(),
+        parts.next().unwrap(),
+        get_base64().encode([1u8; 64])
+    );
+    let chain = parse_chain(&header).unwrap();
+    let roots = parse_pem_bundle(ROOT_CA).unwrap();
+    let checks = validate(&tampered, &header, &chain, &roots, IAT);
+    let failures = failed(&checks);
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0], "[FAIL] signature: Invalid signature");
+    assert!(
+        failures[1].starts_with("[FAIL] x5t: x5t is \"AAAA\", the leaf certificate thumbprint is ")
+    );
+}
+
+#[test]
+fn assert_validate_without_x5c() {
+    let checks = validate(TEST_TOKEN, &serde_json::json!({}), &[], &[], IAT);
+    assert_eq!(
+        failed(&checks),
+        ["[FAIL] x5c: x5c header is missing or empty"]
+    );
+}
@@ -0,0 +1,199 @@
+#![crate_name = "jwtinfo_wasm"]
+
+//! # jwtinfo-wasm
+//!
+//! WebAssembly bindings (generated with `wasm-bindgen`) of the `jwt` module of
+//! `jwtinfo`, so that browsers and Node.js can decode and validate tokens
+//! without spawning the CLI.
+//!
+//! Every function returns plain JavaScript objects (the same JSON produced by
+//! the CLI) and throws an `Error` when the token cannot be parsed.
+//!
+//! ## Build
+//!
+//! ```bash
+//! wasm-pack build wasm --target bundler   # or --target nodejs / web
+//! wasm-pack build wasm --target bundler -- --features crypto
+//! ```
+//!
+//! The default build only decodes and validates claims, the `crypto` feature
+//! adds signature verification with a JWK.
+//!
+//! ## Usage
+//!
+//! ```js
+//! import { parse, validate } from "jwtinfo-wasm";
+//!
+//! const { header, claims } = parse(token);
+//! const { valid, checks } = validate(token, "oidc-id-token", {
+//!   issuer: "https://server.example.com",
+//!   audience: "s6BhdRkqt3",
+//!   nonce: "n-0S6_WzA2Mj",
+//! });
+//! ```
+
+use jwtinfo::{jwt, profile, provider, validation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+/// The options of `validate`: the expected values and the current time
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValidateOptions {
+    /// the expected issuer (`iss`)
+    pub issuer: Option<String>,
+    /// the expected audience (the client id, for ID tokens)
+    pub audience: Option<String>,
+    /// the `nonce` sent in the authentication request
+    pub nonce: Option<String>,
+    /// the `max_age` sent in the authentication request, in seconds
+    pub max_age: Option<u64>,
+    /// the access token issued with the ID token (checked against `at_hash`)
+    pub access_token: Option<String>,
+    /// the authorization code issued with the ID token (checked against `c_hash`)
+    pub code: Option<String>,
+    /// the current time, in seconds since the Unix epoch (defaults to `Date.now()`)
+    pub now: Option<u64>,
+}
+
+impl ValidateOptions {
+    #[doc(hidden)]
+    fn expectations(&self) -> profile::Expectations {
+        profile::Expectations {
+            issuer: self.issuer.clone(),
+            audience: self.audience.clone(),
+            nonce: self.nonce.clone(),
+            max_age: self.max_age,
+            access_token: self.access_token.clone(),
+            code: self.code.clone(),
+        }
+    }
+}
+
+/// Decodes a token into `{ header, claims }` (plus `nested`, when `max_depth` is given)
+///
+/// # Errors
+///
+/// This function will return an error message if the token cannot be parsed
+pub fn parse_token(token: &str, max_depth: Option<usize>) -> Result<Value, String> {
+    let token = token.trim();
+    let parsed = match max_depth {
+        Some(max_depth) => jwt::parse_recursive(token, max_depth),
+        None => jwt::parse(token),
+    };
+    parsed.map(|t| t.to_json()).map_err(|e| e.to_string())
+}
+
+/// Validates the claims of a token against a profile (`"auto"` picks the
+/// profile of the detected provider) and returns `{ checks, valid, provider }`
+///
+/// # Errors
+///
+/// This function will return an error message if the token cannot be parsed
+/// or the profile is unknown
+pub fn validate_token(
+    token: &str,
+    profile_name: &str,
+    options: &ValidateOptions,
+    now: u64,
+) -> Result<Value, String> {
+    let token = jwt::parse(token.trim()).map_err(|e| e.to_string())?;
+    let detection = provider::detect(&token.header, &token.body);
+    let selected: profile::Profile = if profile_name == "auto" {
+        detection
+            .as_ref()
+            .and_then(|d| d.provider.default_profile())
+            .ok_or("Cannot pick a profile: the provider of the token is not detected or has no default profile")?
+    } else {
+        profile_name
+            .parse()
+            .map_err(|e: profile::ProfileError| e.to_string())?
+    };
+    let checks = selected.check(
+        &token.header,
+        &token.body,
+        &options.expectations(),
+        options.now.unwrap_or(now),
+    );
+    Ok(serde_json::json!({
+        "profile": selected.to_string(),
+        "checks": checks.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+        "valid": validation::all_passed(&checks),
+        "provider": detection.map(|d| d.to_json()),
+    }))
+}
+
+/// Verifies the signature of a token with a public JWK
+///
+/// Returns `Ok(false)` if the signature doesn't match.
+///
+/// # Errors
+///
+/// This function will return an error message if the key or the token are not
+/// valid, or the algorithm is not supported
+#[cfg(feature = "crypto")]
+pub fn verify_token(token: &str, jwk: &Value) -> Result<bool, String> {
+    match jwtinfo::jwk::verify_jws(token.trim(), jwk) {
+        Ok(()) => Ok(true),
+        Err(jwtinfo::jwk::JwkError::InvalidSignature()) => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Converts a JSON value to a plain JavaScript value (objects rather than `Map`s)
+#[doc(hidden)]
+fn to_js(value: &Value) -> Result<JsValue, JsError> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// The current time, in seconds since the Unix epoch
+#[doc(hidden)]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Decodes a JWT into `{ header, claims }`
+#[wasm_bindgen]
+pub fn parse(token: &str) -> Result<JsValue, JsError> {
+    to_js(&parse_token(token, None).map_err(|e| JsError::new(&e))?)
+}
+
+/// Decodes a JWT and the tokens nested in it, up to `maxDepth` levels
+/// (5 by default), into `{ header, claims, nested }`
+#[wasm_bindgen(js_name = parseRecursive)]
+pub fn parse_recursive(token: &str, max_depth: Option<usize>) -> Result<JsValue, JsError> {
+    let token = parse_token(token, Some(max_depth.unwrap_or(5))).map_err(|e| JsError::new(&e))?;
+    to_js(&token)
+}
+
+/// Returns `true` if a string looks like a compact JWS or JWE
+#[wasm_bindgen(js_name = looksLikeToken)]
+pub fn looks_like_token(token: &str) -> bool {
+    jwt::looks_like_token(token.trim())
+}
+
+/// Validates the claims of a JWT against a profile (`"oidc-id-token"`,
+/// `"jwt-access-token"` or `"auto"`) and returns `{ profile, checks, valid, provider }`
+#[wasm_bindgen]
+pub fn validate(token: &str, profile: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let options: ValidateOptions = if options.is_undefined() || options.is_null() {
+        ValidateOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+    let report = validate_token(token, profile, &options, now()).map_err(|e| JsError::new(&e))?;
+    to_js(&report)
+}
+
+/// Verifies the signature of a JWT with a public JWK (an object), returning
+/// `false` if it doesn't match
+#[cfg(feature = "crypto")]
+#[wasm_bindgen]
+pub fn verify(token: &str, jwk: JsValue) -> Result<bool, JsError> {
+    let jwk: Value = serde_wasm_bindgen::from_value(jwk)?;
+    verify_token(token, &jwk).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod test;